@@ -0,0 +1,100 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    Data, DeriveInput, Fields, Index, Meta, NestedMeta,
+    Error, Result, Type,
+};
+
+
+/// The way how the field is packed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// Packed via `Pack` to both buffers (default).
+    Both,
+    /// Packed via `PackInt` to the integer buffer only.
+    Int,
+    /// Packed via `PackFloat` to the float buffer only.
+    Float,
+    /// Not packed at all.
+    Skip,
+}
+
+/// Struct field with its packing parameters.
+pub struct Field {
+    /// Field accessor (`name` or `0` for tuple structs).
+    pub member: TokenStream,
+    pub ty: Type,
+    pub kind: Kind,
+}
+
+fn parse_kind(attrs: &[syn::Attribute]) -> Result<Kind> {
+    let mut kind = Kind::Both;
+    for attr in attrs.iter().filter(|a| a.path.is_ident("pack")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(
+                meta, "expected `#[pack(skip)]`, `#[pack(int)]` or `#[pack(float)]`",
+            )),
+        };
+        for nested in list.nested.iter() {
+            let new_kind = match nested {
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => Kind::Skip,
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("int") => Kind::Int,
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("float") => Kind::Float,
+                _ => return Err(Error::new_spanned(
+                    nested, "unknown pack attribute, expected `skip`, `int` or `float`",
+                )),
+            };
+            if kind != Kind::Both {
+                return Err(Error::new_spanned(nested, "conflicting pack attributes"));
+            }
+            kind = new_kind;
+        }
+    }
+    Ok(kind)
+}
+
+/// Collects fields of the struct in declaration order.
+pub fn collect(input: &DeriveInput, derive: &str) -> Result<Vec<Field>> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => return Err(Error::new_spanned(
+            &input.ident, format!("`{}` can be derived only for structs", derive),
+        )),
+    };
+    let fields = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields.unnamed.iter().collect::<Vec<_>>(),
+        Fields::Unit => Vec::new(),
+    };
+    fields.into_iter().enumerate().map(|(i, field)| {
+        let member = match &field.ident {
+            Some(ident) => ident.to_token_stream(),
+            None => Index::from(i).to_token_stream(),
+        };
+        Ok(Field {
+            member,
+            ty: field.ty.clone(),
+            kind: parse_kind(&field.attrs)?,
+        })
+    }).collect()
+}
+
+/// Where clause of the original struct extended with bounds on field types.
+pub fn where_clause<F>(input: &DeriveInput, fields: &[Field], bound: F) -> TokenStream
+where F: Fn(Kind) -> Option<TokenStream> {
+    let mut predicates = input.generics.where_clause.as_ref()
+    .map(|wc| wc.predicates.iter().map(|p| p.to_token_stream()).collect::<Vec<_>>())
+    .unwrap_or_default();
+    for field in fields.iter() {
+        if let Some(trait_) = bound(field.kind) {
+            let ty = &field.ty;
+            predicates.push(quote!{ #ty: #trait_ });
+        }
+    }
+    if predicates.is_empty() {
+        TokenStream::new()
+    } else {
+        quote!{ where #( #predicates ),* }
+    }
+}
@@ -0,0 +1,141 @@
+//! Derive macros for [clay-core](https://docs.rs/clay-core).
+//!
+//! Macros are reexported from `clay_core::pack`, so you don't need to depend on this crate directly.
+
+extern crate proc_macro;
+
+mod fields;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Result};
+use fields::{Kind, Field};
+
+
+/// Derives `Pack` for a struct by packing its fields one after another in declaration order.
+///
+/// Field attributes:
+/// + `#[pack(skip)]` - the field isn't packed at all,
+/// + `#[pack(int)]` - the field is packed via `PackInt` into integer buffer only,
+/// + `#[pack(float)]` - the field is packed via `PackFloat` into float buffer only.
+#[proc_macro_derive(Pack, attributes(pack))]
+pub fn derive_pack(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    pack(&input)
+    .unwrap_or_else(|e| e.to_compile_error())
+    .into()
+}
+
+fn size(fields: &[Field], both: TokenStream, other: Kind) -> TokenStream {
+    let terms = fields.iter().filter_map(|f| {
+        let ty = &f.ty;
+        match f.kind {
+            Kind::Both => Some(quote!{ <#ty as ::clay_core::pack::Pack>::#both() }),
+            Kind::Int if other == Kind::Int => Some(quote!{ <#ty as ::clay_core::pack::PackInt>::size() }),
+            Kind::Float if other == Kind::Float => Some(quote!{ <#ty as ::clay_core::pack::PackFloat>::size() }),
+            _ => None,
+        }
+    });
+    quote!{ 0 #( + #terms )* }
+}
+
+fn pack(input: &DeriveInput) -> Result<TokenStream> {
+    let fields = fields::collect(input, "Pack")?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = fields::where_clause(input, &fields, |kind| match kind {
+        Kind::Both => Some(quote!{ ::clay_core::pack::Pack }),
+        Kind::Int => Some(quote!{ ::clay_core::pack::PackInt }),
+        Kind::Float => Some(quote!{ ::clay_core::pack::PackFloat }),
+        Kind::Skip => None,
+    });
+
+    let size_int = size(&fields, quote!{ size_int }, Kind::Int);
+    let size_float = size(&fields, quote!{ size_float }, Kind::Float);
+    let layout_fields = fields.iter().filter_map(|f| {
+        let ty = &f.ty;
+        let name = f.member.to_string();
+        match f.kind {
+            Kind::Both => Some(quote!{ .field::<#ty>(#name) }),
+            Kind::Int => Some(quote!{ .field_int::<#ty>(#name) }),
+            Kind::Float => Some(quote!{ .field_float::<#ty>(#name) }),
+            Kind::Skip => None,
+        }
+    });
+    let packs = fields.iter().filter_map(|f| {
+        let member = &f.member;
+        match f.kind {
+            Kind::Both => Some(quote!{ .pack(&self.#member) }),
+            Kind::Int => Some(quote!{ .pack_int(&self.#member) }),
+            Kind::Float => Some(quote!{ .pack_float(&self.#member) }),
+            Kind::Skip => None,
+        }
+    });
+
+    Ok(quote!{
+        impl #impl_generics ::clay_core::pack::Pack for #name #ty_generics #where_clause {
+            fn size_int() -> usize {
+                #size_int
+            }
+            fn size_float() -> usize {
+                #size_float
+            }
+            fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+                ::clay_core::pack::Packer::new(buffer_int, buffer_float)
+                #( #packs )*;
+            }
+            fn layout() -> ::clay_core::layout::Layout {
+                ::clay_core::layout::Layout::builder()
+                #( #layout_fields )*
+                .build()
+            }
+        }
+    })
+}
+
+/// Derives `Unpack` for a struct, the reverse of `#[derive(Pack)]`.
+///
+/// It accepts the same `#[pack(...)]` field attributes.
+/// Skipped fields are initialized with `Default::default()`.
+#[proc_macro_derive(Unpack, attributes(pack))]
+pub fn derive_unpack(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    unpack(&input)
+    .unwrap_or_else(|e| e.to_compile_error())
+    .into()
+}
+
+fn unpack(input: &DeriveInput) -> Result<TokenStream> {
+    let fields = fields::collect(input, "Unpack")?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = fields::where_clause(input, &fields, |kind| match kind {
+        Kind::Both => Some(quote!{ ::clay_core::unpack::Unpack }),
+        Kind::Int => Some(quote!{ ::clay_core::unpack::UnpackInt }),
+        Kind::Float => Some(quote!{ ::clay_core::unpack::UnpackFloat }),
+        Kind::Skip => Some(quote!{ ::std::default::Default }),
+    });
+
+    let inits = fields.iter().map(|f| {
+        let member = &f.member;
+        let value = match f.kind {
+            Kind::Both => quote!{ unpacker.unpack() },
+            Kind::Int => quote!{ unpacker.unpack_int() },
+            Kind::Float => quote!{ unpacker.unpack_float() },
+            Kind::Skip => quote!{ ::std::default::Default::default() },
+        };
+        quote!{ #member: #value }
+    });
+
+    Ok(quote!{
+        impl #impl_generics ::clay_core::unpack::Unpack for #name #ty_generics #where_clause {
+            #[allow(unused_mut, unused_variables)]
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+                let mut unpacker = ::clay_core::unpack::Unpacker::new(buffer_int, buffer_float);
+                Self { #( #inits, )* }
+            }
+        }
+    })
+}
@@ -0,0 +1,26 @@
+#pragma once
+
+#include <clay_core/matrix.h>
+#include "map.h"
+
+// fbuf: linear matrix (9), inverse matrix (9), normal matrix (9), shift vector (3)
+
+MAP_RET affine_map_rel(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf), v);
+}
+
+MAP_RET affine_map_abs(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf), v) + vload3(0, fbuf + 27);
+}
+
+MAP_RET affine_map_rel_inv(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf + 9), v);
+}
+
+MAP_RET affine_map_abs_inv(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf + 9), v - vload3(0, fbuf + 27));
+}
+
+MAP_RET affine_map_norm(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf + 18), v);
+}
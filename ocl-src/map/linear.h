@@ -0,0 +1,26 @@
+#pragma once
+
+#include <clay_core/matrix.h>
+#include "map.h"
+
+// fbuf: matrix (9), inverse matrix (9), normal matrix (9)
+
+MAP_RET linear_map_rel(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf), v);
+}
+
+MAP_RET linear_map_abs(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf), v);
+}
+
+MAP_RET linear_map_rel_inv(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf + 9), v);
+}
+
+MAP_RET linear_map_abs_inv(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf + 9), v);
+}
+
+MAP_RET linear_map_norm(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf + 18), v);
+}
@@ -0,0 +1,27 @@
+#pragma once
+
+#include <clay_core/matrix.h>
+#include "map.h"
+
+// fbuf: rotation matrix (9), inverse matrix (9)
+// The normal matrix of the rotation is the rotation matrix itself.
+
+MAP_RET rotation_map_rel(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf), v);
+}
+
+MAP_RET rotation_map_abs(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf), v);
+}
+
+MAP_RET rotation_map_rel_inv(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf + 9), v);
+}
+
+MAP_RET rotation_map_abs_inv(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf + 9), v);
+}
+
+MAP_RET rotation_map_norm(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_load(fbuf), v);
+}
@@ -0,0 +1,25 @@
+#pragma once
+
+#include "map.h"
+
+// fbuf: scale factor (3), inverse factor (3)
+
+MAP_RET scale_map_rel(MAP_ARGS_DEF) {
+    return v*vload3(0, fbuf);
+}
+
+MAP_RET scale_map_abs(MAP_ARGS_DEF) {
+    return v*vload3(0, fbuf);
+}
+
+MAP_RET scale_map_rel_inv(MAP_ARGS_DEF) {
+    return v*vload3(1, fbuf);
+}
+
+MAP_RET scale_map_abs_inv(MAP_ARGS_DEF) {
+    return v*vload3(1, fbuf);
+}
+
+MAP_RET scale_map_norm(MAP_ARGS_DEF) {
+    return v*vload3(1, fbuf);
+}
@@ -0,0 +1,25 @@
+#pragma once
+
+#include "map.h"
+
+// fbuf: offset vector (3)
+
+MAP_RET shift_map_rel(MAP_ARGS_DEF) {
+    return v;
+}
+
+MAP_RET shift_map_abs(MAP_ARGS_DEF) {
+    return v + vload3(0, fbuf);
+}
+
+MAP_RET shift_map_rel_inv(MAP_ARGS_DEF) {
+    return v;
+}
+
+MAP_RET shift_map_abs_inv(MAP_ARGS_DEF) {
+    return v - vload3(0, fbuf);
+}
+
+MAP_RET shift_map_norm(MAP_ARGS_DEF) {
+    return v;
+}
@@ -0,0 +1,30 @@
+#pragma once
+
+#include <clay_core/random.h>
+#include <clay_core/texture/texture.h>
+#include "material.h"
+
+
+// Weight of the second material is given by the texture (see `TEXTURE_WEIGHT`)
+#define BLEND_MATERIAL_FN_DEF(blend_material, first, second, texture, adi, adf, bdi, bdf, tdi, tdf) \
+    MATERIAL_BOUNCE_EXT_RET blend_material##_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) { \
+        if (random_uniform(seed) < TEXTURE_WEIGHT(texture, tdi, tdf)) { \
+            return second##_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_B(bdi, bdf)); \
+        } \
+        return first##_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_B(adi, adf)); \
+    } \
+    MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(blend_material) \
+    MATERIAL_EVAL_RET blend_material##_eval(MATERIAL_EVAL_ARGS_DEF) { \
+        return mix( \
+            first##_eval(MATERIAL_EVAL_ARGS_B(adi, adf)), \
+            second##_eval(MATERIAL_EVAL_ARGS_B(bdi, bdf)), \
+            TEXTURE_WEIGHT(texture, tdi, tdf) \
+        ); \
+    } \
+    MATERIAL_PDF_RET blend_material##_pdf(MATERIAL_PDF_ARGS_DEF) { \
+        return mix( \
+            first##_pdf(MATERIAL_PDF_ARGS_B(adi, adf)), \
+            second##_pdf(MATERIAL_PDF_ARGS_B(bdi, bdf)), \
+            TEXTURE_WEIGHT(texture, tdi, tdf) \
+        ); \
+    }
@@ -7,4 +7,14 @@
     MATERIAL_BOUNCE_RET colored_material##_bounce(MATERIAL_BOUNCE_ARGS_DEF) { \
         ray.color *= vload3(0, fbuf + mdf); \
         return material##_bounce(MATERIAL_BOUNCE_ARGS); \
+    } \
+    MATERIAL_BOUNCE_EXT_RET colored_material##_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) { \
+        ray.color *= vload3(0, fbuf + mdf); \
+        return material##_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS); \
+    } \
+    MATERIAL_EVAL_RET colored_material##_eval(MATERIAL_EVAL_ARGS_DEF) { \
+        return vload3(0, fbuf + mdf)*material##_eval(MATERIAL_EVAL_ARGS); \
+    } \
+    MATERIAL_PDF_RET colored_material##_pdf(MATERIAL_PDF_ARGS_DEF) { \
+        return material##_pdf(MATERIAL_PDF_ARGS); \
     }
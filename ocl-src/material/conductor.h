@@ -0,0 +1,65 @@
+#pragma once
+
+#include "material.h"
+#include "microfacet.h"
+
+// Fresnel reflectance of the conductor with the complex index of refraction `eta + i*k`
+float3 conductor_fresnel(float c, float3 eta, float3 k) {
+    float c2 = c*c;
+    float s2 = 1.0f - c2;
+    float3 t0 = eta*eta - k*k - s2;
+    float3 a2b2 = sqrt(t0*t0 + 4.0f*eta*eta*k*k);
+    float3 a = sqrt(fmax(0.5f*(a2b2 + t0), 0.0f));
+    float3 t1 = a2b2 + c2;
+    float3 t2 = 2.0f*c*a;
+    float3 rs = (t1 - t2)/(t1 + t2);
+    float3 t3 = c2*a2b2 + s2*s2;
+    float3 t4 = t2*s2;
+    float3 rp = rs*(t3 - t4)/(t3 + t4);
+    return 0.5f*(rs + rp);
+}
+
+// Rough metal with GGX microfacets
+// fbuf: roughness, eta (3), k (3)
+MATERIAL_BOUNCE_EXT_RET conductor_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) {
+    float alpha = microfacet_alpha(fbuf[0]);
+    float3 eta = vload3(0, fbuf + 1);
+    float3 k = vload3(0, fbuf + 4);
+    float3 n = material_facing(ray, norm);
+
+    float3 o, i, m;
+    float3 d = microfacet_reflect(seed, ray.dir, n, alpha, &o, &i, &m);
+    if (i.z <= 0.0f) {
+        return false;
+    }
+
+    *new_ray = ray;
+    new_ray->start = pos;
+    new_ray->dir = d;
+    new_ray->color *= conductor_fresnel(dot(o, m), eta, k)*microfacet_weight(o, i, alpha);
+    new_ray->history = RAY_DIFFUSE;
+    return true;
+}
+
+MATERIAL_EVAL_RET conductor_eval(MATERIAL_EVAL_ARGS_DEF) {
+    float alpha = microfacet_alpha(fbuf[0]);
+    float3 o, i, m;
+    microfacet_local(ray.dir, material_facing(ray, norm), dir, &o, &i, &m);
+    if (i.z <= 0.0f) {
+        return MATERIAL_EVAL_RET_BAD;
+    }
+    float3 f = conductor_fresnel(dot(o, m), vload3(0, fbuf + 1), vload3(0, fbuf + 4));
+    return f*microfacet_eval(o, i, m, alpha);
+}
+
+MATERIAL_PDF_RET conductor_pdf(MATERIAL_PDF_ARGS_DEF) {
+    float alpha = microfacet_alpha(fbuf[0]);
+    float3 o, i, m;
+    microfacet_local(ray.dir, material_facing(ray, norm), dir, &o, &i, &m);
+    if (i.z <= 0.0f) {
+        return 0.0f;
+    }
+    return microfacet_pdf(o, m, alpha);
+}
+
+MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(conductor)
@@ -0,0 +1,44 @@
+#pragma once
+
+#include <clay_core/random.h>
+#include <clay_core/linalg.h>
+#include "material.h"
+
+// Probability to send the ray to the target if it is offered
+#define DIFFUSE_TARGET_PROB 0.5f
+
+// Lambertian surface
+MATERIAL_BOUNCE_RET diffuse_bounce(MATERIAL_BOUNCE_ARGS_DEF) {
+    float3 n = material_facing(ray, norm);
+    *new_ray = ray;
+    new_ray->start = pos;
+    new_ray->history = RAY_DIFFUSE;
+    if (directed) {
+        if (random_uniform(seed) < DIFFUSE_TARGET_PROB) {
+            float c = dot(dir, n);
+            if (c <= 0.0f) {
+                return false;
+            }
+            new_ray->dir = dir;
+            new_ray->history |= RAY_TARGETED;
+            new_ray->color *= c*size/(M_PI_F*DIFFUSE_TARGET_PROB);
+            return true;
+        }
+        new_ray->color /= 1.0f - DIFFUSE_TARGET_PROB;
+    }
+    float3 x, y;
+    complement(n, &x, &y);
+    float3 d = random_hemisphere_cosine(seed);
+    new_ray->dir = d.x*x + d.y*y + d.z*n;
+    return true;
+}
+
+MATERIAL_BOUNCE_EXT_FROM_BOUNCE_FN_DEF(diffuse)
+
+MATERIAL_EVAL_RET diffuse_eval(MATERIAL_EVAL_ARGS_DEF) {
+    return (float3)(fmax(dot(dir, material_facing(ray, norm)), 0.0f)/M_PI_F);
+}
+
+MATERIAL_PDF_RET diffuse_pdf(MATERIAL_PDF_ARGS_DEF) {
+    return fmax(dot(dir, material_facing(ray, norm)), 0.0f)/M_PI_F;
+}
@@ -0,0 +1,53 @@
+#pragma once
+
+#include <clay_core/random.h>
+#include <clay_core/linalg.h>
+#include "material.h"
+
+// Phong exponent that gives the lobe width similar to the roughness
+float glossy_exponent(__global const float *fbuf) {
+    float roughness = fmax(fbuf[0], 1e-3f);
+    return 2.0f/(roughness*roughness) - 2.0f;
+}
+
+// Rough mirror that scatters rays in the Phong lobe around the reflected direction
+// fbuf: roughness
+MATERIAL_BOUNCE_EXT_RET glossy_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) {
+    float e = glossy_exponent(fbuf);
+    float3 n = material_facing(ray, norm);
+    float3 r = ray.dir - 2.0f*dot(ray.dir, n)*n;
+
+    float phi = 2.0f*M_PI_F*random_uniform(seed);
+    float cos_theta = pow(random_uniform(seed), 1.0f/(e + 1.0f));
+    float sin_theta = sqrt(fmax(1.0f - cos_theta*cos_theta, 0.0f));
+    float3 x, y;
+    complement(r, &x, &y);
+    float3 d = (cos(phi)*x + sin(phi)*y)*sin_theta + cos_theta*r;
+    if (dot(d, n) <= 0.0f) {
+        return false;
+    }
+
+    *new_ray = ray;
+    new_ray->start = pos;
+    new_ray->dir = d;
+    new_ray->history = RAY_DIFFUSE;
+    return true;
+}
+
+MATERIAL_PDF_RET glossy_pdf(MATERIAL_PDF_ARGS_DEF) {
+    float e = glossy_exponent(fbuf);
+    float3 n = material_facing(ray, norm);
+    float3 r = ray.dir - 2.0f*dot(ray.dir, n)*n;
+    float c = dot(dir, r);
+    if (c <= 0.0f || dot(dir, n) <= 0.0f) {
+        return 0.0f;
+    }
+    return (e + 1.0f)/(2.0f*M_PI_F)*pow(c, e);
+}
+
+// The sampled ray keeps its color, so the value equals the density
+MATERIAL_EVAL_RET glossy_eval(MATERIAL_EVAL_ARGS_DEF) {
+    return (float3)(glossy_pdf(MATERIAL_PDF_ARGS));
+}
+
+MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(glossy)
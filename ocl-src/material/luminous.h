@@ -0,0 +1,21 @@
+#pragma once
+
+#include "material.h"
+
+// Light source that emits light from both sides and doesn't reflect anything
+// fbuf: emitted light (3)
+MATERIAL_BOUNCE_EXT_RET luminous_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) {
+    *color = ray.color*vload3(0, fbuf);
+    return false;
+}
+
+// Emits light only, nothing is reflected
+MATERIAL_EVAL_RET luminous_eval(MATERIAL_EVAL_ARGS_DEF) {
+    return MATERIAL_EVAL_RET_BAD;
+}
+
+MATERIAL_PDF_RET luminous_pdf(MATERIAL_PDF_ARGS_DEF) {
+    return MATERIAL_PDF_RET_BAD;
+}
+
+MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(luminous)
@@ -1,5 +1,16 @@
 #pragma once
 
+#include <clay_core/shape/shape.h>
+#include <clay_core/texture/texture.h>
+
+// Returns `true` if the ray is bounced and writes it to `new_ray`,
+// `color` is the light emitted by the surface towards the ray, already multiplied by `ray.color`.
+// If `directed` is set, `dir` is the direction to some light source sampled with the density `1/size`,
+// and the material may send the ray there marking it with `RAY_TARGETED` (see `diffuse.h`).
+// `bounce_ext` doesn't offer the target, scenes of this crate sample light sources themselves (see `scene/trace.h`).
+// `new_ray->history` describes the last bounce: bounces sampled from some density are marked
+// with `RAY_DIFFUSE` (`eval` and `pdf` describe them), specular ones clear the flag.
+
 #define MATERIAL_BOUNCE_RET bool
 #define MATERIAL_BOUNCE_RET_BAD false
 
@@ -16,3 +27,86 @@
 
 #define MATERIAL_BOUNCE_ARGS_B(di, df) \
     seed, ray, pos, norm, directed, dir, size, ibuf + (di), fbuf + (df), new_ray, color
+
+// Extended `bounce` that gets the surface reported by the shape (see `ShapeSurface`),
+// textures are located in the shared atlas (`TEXTURE_ATLAS_ARGS`).
+// It is optional: materials that implement only `bounce` get the one defined by `MATERIAL_BOUNCE_EXT_FROM_BOUNCE_FN_DEF`.
+
+#define MATERIAL_BOUNCE_EXT_RET MATERIAL_BOUNCE_RET
+#define MATERIAL_BOUNCE_EXT_RET_BAD MATERIAL_BOUNCE_RET_BAD
+
+#define MATERIAL_BOUNCE_EXT_ARGS_DEF \
+    uint *seed, Ray ray, \
+    float3 pos, float3 norm, ShapeSurface surface, \
+    __global const int *ibuf, \
+    __global const float *fbuf, \
+    TEXTURE_ATLAS_ARGS_DEF, \
+    Ray *new_ray, float3 *color
+
+#define MATERIAL_BOUNCE_EXT_ARGS \
+    seed, ray, pos, norm, surface, ibuf, fbuf, TEXTURE_ATLAS_ARGS, new_ray, color
+
+#define MATERIAL_BOUNCE_EXT_ARGS_B(di, df) \
+    seed, ray, pos, norm, surface, ibuf + (di), fbuf + (df), TEXTURE_ATLAS_ARGS, new_ray, color
+
+// Defines `bounce_ext` of the material that implements only `bounce`
+#define MATERIAL_BOUNCE_EXT_FROM_BOUNCE_FN_DEF(material) \
+    MATERIAL_BOUNCE_EXT_RET material##_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) { \
+        return material##_bounce(seed, ray, pos, norm, false, (float3)(0.0f), 0.0f, ibuf, fbuf, new_ray, color); \
+    }
+
+// Defines `bounce` of the material that implements `bounce_ext`,
+// the surface is unknown then and textures from the atlas are white
+#define MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(material) \
+    MATERIAL_BOUNCE_RET material##_bounce(MATERIAL_BOUNCE_ARGS_DEF) { \
+        return material##_bounce_ext( \
+            seed, ray, pos, norm, shape_surface_new(), ibuf, fbuf, TEXTURE_ATLAS_ARGS_NONE, new_ray, color \
+        ); \
+    }
+
+// Returns the BSDF for the ray coming to `pos` and leaving it in the direction `dir`
+// multiplied by the cosine of the angle between `dir` and the normal (but not by `ray.color`).
+// Specular reflections and refractions are not included, so it is zero for ideal mirrors.
+
+#define MATERIAL_EVAL_RET float3
+#define MATERIAL_EVAL_RET_BAD ((float3)(0.0f))
+
+#define MATERIAL_EVAL_ARGS_DEF \
+    Ray ray, float3 pos, float3 norm, ShapeSurface surface, float3 dir, \
+    __global const int *ibuf, \
+    __global const float *fbuf, \
+    TEXTURE_ATLAS_ARGS_DEF
+
+#define MATERIAL_EVAL_ARGS \
+    ray, pos, norm, surface, dir, ibuf, fbuf, TEXTURE_ATLAS_ARGS
+
+#define MATERIAL_EVAL_ARGS_B(di, df) \
+    ray, pos, norm, surface, dir, ibuf + (di), fbuf + (df), TEXTURE_ATLAS_ARGS
+
+// Returns the density (with respect to the solid angle) with which `bounce` chooses the direction `dir`.
+// Specular bounces are not included, so the value integrates to the probability of the non-specular ones.
+
+#define MATERIAL_PDF_RET float
+#define MATERIAL_PDF_RET_BAD 0.0f
+
+#define MATERIAL_PDF_ARGS_DEF MATERIAL_EVAL_ARGS_DEF
+#define MATERIAL_PDF_ARGS MATERIAL_EVAL_ARGS
+#define MATERIAL_PDF_ARGS_B(di, df) MATERIAL_EVAL_ARGS_B(di, df)
+
+// `eval` and `pdf` are optional, materials that implement only `bounce` get these ones,
+// so their bounces are not weighted against the light source sampling.
+
+#define MATERIAL_EVAL_DEFAULT_FN_DEF(material) \
+    MATERIAL_EVAL_RET material##_eval(MATERIAL_EVAL_ARGS_DEF) { \
+        return MATERIAL_EVAL_RET_BAD; \
+    }
+
+#define MATERIAL_PDF_DEFAULT_FN_DEF(material) \
+    MATERIAL_PDF_RET material##_pdf(MATERIAL_PDF_ARGS_DEF) { \
+        return MATERIAL_PDF_RET_BAD; \
+    }
+
+// Side of the surface the ray comes from
+float3 material_facing(Ray ray, float3 norm) {
+    return dot(ray.dir, norm) > 0.0f ? -norm : norm;
+}
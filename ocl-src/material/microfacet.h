@@ -0,0 +1,91 @@
+#pragma once
+
+#include <clay_core/random.h>
+#include <clay_core/linalg.h>
+
+
+// GGX (Trowbridge-Reitz) microfacet distribution.
+// Directions are given in the local frame where `z` is the surface normal.
+
+// Minimal distribution width, smaller values give numerical issues
+#define MICROFACET_MIN_ALPHA 1e-3f
+
+// Distribution width from the perceptual roughness
+float microfacet_alpha(float roughness) {
+    return fmax(roughness*roughness, MICROFACET_MIN_ALPHA);
+}
+
+// Smith auxiliary function
+float microfacet_lambda(float3 v, float alpha) {
+    float c2 = v.z*v.z;
+    float t2 = fmax(1.0f - c2, 0.0f)/fmax(c2, 1e-12f);
+    return 0.5f*(sqrt(1.0f + alpha*alpha*t2) - 1.0f);
+}
+
+// Ratio of the height-correlated masking-shadowing term to the masking term of `o`,
+// it is the weight of the ray reflected from the visible normal sampled for `o`.
+float microfacet_weight(float3 o, float3 i, float alpha) {
+    float lo = microfacet_lambda(o, alpha);
+    float li = microfacet_lambda(i, alpha);
+    return (1.0f + lo)/(1.0f + lo + li);
+}
+
+// Distribution of microfacet normals
+float microfacet_d(float3 m, float alpha) {
+    float a2 = alpha*alpha;
+    float t = m.z*m.z*(a2 - 1.0f) + 1.0f;
+    return a2/(M_PI_F*t*t);
+}
+
+// Density of the direction `i` obtained by reflecting `o` off the visible normal,
+// `m` is the half vector between them
+float microfacet_pdf(float3 o, float3 m, float alpha) {
+    return microfacet_d(m, alpha)/(4.0f*(1.0f + microfacet_lambda(o, alpha))*o.z);
+}
+
+// Reflectance of the microfacets with the ideal Fresnel term multiplied by the cosine of `i`
+float microfacet_eval(float3 o, float3 i, float3 m, float alpha) {
+    float g = 1.0f/(1.0f + microfacet_lambda(o, alpha) + microfacet_lambda(i, alpha));
+    return microfacet_d(m, alpha)*g/(4.0f*o.z);
+}
+
+// Samples the microfacet normal visible from `v` (Heitz, 2018)
+float3 microfacet_sample(uint *seed, float3 v, float alpha) {
+    float3 vh = normalize((float3)(alpha*v.x, alpha*v.y, v.z));
+    float lsq = vh.x*vh.x + vh.y*vh.y;
+    float3 t1 = lsq > 0.0f ? (float3)(-vh.y, vh.x, 0.0f)*rsqrt(lsq) : (float3)(1.0f, 0.0f, 0.0f);
+    float3 t2 = cross(vh, t1);
+
+    float r = sqrt(random_uniform(seed));
+    float phi = 2.0f*M_PI_F*random_uniform(seed);
+    float p1 = r*cos(phi);
+    float p2 = r*sin(phi);
+    float s = 0.5f*(1.0f + vh.z);
+    p2 = (1.0f - s)*sqrt(fmax(1.0f - p1*p1, 0.0f)) + s*p2;
+
+    float3 nh = p1*t1 + p2*t2 + sqrt(fmax(1.0f - p1*p1 - p2*p2, 0.0f))*vh;
+    return normalize((float3)(alpha*nh.x, alpha*nh.y, fmax(nh.z, 0.0f)));
+}
+
+// Directions of the ray and `dir` in the local frame of the normal `n` and the half vector between them
+void microfacet_local(float3 ray_dir, float3 n, float3 dir, float3 *o, float3 *i, float3 *m) {
+    float3 x, y;
+    complement(n, &x, &y);
+    float3 v = -ray_dir;
+    *o = (float3)(dot(v, x), dot(v, y), fmax(dot(v, n), 1e-6f));
+    *i = (float3)(dot(dir, x), dot(dir, y), dot(dir, n));
+    *m = normalize(*o + *i);
+}
+
+// Reflects the ray off the rough surface.
+// Returns the reflected direction in the world frame and writes the local directions to `o` and `i`.
+// The returned direction is below the surface if the ray is shadowed.
+float3 microfacet_reflect(uint *seed, float3 ray_dir, float3 n, float alpha, float3 *o, float3 *i, float3 *m) {
+    float3 x, y;
+    complement(n, &x, &y);
+    float3 v = -ray_dir;
+    *o = (float3)(dot(v, x), dot(v, y), fmax(dot(v, n), 1e-6f));
+    *m = microfacet_sample(seed, *o, alpha);
+    *i = 2.0f*dot(*o, *m)*(*m) - *o;
+    return i->x*x + i->y*y + i->z*n;
+}
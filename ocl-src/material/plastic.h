@@ -0,0 +1,75 @@
+#pragma once
+
+#include "material.h"
+#include "microfacet.h"
+#include "diffuse.h"
+#include "refractive.h"
+
+// Reflectance of the coating for the cosine `c` of the angle of incidence
+float plastic_fresnel(float c, float ior) {
+    float eta = 1.0f/ior;
+    float k = 1.0f - eta*eta*(1.0f - c*c);
+    if (k <= 0.0f) {
+        return 1.0f;
+    }
+    return refractive_fresnel(eta, c, sqrt(k));
+}
+
+// Diffuse base under the rough dielectric coating.
+// The coating reflection is chosen with the probability of the Fresnel reflectance at the macro normal,
+// otherwise the ray passes through the coating and bounces off the base like off the diffuse surface.
+// fbuf: roughness, index of refraction, base color (3)
+MATERIAL_BOUNCE_EXT_RET plastic_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) {
+    float alpha = microfacet_alpha(fbuf[0]);
+    float ior = fbuf[1];
+    float3 base = vload3(0, fbuf + 2);
+    float3 n = material_facing(ray, norm);
+
+    float fr = plastic_fresnel(-dot(ray.dir, n), ior);
+    if (random_uniform(seed) < fr) {
+        float3 o, i, m;
+        float3 d = microfacet_reflect(seed, ray.dir, n, alpha, &o, &i, &m);
+        if (i.z <= 0.0f) {
+            return false;
+        }
+        *new_ray = ray;
+        new_ray->start = pos;
+        new_ray->dir = d;
+        new_ray->color *= plastic_fresnel(dot(o, m), ior)/fr*microfacet_weight(o, i, alpha);
+        new_ray->history = RAY_DIFFUSE;
+        return true;
+    }
+
+    if (!diffuse_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS)) {
+        return false;
+    }
+    new_ray->color *= base*(1.0f - plastic_fresnel(dot(new_ray->dir, n), ior));
+    return true;
+}
+
+MATERIAL_EVAL_RET plastic_eval(MATERIAL_EVAL_ARGS_DEF) {
+    float alpha = microfacet_alpha(fbuf[0]);
+    float ior = fbuf[1];
+    float3 base = vload3(0, fbuf + 2);
+    float3 o, i, m;
+    microfacet_local(ray.dir, material_facing(ray, norm), dir, &o, &i, &m);
+    if (i.z <= 0.0f) {
+        return MATERIAL_EVAL_RET_BAD;
+    }
+    float spec = plastic_fresnel(dot(o, m), ior)*microfacet_eval(o, i, m, alpha);
+    float3 diff = base*(1.0f - plastic_fresnel(o.z, ior))*(1.0f - plastic_fresnel(i.z, ior))*i.z/M_PI_F;
+    return spec + diff;
+}
+
+MATERIAL_PDF_RET plastic_pdf(MATERIAL_PDF_ARGS_DEF) {
+    float alpha = microfacet_alpha(fbuf[0]);
+    float3 o, i, m;
+    microfacet_local(ray.dir, material_facing(ray, norm), dir, &o, &i, &m);
+    if (i.z <= 0.0f) {
+        return 0.0f;
+    }
+    float fr = plastic_fresnel(o.z, fbuf[1]);
+    return fr*microfacet_pdf(o, m, alpha) + (1.0f - fr)*i.z/M_PI_F;
+}
+
+MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(plastic)
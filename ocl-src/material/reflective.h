@@ -0,0 +1,23 @@
+#pragma once
+
+#include "material.h"
+
+// Ideal mirror
+MATERIAL_BOUNCE_EXT_RET reflective_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) {
+    *new_ray = ray;
+    new_ray->start = pos;
+    new_ray->dir = ray.dir - 2.0f*dot(ray.dir, norm)*norm;
+    new_ray->history = ray.history & ~(RAY_DIFFUSE | RAY_TARGETED);
+    return true;
+}
+
+// Specular surfaces have no density
+MATERIAL_EVAL_RET reflective_eval(MATERIAL_EVAL_ARGS_DEF) {
+    return MATERIAL_EVAL_RET_BAD;
+}
+
+MATERIAL_PDF_RET reflective_pdf(MATERIAL_PDF_ARGS_DEF) {
+    return MATERIAL_PDF_RET_BAD;
+}
+
+MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(reflective)
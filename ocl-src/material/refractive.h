@@ -0,0 +1,52 @@
+#pragma once
+
+#include <clay_core/random.h>
+#include "material.h"
+
+// Fresnel reflectance of the dielectric interface.
+// `eta` is the ratio of indices of refraction, `ci` and `ct` are cosines of incident and transmitted angles.
+float refractive_fresnel(float eta, float ci, float ct) {
+    float rs = (eta*ci - ct)/(eta*ci + ct);
+    float rp = (ci - eta*ct)/(ci + eta*ct);
+    return 0.5f*(rs*rs + rp*rp);
+}
+
+// Smooth dielectric (e.g. glass or water)
+// fbuf: index of refraction
+MATERIAL_BOUNCE_EXT_RET refractive_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) {
+    float ior = fbuf[0];
+    float3 n = norm;
+    float ci = -dot(ray.dir, n);
+    float eta = 1.0f/ior;
+    if (ci < 0.0f) {
+        // The ray goes out of the object
+        n = -n;
+        ci = -ci;
+        eta = ior;
+    }
+    *new_ray = ray;
+    new_ray->start = pos;
+    new_ray->history = ray.history & ~(RAY_DIFFUSE | RAY_TARGETED);
+
+    float k = 1.0f - eta*eta*(1.0f - ci*ci);
+    if (k > 0.0f) {
+        float ct = sqrt(k);
+        if (random_uniform(seed) >= refractive_fresnel(eta, ci, ct)) {
+            new_ray->dir = normalize(eta*ray.dir + (eta*ci - ct)*n);
+            return true;
+        }
+    }
+    new_ray->dir = ray.dir + 2.0f*ci*n;
+    return true;
+}
+
+// Specular surfaces have no density
+MATERIAL_EVAL_RET refractive_eval(MATERIAL_EVAL_ARGS_DEF) {
+    return MATERIAL_EVAL_RET_BAD;
+}
+
+MATERIAL_PDF_RET refractive_pdf(MATERIAL_PDF_ARGS_DEF) {
+    return MATERIAL_PDF_RET_BAD;
+}
+
+MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(refractive)
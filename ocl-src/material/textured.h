@@ -0,0 +1,19 @@
+#pragma once
+
+#include <clay_core/texture/texture.h>
+#include "material.h"
+
+
+#define TEXTURED_MATERIAL_FN_DEF(textured_material, material, texture, mdi, mdf, tdi, tdf) \
+    MATERIAL_BOUNCE_EXT_RET textured_material##_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_DEF) { \
+        ray.color *= texture##_lookup(TEXTURE_LOOKUP_ARGS_B(tdi, tdf)); \
+        return material##_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_B(mdi, mdf)); \
+    } \
+    MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(textured_material) \
+    MATERIAL_EVAL_RET textured_material##_eval(MATERIAL_EVAL_ARGS_DEF) { \
+        return texture##_lookup(TEXTURE_LOOKUP_ARGS_B(tdi, tdf))* \
+            material##_eval(MATERIAL_EVAL_ARGS_B(mdi, mdf)); \
+    } \
+    MATERIAL_PDF_RET textured_material##_pdf(MATERIAL_PDF_ARGS_DEF) { \
+        return material##_pdf(MATERIAL_PDF_ARGS_B(mdi, mdf)); \
+    }
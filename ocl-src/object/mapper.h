@@ -8,4 +8,13 @@
     MAP_SHAPE_FN_DEF(map_object, object, map, sdi, sdf) \
     OBJECT_BOUNCE_RET map_object##_bounce(OBJECT_BOUNCE_ARGS_DEF) { \
         return object##_bounce(OBJECT_BOUNCE_ARGS); \
+    } \
+    OBJECT_BOUNCE_EXT_RET map_object##_bounce_ext(OBJECT_BOUNCE_EXT_ARGS_DEF) { \
+        return object##_bounce_ext(OBJECT_BOUNCE_EXT_ARGS); \
+    } \
+    OBJECT_EVAL_RET map_object##_eval(OBJECT_EVAL_ARGS_DEF) { \
+        return object##_eval(OBJECT_EVAL_ARGS); \
+    } \
+    OBJECT_PDF_RET map_object##_pdf(OBJECT_PDF_ARGS_DEF) { \
+        return object##_pdf(OBJECT_PDF_ARGS); \
     }
@@ -3,15 +3,40 @@
 #include <clay_core/shape/shape.h>
 #include <clay_core/material/material.h>
 
-#define OBJECT_HIT_RET               SHAPE_HIT_RET
-#define OBJECT_HIT_RET_BAD           SHAPE_HIT_RET_BAD
-#define OBJECT_HIT_ARGS_DEF          SHAPE_HIT_ARGS_DEF
-#define OBJECT_HIT_ARGS              SHAPE_HIT_ARGS
-#define OBJECT_HIT_ARGS_B(di, df)    SHAPE_HIT_ARGS_B(di, df)
-#define OBJECT_HIT_ARGS_R(r)         SHAPE_HIT_ARGS_R(r)
+#define OBJECT_HIT_RET                   SHAPE_HIT_RET
+#define OBJECT_HIT_RET_BAD               SHAPE_HIT_RET_BAD
+#define OBJECT_HIT_ARGS_DEF              SHAPE_HIT_ARGS_DEF
+#define OBJECT_HIT_ARGS                  SHAPE_HIT_ARGS
+#define OBJECT_HIT_ARGS_B(di, df)        SHAPE_HIT_ARGS_B(di, df)
+#define OBJECT_HIT_ARGS_R(r)             SHAPE_HIT_ARGS_R(r)
 
-#define OBJECT_BOUNCE_RET            MATERIAL_BOUNCE_RET
-#define OBJECT_BOUNCE_RET_BAD        MATERIAL_BOUNCE_RET_BAD
-#define OBJECT_BOUNCE_ARGS_DEF       MATERIAL_BOUNCE_ARGS_DEF
-#define OBJECT_BOUNCE_ARGS           MATERIAL_BOUNCE_ARGS
-#define OBJECT_BOUNCE_ARGS_B(di, df) MATERIAL_BOUNCE_ARGS_B(di, df)
+#define OBJECT_HIT_EXT_RET               SHAPE_HIT_EXT_RET
+#define OBJECT_HIT_EXT_RET_BAD           SHAPE_HIT_EXT_RET_BAD
+#define OBJECT_HIT_EXT_ARGS_DEF          SHAPE_HIT_EXT_ARGS_DEF
+#define OBJECT_HIT_EXT_ARGS              SHAPE_HIT_EXT_ARGS
+#define OBJECT_HIT_EXT_ARGS_B(di, df)    SHAPE_HIT_EXT_ARGS_B(di, df)
+#define OBJECT_HIT_EXT_ARGS_R(r)         SHAPE_HIT_EXT_ARGS_R(r)
+
+#define OBJECT_BOUNCE_RET                MATERIAL_BOUNCE_RET
+#define OBJECT_BOUNCE_RET_BAD            MATERIAL_BOUNCE_RET_BAD
+#define OBJECT_BOUNCE_ARGS_DEF           MATERIAL_BOUNCE_ARGS_DEF
+#define OBJECT_BOUNCE_ARGS               MATERIAL_BOUNCE_ARGS
+#define OBJECT_BOUNCE_ARGS_B(di, df)     MATERIAL_BOUNCE_ARGS_B(di, df)
+
+#define OBJECT_BOUNCE_EXT_RET            MATERIAL_BOUNCE_EXT_RET
+#define OBJECT_BOUNCE_EXT_RET_BAD        MATERIAL_BOUNCE_EXT_RET_BAD
+#define OBJECT_BOUNCE_EXT_ARGS_DEF       MATERIAL_BOUNCE_EXT_ARGS_DEF
+#define OBJECT_BOUNCE_EXT_ARGS           MATERIAL_BOUNCE_EXT_ARGS
+#define OBJECT_BOUNCE_EXT_ARGS_B(di, df) MATERIAL_BOUNCE_EXT_ARGS_B(di, df)
+
+#define OBJECT_EVAL_RET                  MATERIAL_EVAL_RET
+#define OBJECT_EVAL_RET_BAD              MATERIAL_EVAL_RET_BAD
+#define OBJECT_EVAL_ARGS_DEF             MATERIAL_EVAL_ARGS_DEF
+#define OBJECT_EVAL_ARGS                 MATERIAL_EVAL_ARGS
+#define OBJECT_EVAL_ARGS_B(di, df)       MATERIAL_EVAL_ARGS_B(di, df)
+
+#define OBJECT_PDF_RET                   MATERIAL_PDF_RET
+#define OBJECT_PDF_RET_BAD               MATERIAL_PDF_RET_BAD
+#define OBJECT_PDF_ARGS_DEF              MATERIAL_PDF_ARGS_DEF
+#define OBJECT_PDF_ARGS                  MATERIAL_PDF_ARGS
+#define OBJECT_PDF_ARGS_B(di, df)        MATERIAL_PDF_ARGS_B(di, df)
@@ -0,0 +1,13 @@
+#pragma once
+
+#include <clay_core/ray.h>
+
+#define BACKGROUND_ARGS_DEF \
+    float3 background_color
+
+#define BACKGROUND_ARGS \
+    background_color
+
+float3 __background(Ray ray, BACKGROUND_ARGS_DEF) {
+    return background_color;
+}
@@ -0,0 +1,121 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_core/random.h>
+
+// Equirectangular environment map with `z` axis pointing up.
+// The top row of the image is the zenith, the image center looks along `x` axis.
+// `env_cdf` contains the marginal CDF of rows (`height` floats)
+// followed by conditional CDFs of pixels in each row (`width` floats per row).
+
+#define BACKGROUND_ARGS_DEF \
+    __global const float *env_map, \
+    __global const float *env_cdf, \
+    int2 env_size, \
+    float env_scale
+
+#define BACKGROUND_ARGS \
+    env_map, env_cdf, env_size, env_scale
+
+// Image coordinates of the direction in pixels
+float2 environment_coords(float3 dir, int2 size) {
+    float phi = atan2(dir.y, dir.x);
+    float theta = acos(clamp(dir.z, -1.0f, 1.0f));
+    return (float2)(
+        (0.5f + 0.5f*phi/M_PI_F)*size.x,
+        theta/M_PI_F*size.y
+    );
+}
+
+float3 environment_texel(__global const float *map, int2 size, int x, int y) {
+    x = ((x % size.x) + size.x) % size.x;
+    y = clamp(y, 0, size.y - 1);
+    return vload3(x + y*size.x, map);
+}
+
+// Bilinear interpolation of the map
+float3 environment_lookup(__global const float *map, int2 size, float3 dir) {
+    float2 p = environment_coords(dir, size) - 0.5f;
+    float2 f = floor(p);
+    float2 t = p - f;
+    int x = (int)f.x, y = (int)f.y;
+    return mix(
+        mix(environment_texel(map, size, x, y), environment_texel(map, size, x + 1, y), t.x),
+        mix(environment_texel(map, size, x, y + 1), environment_texel(map, size, x + 1, y + 1), t.x),
+        t.y
+    );
+}
+
+float3 __background(Ray ray, BACKGROUND_ARGS_DEF) {
+    return env_scale*environment_lookup(env_map, env_size, ray.dir);
+}
+
+// Index of the first element of the non-decreasing array that is greater than `u`
+int environment_search(__global const float *cdf, int n, float u) {
+    int lo = 0, hi = n - 1;
+    while (lo < hi) {
+        int mid = (lo + hi)/2;
+        if (cdf[mid] > u) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    return lo;
+}
+
+// The background could be sampled as a light source
+#define BACKGROUND_SAMPLE
+
+// Samples the direction with the probability proportional to the luminance of the map.
+// Returns the density of the direction with respect to the solid angle (zero if the map is black).
+float __background_sample(uint *seed, float3 pos, BACKGROUND_ARGS_DEF, float3 *dir) {
+    int w = env_size.x, h = env_size.y;
+    if (env_cdf[h - 1] <= 0.0f) {
+        return 0.0f;
+    }
+
+    float u = random_uniform(seed);
+    int y = environment_search(env_cdf, h, u);
+    float y0 = y > 0 ? env_cdf[y - 1] : 0.0f;
+    float py = env_cdf[y] - y0;
+
+    __global const float *row = env_cdf + h + y*w;
+    float v = random_uniform(seed);
+    int x = environment_search(row, w, v);
+    float x0 = x > 0 ? row[x - 1] : 0.0f;
+    float px = row[x] - x0;
+    if (px <= 0.0f || py <= 0.0f) {
+        return 0.0f;
+    }
+
+    // Reuse the remainders of random numbers for the position inside the pixel
+    float phi = (2.0f*(x + (v - x0)/px)/w - 1.0f)*M_PI_F;
+    float theta = (y + (u - y0)/py)/h*M_PI_F;
+    float sin_theta = sin(theta);
+    *dir = (float3)(cos(phi)*sin_theta, sin(phi)*sin_theta, cos(theta));
+    if (sin_theta <= 0.0f) {
+        return 0.0f;
+    }
+    return px*py*w*h/(2.0f*M_PI_F*M_PI_F*sin_theta);
+}
+
+// Density of `__background_sample` for the direction
+float __background_pdf(float3 pos, float3 dir, BACKGROUND_ARGS_DEF) {
+    int w = env_size.x, h = env_size.y;
+    if (env_cdf[h - 1] <= 0.0f) {
+        return 0.0f;
+    }
+    dir = normalize(dir);
+    float sin_theta = sqrt(fmax(1.0f - dir.z*dir.z, 0.0f));
+    if (sin_theta <= 0.0f) {
+        return 0.0f;
+    }
+    float2 p = environment_coords(dir, env_size);
+    int x = clamp((int)p.x, 0, w - 1);
+    int y = clamp((int)p.y, 0, h - 1);
+    float py = env_cdf[y] - (y > 0 ? env_cdf[y - 1] : 0.0f);
+    __global const float *row = env_cdf + h + y*w;
+    float px = row[x] - (x > 0 ? row[x - 1] : 0.0f);
+    return px*py*w*h/(2.0f*M_PI_F*M_PI_F*sin_theta);
+}
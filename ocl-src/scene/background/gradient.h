@@ -0,0 +1,23 @@
+#pragma once
+
+#include <clay_core/ray.h>
+
+// Sky color changes from horizon to zenith with the `z` component of the direction,
+// everything below the horizon has the ground color.
+
+#define BACKGROUND_ARGS_DEF \
+    float3 background_zenith, \
+    float3 background_horizon, \
+    float3 background_ground
+
+#define BACKGROUND_ARGS \
+    background_zenith, background_horizon, background_ground
+
+float3 __background(Ray ray, BACKGROUND_ARGS_DEF) {
+    float z = ray.dir.z;
+    if (z < 0.0f) {
+        return background_ground;
+    }
+    // Square root makes the horizon band thinner
+    return mix(background_horizon, background_zenith, sqrt(z));
+}
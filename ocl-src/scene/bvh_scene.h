@@ -0,0 +1,117 @@
+#pragma once
+
+#include <clay_core/object/object.h>
+
+// bvh_ibuf: nodes count, unbounded objects count,
+//           nodes `(first, count)` for leaves and `(right child, 0)` for inner ones (left child is the next node),
+//           object indices in leaf order, unbounded object indices
+// bvh_fbuf: node bounds (min and max corners)
+
+#define SCENE_BVH_ARGS_DEF \
+    __global const int *bvh_ibuf, \
+    __global const float *bvh_fbuf
+
+#define SCENE_BVH_ARGS \
+    bvh_ibuf, bvh_fbuf
+
+#define SCENE_ARGS_DEF \
+    SCENE_OBJECTS_ARGS_DEF, \
+    SCENE_BVH_ARGS_DEF, \
+    SCENE_TARGETS_ARGS_DEF, \
+    SHAPE_MESH_ARGS_DEF, \
+    TEXTURE_ATLAS_ARGS_DEF, \
+    SCENE_TRACE_ARGS_DEF, \
+    BACKGROUND_ARGS_DEF
+
+#define SCENE_ARGS \
+    SCENE_OBJECTS_ARGS, \
+    SCENE_BVH_ARGS, \
+    SCENE_TARGETS_ARGS, \
+    SHAPE_MESH_ARGS, \
+    TEXTURE_ATLAS_ARGS, \
+    SCENE_TRACE_ARGS, \
+    BACKGROUND_ARGS
+
+// The host limits the hierarchy depth so that the traversal never overflows the stack
+#define BVH_SCENE_STACK_SIZE 64
+
+// Returns the nearest distance where the ray enters the box or `INFINITY` if it misses
+float _bvh_scene_box(float3 start, float3 inv_dir, __global const float *bounds) {
+    float3 ta = (vload3(0, bounds) - start)*inv_dir;
+    float3 tb = (vload3(1, bounds) - start)*inv_dir;
+    float3 tn = fmin(ta, tb), tf = fmax(ta, tb);
+    float t0 = fmax(fmax(tn.x, tn.y), tn.z);
+    float t1 = fmin(fmin(tf.x, tf.y), tf.z);
+    return (t0 <= t1 && t1 > 0.0f) ? t0 : INFINITY;
+}
+
+// Tests the object and updates the nearest hit
+void _bvh_scene_test(
+    uint *seed, Ray ray, int i,
+    SCENE_ARGS_DEF,
+    int *idx, float *best, float3 *norm, ShapeSurface *surface
+) {
+    float enter, exit;
+    float3 n;
+    ShapeSurface c = shape_surface_new();
+    if (__object_hit_ext(
+        seed, ray,
+        object_ibuf + i*SCENE_OBJECT_SIZE_INT,
+        object_fbuf + i*SCENE_OBJECT_SIZE_FLOAT,
+        SHAPE_MESH_ARGS,
+        &enter, &exit, &n, &c
+    )) {
+        float t = enter > 0.0f ? enter : exit;
+        if (t < *best) {
+            *best = t;
+            *idx = i;
+            *norm = n;
+            *surface = c;
+        }
+    }
+}
+
+// Traverses the hierarchy and returns the index of the nearest object hit by the ray or -1
+int bvh_scene_hit(uint *seed, Ray ray, SCENE_ARGS_DEF, float *dist, float3 *norm, ShapeSurface *surface) {
+    int node_count = bvh_ibuf[0];
+    int unbounded_count = bvh_ibuf[1];
+    __global const int *nodes = bvh_ibuf + 2;
+    __global const int *order = nodes + 2*node_count;
+    __global const int *unbounded = order + (object_count - unbounded_count);
+
+    int idx = -1;
+    float best = INFINITY;
+
+    for (int i = 0; i < unbounded_count; ++i) {
+        _bvh_scene_test(seed, ray, unbounded[i], SCENE_ARGS, &idx, &best, norm, surface);
+    }
+
+    if (node_count > 0) {
+        float3 inv_dir = 1.0f/ray.dir;
+        int stack[BVH_SCENE_STACK_SIZE];
+        int sp = 0;
+        stack[sp++] = 0;
+        while (sp > 0) {
+            int n = stack[--sp];
+            if (_bvh_scene_box(ray.start, inv_dir, bvh_fbuf + 6*n) >= best) {
+                continue;
+            }
+            int a = nodes[2*n], b = nodes[2*n + 1];
+            if (b > 0) {
+                for (int i = a; i < a + b; ++i) {
+                    _bvh_scene_test(seed, ray, order[i], SCENE_ARGS, &idx, &best, norm, surface);
+                }
+            } else {
+                stack[sp++] = a;
+                stack[sp++] = n + 1;
+            }
+        }
+    }
+
+    *dist = best;
+    return idx;
+}
+
+#define __scene_hit bvh_scene_hit
+
+#include "trace.h"
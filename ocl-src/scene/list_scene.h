@@ -0,0 +1,51 @@
+#pragma once
+
+#include <clay_core/object/object.h>
+
+#define SCENE_ARGS_DEF \
+    SCENE_OBJECTS_ARGS_DEF, \
+    SCENE_TARGETS_ARGS_DEF, \
+    SHAPE_MESH_ARGS_DEF, \
+    TEXTURE_ATLAS_ARGS_DEF, \
+    SCENE_TRACE_ARGS_DEF, \
+    BACKGROUND_ARGS_DEF
+
+#define SCENE_ARGS \
+    SCENE_OBJECTS_ARGS, \
+    SCENE_TARGETS_ARGS, \
+    SHAPE_MESH_ARGS, \
+    TEXTURE_ATLAS_ARGS, \
+    SCENE_TRACE_ARGS, \
+    BACKGROUND_ARGS
+
+// Tests every object and returns the index of the nearest one hit by the ray or -1
+int list_scene_hit(uint *seed, Ray ray, SCENE_ARGS_DEF, float *dist, float3 *norm, ShapeSurface *surface) {
+    int idx = -1;
+    float best = INFINITY;
+    for (int i = 0; i < object_count; ++i) {
+        float enter, exit;
+        float3 n;
+        ShapeSurface c = shape_surface_new();
+        if (__object_hit_ext(
+            seed, ray,
+            object_ibuf + i*SCENE_OBJECT_SIZE_INT,
+            object_fbuf + i*SCENE_OBJECT_SIZE_FLOAT,
+            SHAPE_MESH_ARGS,
+            &enter, &exit, &n, &c
+        )) {
+            float t = enter > 0.0f ? enter : exit;
+            if (t < best) {
+                best = t;
+                idx = i;
+                *norm = n;
+                *surface = c;
+            }
+        }
+    }
+    *dist = best;
+    return idx;
+}
+
+#define __scene_hit list_scene_hit
+
+#include "trace.h"
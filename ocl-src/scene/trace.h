@@ -0,0 +1,237 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_core/random.h>
+#include <clay_core/object/object.h>
+#include <clay_core/shape/target.h>
+
+// Common part of the scenes that store objects and targets in instance buffers.
+//
+// The scene header should define `SCENE_ARGS_DEF` and `SCENE_ARGS`
+// (containing at least the arguments below and `BACKGROUND_ARGS_DEF`)
+// and the function `__scene_hit` that finds the nearest object (along with its normal and surface)
+// before including this header.
+// `SCENE_OBJECT_SIZE_*` and `SCENE_TARGET_SIZE_*` are generated on the host.
+
+// Distance the ray start is moved along the ray after the bounce to avoid self-intersection
+#define SCENE_EPS 1e-4f
+
+// Probability to sample the background instead of targets if both are available
+#define SCENE_BACKGROUND_PROB 0.5f
+
+#define SCENE_OBJECTS_ARGS_DEF \
+    __global const int *object_ibuf, \
+    __global const float *object_fbuf, \
+    int object_count
+
+#define SCENE_OBJECTS_ARGS \
+    object_ibuf, object_fbuf, object_count
+
+// `target_cdf` contains cumulative probabilities of choosing each target,
+// `target_objects` contains indices of objects that targets belong to
+#define SCENE_TARGETS_ARGS_DEF \
+    __global const int *target_ibuf, \
+    __global const float *target_fbuf, \
+    int target_count, \
+    __global const float *target_cdf, \
+    __global const int *target_objects
+
+#define SCENE_TARGETS_ARGS \
+    target_ibuf, target_fbuf, target_count, target_cdf, target_objects
+
+// Maximum number of bounces and the depth from which diffuse rays are terminated randomly
+#define SCENE_TRACE_ARGS_DEF \
+    int max_depth, int roulette_depth
+
+#define SCENE_TRACE_ARGS \
+    max_depth, roulette_depth
+
+// Chooses the target with probability proportional to its brightness and samples the direction to it.
+// If the background could be sampled (defines `BACKGROUND_SAMPLE`) it is chosen with the probability
+// `SCENE_BACKGROUND_PROB` (or always if there are no targets), then the target index is -1.
+bool scene_target_sample(
+    uint *seed, float3 pos,
+    SCENE_TARGETS_ARGS_DEF,
+    BACKGROUND_ARGS_DEF,
+    int *target, float3 *dir
+) {
+#ifdef BACKGROUND_SAMPLE
+    float bg_prob = target_count > 0 ? SCENE_BACKGROUND_PROB : 1.0f;
+    if (random_uniform(seed) < bg_prob) {
+        *target = -1;
+        return __background_sample(seed, pos, BACKGROUND_ARGS, dir) > 0.0f;
+    }
+#endif
+    if (target_count <= 0) {
+        return false;
+    }
+    float alpha = random_uniform(seed);
+    int i = 0;
+    for (; i < target_count - 1; ++i) {
+        if (alpha < target_cdf[i]) {
+            break;
+        }
+    }
+    float angle = __target_sample(
+        seed, pos,
+        target_ibuf + i*SCENE_TARGET_SIZE_INT,
+        target_fbuf + i*SCENE_TARGET_SIZE_FLOAT,
+        dir
+    );
+    *target = i;
+    return angle > 0.0f;
+}
+
+// Density with which `scene_target_sample` produces the direction
+// that reaches the object `idx` (or the background if `idx` is negative).
+// Targets that belong to other objects don't count, because their samples are occluded by this object.
+float scene_target_pdf(float3 pos, float3 dir, int idx, SCENE_TARGETS_ARGS_DEF, BACKGROUND_ARGS_DEF) {
+#ifdef BACKGROUND_SAMPLE
+    float bg_prob = target_count > 0 ? SCENE_BACKGROUND_PROB : 1.0f;
+    if (idx < 0) {
+        return bg_prob*__background_pdf(pos, dir, BACKGROUND_ARGS);
+    }
+    float tg_prob = 1.0f - bg_prob;
+#else
+    if (idx < 0) {
+        return 0.0f;
+    }
+    float tg_prob = 1.0f;
+#endif
+    float pdf = 0.0f;
+    for (int i = 0; i < target_count; ++i) {
+        if (target_objects[i] == idx) {
+            float prob = target_cdf[i] - (i > 0 ? target_cdf[i - 1] : 0.0f);
+            pdf += prob*__target_pdf(
+                pos, dir,
+                target_ibuf + i*SCENE_TARGET_SIZE_INT,
+                target_fbuf + i*SCENE_TARGET_SIZE_FLOAT
+            );
+        }
+    }
+    return tg_prob*pdf;
+}
+
+// Power heuristic weight of the strategy with density `a` against the one with density `b`
+float scene_mis_weight(float a, float b) {
+    if (b <= 0.0f) {
+        return 1.0f;
+    }
+    a *= a;
+    b *= b;
+    return a/(a + b);
+}
+
+// Samples the light source from the point of the object `idx`
+// and returns the light reflected along the ray weighted by the power heuristic.
+float3 scene_light(uint *seed, Ray ray, float3 pos, float3 norm, ShapeSurface surface, int idx, SCENE_ARGS_DEF) {
+    int target = -1;
+    float3 dir = (float3)(0.0f);
+    if (!scene_target_sample(seed, pos, SCENE_TARGETS_ARGS, BACKGROUND_ARGS, &target, &dir)) {
+        return (float3)(0.0f);
+    }
+    __global const int *ibuf = object_ibuf + idx*SCENE_OBJECT_SIZE_INT;
+    __global const float *fbuf = object_fbuf + idx*SCENE_OBJECT_SIZE_FLOAT;
+    float3 f = __object_eval(ray, pos, norm, surface, dir, ibuf, fbuf, TEXTURE_ATLAS_ARGS);
+    if (fmax(f.x, fmax(f.y, f.z)) <= 0.0f) {
+        return (float3)(0.0f);
+    }
+
+    Ray light_ray = ray;
+    light_ray.start = pos + SCENE_EPS*dir;
+    light_ray.dir = dir;
+    light_ray.history = RAY_INITIAL;
+    light_ray.origin = idx;
+    light_ray.target = target;
+    float dist;
+    float3 light_norm;
+    ShapeSurface light_surface = shape_surface_new();
+    int light = __scene_hit(seed, light_ray, SCENE_ARGS, &dist, &light_norm, &light_surface);
+    if (light != (target < 0 ? -1 : target_objects[target])) {
+        return (float3)(0.0f);
+    }
+    float pdf = scene_target_pdf(pos, dir, light, SCENE_TARGETS_ARGS, BACKGROUND_ARGS);
+    if (pdf <= 0.0f) {
+        return (float3)(0.0f);
+    }
+    float weight = scene_mis_weight(pdf, __object_pdf(ray, pos, norm, surface, dir, ibuf, fbuf, TEXTURE_ATLAS_ARGS));
+    light_ray.color = ray.color*f*(weight/pdf);
+    if (light < 0) {
+        return light_ray.color*__background(light_ray, BACKGROUND_ARGS);
+    }
+
+    // The light emitted towards the ray is obtained from the bounce, the bounced ray itself is dropped
+    Ray new_ray = light_ray;
+    float3 glow = (float3)(0.0f);
+    __object_bounce_ext(
+        seed, light_ray, light_ray.start + dist*dir, light_norm, light_surface,
+        object_ibuf + light*SCENE_OBJECT_SIZE_INT,
+        object_fbuf + light*SCENE_OBJECT_SIZE_FLOAT,
+        TEXTURE_ATLAS_ARGS, &new_ray, &glow
+    );
+    return glow;
+}
+
+// Path tracing with multiple importance sampling.
+// At each non-specular bounce the light source is sampled (`scene_light`),
+// and the light found by the bounced ray is weighted against that sampling.
+float3 __scene_trace(uint *seed, Ray ray, SCENE_ARGS_DEF) {
+    float3 color = (float3)(0.0f);
+    // Point of the last bounce and the material density of the ray direction there
+    float3 prev_pos = ray.start;
+    float prev_pdf = 0.0f;
+    for (int depth = 0; depth < max_depth; ++depth) {
+        // Materials without `pdf` are not weighted, `scene_light` gets nothing from them either.
+        // Rays sent to the target by the material itself (`RAY_TARGETED`) are not described by its `pdf`.
+        bool weighted = (ray.history & (RAY_DIFFUSE | RAY_TARGETED)) == RAY_DIFFUSE && prev_pdf > 0.0f;
+
+        float dist;
+        float3 norm;
+        ShapeSurface surface = shape_surface_new();
+        int idx = __scene_hit(seed, ray, SCENE_ARGS, &dist, &norm, &surface);
+        float weight = weighted ? scene_mis_weight(
+            prev_pdf, scene_target_pdf(prev_pos, ray.dir, idx, SCENE_TARGETS_ARGS, BACKGROUND_ARGS)
+        ) : 1.0f;
+        if (idx < 0) {
+            color += weight*ray.color*__background(ray, BACKGROUND_ARGS);
+            break;
+        }
+        float3 pos = ray.start + dist*ray.dir;
+
+        if (depth + 1 < max_depth) {
+            color += scene_light(seed, ray, pos, norm, surface, idx, SCENE_ARGS);
+        }
+
+        __global const int *ibuf = object_ibuf + idx*SCENE_OBJECT_SIZE_INT;
+        __global const float *fbuf = object_fbuf + idx*SCENE_OBJECT_SIZE_FLOAT;
+        Ray new_ray = ray;
+        float3 glow = (float3)(0.0f);
+        bool bounced = __object_bounce_ext(
+            seed, ray, pos, norm, surface,
+            ibuf, fbuf, TEXTURE_ATLAS_ARGS, &new_ray, &glow
+        );
+        color += weight*glow;
+        if (!bounced) {
+            break;
+        }
+        new_ray.start = pos + SCENE_EPS*new_ray.dir;
+        new_ray.origin = idx;
+        new_ray.target = -1;
+        if (new_ray.history & RAY_DIFFUSE) {
+            prev_pdf = __object_pdf(ray, pos, norm, surface, new_ray.dir, ibuf, fbuf, TEXTURE_ATLAS_ARGS);
+        }
+        prev_pos = pos;
+
+        // Russian roulette for non-specular rays
+        if (depth + 1 >= roulette_depth && (new_ray.history & RAY_DIFFUSE)) {
+            float3 c = new_ray.color;
+            float p = clamp(fmax(c.x, fmax(c.y, c.z)), 0.05f, 1.0f);
+            if (random_uniform(seed) >= p) {
+                break;
+            }
+            new_ray.color /= p;
+        }
+        ray = new_ray;
+    }
+    return color;
+}
@@ -0,0 +1,25 @@
+#pragma once
+
+#include <clay_core/ray.h>
+
+// Fast rejection test.
+// Returns `false` if the ray definitely doesn't hit anything inside the bound,
+// otherwise returns `true` and writes the distance where the ray enters the bound
+// (zero if the ray starts inside) to `enter`.
+#define BOUND_BOUND_RET bool
+#define BOUND_BOUND_RET_BAD true
+
+#define BOUND_BOUND_ARGS_DEF \
+    Ray ray, \
+    __global const int *ibuf, \
+    __global const float *fbuf, \
+    float *enter
+
+#define BOUND_BOUND_ARGS \
+    ray, ibuf, fbuf, enter
+
+#define BOUND_BOUND_ARGS_B(di, df) \
+    ray, ibuf + (di), fbuf + (df), enter
+
+#define BOUND_BOUND_ARGS_R(r) \
+    (r), ibuf, fbuf, enter
@@ -0,0 +1,83 @@
+#pragma once
+
+#include <clay_core/random.h>
+#include "bound.h"
+#include "interval.h"
+#include "target.h"
+#include "spherical.h"
+
+// Axis-aligned bounding box
+// fbuf: min corner (3), max corner (3)
+
+BOUND_BOUND_RET bounding_box_bound(BOUND_BOUND_ARGS_DEF) {
+    // Slab test without normals
+    float3 inv = 1.0f/ray.dir;
+    float3 ta = (vload3(0, fbuf) - ray.start)*inv;
+    float3 tb = (vload3(1, fbuf) - ray.start)*inv;
+    float3 tn = fmin(ta, tb), tf = fmax(ta, tb);
+    float t0 = fmax(fmax(tn.x, tn.y), tn.z);
+    float t1 = fmin(fmin(tf.x, tf.y), tf.z);
+    if (t0 > t1 || t1 < 0.0f) {
+        return false;
+    }
+    *enter = fmax(t0, 0.0f);
+    return true;
+}
+
+SHAPE_HIT_RET bounding_box_hit(SHAPE_HIT_ARGS_DEF) {
+    return shape_interval_hit(shape_interval_box(
+        ray.start, ray.dir, vload3(0, fbuf), vload3(1, fbuf)
+    ), enter, exit, norm);
+}
+
+// Writes the faces visible from the point as triangles and returns their count,
+// zero if the point is inside the box
+int bounding_box_faces(float3 pos, __global const float *fbuf, float3 *verts) {
+    float3 lo = vload3(0, fbuf), hi = vload3(1, fbuf);
+    float3 size = hi - lo;
+    int count = 0;
+    float3 ex = (float3)(size.x, 0.0f, 0.0f);
+    float3 ey = (float3)(0.0f, size.y, 0.0f);
+    float3 ez = (float3)(0.0f, 0.0f, size.z);
+    if (pos.x < lo.x || pos.x > hi.x) {
+        float3 o = pos.x < lo.x ? lo : lo + ex;
+        count = spherical_push_parallelogram(verts, count, o, ey, ez);
+    }
+    if (pos.y < lo.y || pos.y > hi.y) {
+        float3 o = pos.y < lo.y ? lo : lo + ey;
+        count = spherical_push_parallelogram(verts, count, o, ez, ex);
+    }
+    if (pos.z < lo.z || pos.z > hi.z) {
+        float3 o = pos.z < lo.z ? lo : lo + ez;
+        count = spherical_push_parallelogram(verts, count, o, ex, ey);
+    }
+    return count;
+}
+
+// Uniform sampling of the solid angle of the faces visible from the point
+TARGET_SAMPLE_RET bounding_box_sample(TARGET_SAMPLE_ARGS_DEF) {
+    float3 verts[3*SPHERICAL_MAX_TRIANGLES];
+    int count = bounding_box_faces(pos, fbuf, verts);
+    if (count == 0) {
+        *dir = random_sphere(seed);
+        return 4.0f*M_PI_F;
+    }
+    return spherical_triangles_sample(seed, pos, verts, count, dir);
+}
+
+TARGET_PDF_RET bounding_box_pdf(TARGET_PDF_ARGS_DEF) {
+    float3 verts[3*SPHERICAL_MAX_TRIANGLES];
+    int count = bounding_box_faces(pos, fbuf, verts);
+    if (count == 0) {
+        return 1.0f/(4.0f*M_PI_F);
+    }
+    Ray ray = ray_new();
+    ray.start = pos;
+    ray.dir = dir;
+    float enter;
+    if (!bounding_box_bound(ray, ibuf, fbuf, &enter)) {
+        return 0.0f;
+    }
+    float angle = spherical_triangles_angle(pos, verts, count);
+    return angle > 0.0f ? 1.0f/angle : 0.0f;
+}
@@ -0,0 +1,65 @@
+#pragma once
+
+#include <clay_core/linalg.h>
+#include <clay_core/random.h>
+#include "bound.h"
+#include "interval.h"
+#include "target.h"
+
+// Bounding sphere
+// fbuf: center (3), radius (1)
+
+BOUND_BOUND_RET bounding_sphere_bound(BOUND_BOUND_ARGS_DEF) {
+    float3 p = vload3(0, fbuf) - ray.start;
+    float r = fbuf[3];
+    float3 d = normalize(ray.dir);
+    float l = dot(d, p);
+    float p2 = dot(p, p), r2 = r*r;
+    if (p2 <= r2) {
+        *enter = 0.0f;
+        return true;
+    }
+    float h2 = r2 - (p2 - l*l);
+    if (l < 0.0f || h2 < 0.0f) {
+        return false;
+    }
+    *enter = (l - sqrt(h2))/length(ray.dir);
+    return true;
+}
+
+SHAPE_HIT_RET bounding_sphere_hit(SHAPE_HIT_ARGS_DEF) {
+    return shape_interval_hit(shape_interval_sphere(
+        ray.start, ray.dir, vload3(0, fbuf), fbuf[3]
+    ), enter, exit, norm);
+}
+
+// Uniform sampling of the cone of directions that the sphere occupies
+TARGET_SAMPLE_RET bounding_sphere_sample(TARGET_SAMPLE_ARGS_DEF) {
+    float3 d = vload3(0, fbuf) - pos;
+    float r = fbuf[3];
+    float l2 = dot(d, d);
+    if (l2 <= r*r) {
+        *dir = random_sphere(seed);
+        return 4.0f*M_PI_F;
+    }
+    float cos_alpha = sqrt(1.0f - r*r/l2);
+    float3 z = d/sqrt(l2), x, y;
+    complement(z, &x, &y);
+    float3 c = random_sphere_cap(seed, cos_alpha);
+    *dir = c.x*x + c.y*y + c.z*z;
+    return 2.0f*M_PI_F*(1.0f - cos_alpha);
+}
+
+TARGET_PDF_RET bounding_sphere_pdf(TARGET_PDF_ARGS_DEF) {
+    float3 d = vload3(0, fbuf) - pos;
+    float r = fbuf[3];
+    float l2 = dot(d, d);
+    if (l2 <= r*r) {
+        return 1.0f/(4.0f*M_PI_F);
+    }
+    float cos_alpha = sqrt(1.0f - r*r/l2);
+    if (dot(normalize(dir), d)/sqrt(l2) < cos_alpha) {
+        return 0.0f;
+    }
+    return 1.0f/(2.0f*M_PI_F*(1.0f - cos_alpha));
+}
@@ -0,0 +1,79 @@
+#pragma once
+
+#include "interval.h"
+
+// Cone with the apex at `(0, 0, 1)` and the base of unit radius at `z = -1`
+// Textured by the angle around the axis and the height.
+
+// Outward normal of the conical surface at the point
+float3 _cone_shape_norm(float3 p) {
+    return normalize((float3)(p.x, p.y, 0.25f*(1.0f - p.z)));
+}
+
+SHAPE_HIT_EXT_RET cone_shape_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    // The infinite double cone is `x^2 + y^2 - (1 - z)^2/4 <= 0`,
+    // its lower nappe is cut by the slab `-1 <= z <= 1`.
+    float3 p = ray.start, d = ray.dir;
+    float w = 1.0f - p.z;
+    float a = d.x*d.x + d.y*d.y - 0.25f*d.z*d.z;
+    float b = p.x*d.x + p.y*d.y + 0.25f*w*d.z;
+    float c = p.x*p.x + p.y*p.y - 0.25f*w*w;
+
+    ShapeInterval slab = shape_interval_slab(p.z, d.z);
+    // The ray may cross the double cone in two separate segments
+    ShapeInterval si0 = shape_interval_full(), si1 = shape_interval_full();
+    if (fabs(a) < 1e-8f) {
+        if (b == 0.0f) {
+            if (c > 0.0f) {
+                return false;
+            }
+        } else {
+            float t = -0.5f*c/b;
+            float3 n = _cone_shape_norm(p + d*t);
+            if (b > 0.0f) {
+                si0.exit = t;
+                si0.exit_norm = n;
+            } else {
+                si0.enter = t;
+                si0.enter_norm = n;
+            }
+        }
+        si1.enter = INFINITY;
+    } else {
+        float e = b*b - a*c;
+        if (e < 0.0f) {
+            if (a > 0.0f) {
+                return false;
+            }
+            si1.enter = INFINITY;
+        } else {
+            float s = sqrt(e);
+            float t0 = (-b - s)/a, t1 = (-b + s)/a;
+            if (t0 > t1) {
+                float t = t0; t0 = t1; t1 = t;
+            }
+            float3 n0 = _cone_shape_norm(p + d*t0);
+            float3 n1 = _cone_shape_norm(p + d*t1);
+            if (a > 0.0f) {
+                si0 = shape_interval_new(t0, t1, n0, n1);
+                si1.enter = INFINITY;
+            } else {
+                si0.exit = t0;
+                si0.exit_norm = n0;
+                si1.enter = t1;
+                si1.enter_norm = n1;
+            }
+        }
+    }
+    si0 = shape_interval_intersect(si0, slab);
+    si1 = shape_interval_intersect(si1, slab);
+    // Only one segment lies in the lower nappe
+    if (!shape_interval_hit(shape_interval_empty(si0) ? si1 : si0, enter, exit, norm)) {
+        return false;
+    }
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = shape_uv_cylindrical(shape_hit_point(ray, *enter, *exit));
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(cone_shape)
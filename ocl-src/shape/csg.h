@@ -0,0 +1,108 @@
+#pragma once
+
+#include "shape.h"
+
+// Constructive solid geometry.
+// The ray is walked through boundaries of both operands in order of distance,
+// and the first segment where the combined shape is filled is reported.
+
+#define CSG_MAX_STEPS 64
+#define CSG_EPS 1e-4f
+
+// Location of the point relative to a shape and the nearest boundary ahead of it
+typedef struct {
+    bool inside;
+    float dist;
+    float3 norm;
+    ShapeSurface surface;
+} CsgProbe;
+
+// Inside predicates of operations and the sign of the second operand normal
+#define CSG_UNION_IN(a, b)          ((a) || (b))
+#define CSG_UNION_SIGN              1.0f
+#define CSG_INTERSECTION_IN(a, b)   ((a) && (b))
+#define CSG_INTERSECTION_SIGN       1.0f
+#define CSG_DIFFERENCE_IN(a, b)     ((a) && !(b))
+#define CSG_DIFFERENCE_SIGN         -1.0f
+
+// Defines a function that probes the shape at the distance `t` along the ray
+#define CSG_PROBE_FN_DEF(probe, shape) \
+    CsgProbe probe( \
+        uint *seed, Ray ray, \
+        __global const int *ibuf, \
+        __global const float *fbuf, \
+        SHAPE_MESH_ARGS_DEF, \
+        float t \
+    ) { \
+        CsgProbe p = { .inside = false, .dist = INFINITY, .norm = (float3)(0.0f), .surface = shape_surface_new() }; \
+        Ray r = ray; \
+        r.start = ray.start + ray.dir*t; \
+        float e = 0.0f, x = 0.0f; \
+        float3 n = (float3)(0.0f); \
+        ShapeSurface c = shape_surface_new(); \
+        if (shape##_hit_ext(seed, r, ibuf, fbuf, SHAPE_MESH_ARGS, &e, &x, &n, &c)) { \
+            p.inside = e <= 0.0f; \
+            p.dist = t + (p.inside ? x : e); \
+            p.norm = n; \
+            p.surface = c; \
+        } \
+        return p; \
+    }
+
+#define CSG_SHAPE_FN_DEF(csg, op, first, fdi, fdf, second, sdi, sdf) \
+    CSG_PROBE_FN_DEF(csg##_probe_first, first) \
+    CSG_PROBE_FN_DEF(csg##_probe_second, second) \
+    SHAPE_HIT_EXT_RET csg##_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) { \
+        CsgProbe a = csg##_probe_first(seed, ray, ibuf + (fdi), fbuf + (fdf), SHAPE_MESH_ARGS, 0.0f); \
+        CsgProbe b = csg##_probe_second(seed, ray, ibuf + (sdi), fbuf + (sdf), SHAPE_MESH_ARGS, 0.0f); \
+        bool inside = CSG_##op##_IN(a.inside, b.inside); \
+        float t_enter = 0.0f; \
+        float3 n_enter = (float3)(0.0f); \
+        ShapeSurface c_enter = shape_surface_new(); \
+        for (int i = 0; i < CSG_MAX_STEPS; ++i) { \
+            float t; \
+            float3 n; \
+            ShapeSurface c; \
+            if (a.dist <= b.dist) { \
+                t = a.dist; \
+                n = a.norm; \
+                c = a.surface; \
+            } else { \
+                t = b.dist; \
+                n = CSG_##op##_SIGN*b.norm; \
+                c = b.surface; \
+            } \
+            if (isinf(t)) { \
+                break; \
+            } \
+            float tn = t + CSG_EPS; \
+            if (a.dist <= tn) { \
+                a = csg##_probe_first(seed, ray, ibuf + (fdi), fbuf + (fdf), SHAPE_MESH_ARGS, tn); \
+            } \
+            if (b.dist <= tn) { \
+                b = csg##_probe_second(seed, ray, ibuf + (sdi), fbuf + (sdf), SHAPE_MESH_ARGS, tn); \
+            } \
+            bool next_inside = CSG_##op##_IN(a.inside, b.inside); \
+            if (!inside && next_inside) { \
+                t_enter = t; \
+                n_enter = n; \
+                c_enter = c; \
+            } else if (inside && !next_inside) { \
+                *enter = t_enter; \
+                *exit = t; \
+                *norm = t_enter > 0.0f ? n_enter : n; \
+                *surface = t_enter > 0.0f ? c_enter : c; \
+                return true; \
+            } \
+            inside = next_inside; \
+        } \
+        if (inside) { \
+            /* The shape is unbounded along the ray */ \
+            *enter = t_enter; \
+            *exit = INFINITY; \
+            *norm = n_enter; \
+            *surface = c_enter; \
+        } \
+        return inside; \
+    } \
+    SHAPE_HIT_FROM_HIT_EXT_FN_DEF(csg)
@@ -0,0 +1,22 @@
+#pragma once
+
+#include "interval.h"
+
+// Cube `[-1, 1]^3`
+// Each face is mapped to the whole `[0, 1]^2` square.
+
+SHAPE_HIT_EXT_RET cube_shape_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    if (!shape_interval_hit(shape_interval_box(
+        ray.start, ray.dir, (float3)(-1.0f), (float3)(1.0f)
+    ), enter, exit, norm)) {
+        return false;
+    }
+    float3 p = shape_hit_point(ray, *enter, *exit);
+    float3 n = fabs(*norm);
+    float2 q = n.x > 0.5f ? p.yz : (n.y > 0.5f ? p.zx : p.xy);
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = 0.5f*(q + 1.0f);
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(cube_shape)
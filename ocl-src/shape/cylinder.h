@@ -0,0 +1,39 @@
+#pragma once
+
+#include "interval.h"
+
+// Capped cylinder of unit radius around the `z` axis, `-1 <= z <= 1`
+// Textured by the angle around the axis and the height.
+
+SHAPE_HIT_EXT_RET cylinder_shape_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    float2 p = ray.start.xy, d = ray.dir.xy;
+    float a = dot(d, d);
+    float b = dot(p, d);
+    float c = dot(p, p) - 1.0f;
+
+    ShapeInterval si = shape_interval_full();
+    if (a == 0.0f) {
+        if (c > 0.0f) {
+            return false;
+        }
+    } else {
+        float e = b*b - a*c;
+        if (e < 0.0f) {
+            return false;
+        }
+        float s = sqrt(e);
+        si.enter = (-b - s)/a;
+        si.exit = (-b + s)/a;
+        si.enter_norm = (float3)(normalize(p + d*si.enter), 0.0f);
+        si.exit_norm = (float3)(normalize(p + d*si.exit), 0.0f);
+    }
+    si = shape_interval_intersect(si, shape_interval_slab(ray.start.z, ray.dir.z));
+    if (!shape_interval_hit(si, enter, exit, norm)) {
+        return false;
+    }
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = shape_uv_cylindrical(shape_hit_point(ray, *enter, *exit));
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(cylinder_shape)
@@ -0,0 +1,25 @@
+#pragma once
+
+#include "interval.h"
+
+// Disk of unit radius in the `xy`-plane centered at the origin
+
+SHAPE_HIT_EXT_RET disk_shape_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    if (ray.dir.z == 0.0f) {
+        return false;
+    }
+    float t = -ray.start.z/ray.dir.z;
+    float2 p = ray.start.xy + ray.dir.xy*t;
+    if (dot(p, p) > 1.0f) {
+        return false;
+    }
+    float3 n = (float3)(0.0f, 0.0f, -sign(ray.dir.z));
+    if (!shape_interval_hit(shape_interval_new(t, t, n, n), enter, exit, norm)) {
+        return false;
+    }
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = 0.5f*(p + 1.0f);
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(disk_shape)
@@ -0,0 +1,29 @@
+#pragma once
+
+#include "interval.h"
+
+// Half-space `z <= 0` bounded by the `xy`-plane
+// The plane is textured by its `x` and `y` coordinates, so the texture repeats with the unit period.
+
+SHAPE_HIT_EXT_RET half_space_shape_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    float3 n = (float3)(0.0f, 0.0f, 1.0f);
+    float z = ray.start.z, dz = ray.dir.z;
+    ShapeInterval si = shape_interval_new(-INFINITY, INFINITY, n, n);
+    if (dz == 0.0f) {
+        if (z > 0.0f) {
+            return false;
+        }
+    } else if (dz < 0.0f) {
+        si.enter = -z/dz;
+    } else {
+        si.exit = -z/dz;
+    }
+    if (!shape_interval_hit(si, enter, exit, norm)) {
+        return false;
+    }
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = shape_hit_point(ray, *enter, *exit).xy;
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(half_space_shape)
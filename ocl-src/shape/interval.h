@@ -0,0 +1,111 @@
+#pragma once
+
+#include "shape.h"
+
+
+// Segment of the ray inside a shape with outward normals at its ends
+typedef struct {
+    float enter, exit;
+    float3 enter_norm, exit_norm;
+} ShapeInterval;
+
+ShapeInterval shape_interval_new(float enter, float exit, float3 enter_norm, float3 exit_norm) {
+    ShapeInterval si = {
+        .enter = enter,
+        .exit = exit,
+        .enter_norm = enter_norm,
+        .exit_norm = exit_norm
+    };
+    return si;
+}
+
+// Interval that contains the whole ray
+ShapeInterval shape_interval_full() {
+    return shape_interval_new(-INFINITY, INFINITY, (float3)(0.0f), (float3)(0.0f));
+}
+
+bool shape_interval_empty(ShapeInterval si) {
+    return si.enter > si.exit;
+}
+
+// Intersection of two intervals (used for convex shapes)
+ShapeInterval shape_interval_intersect(ShapeInterval a, ShapeInterval b) {
+    ShapeInterval r = a;
+    if (b.enter > a.enter) {
+        r.enter = b.enter;
+        r.enter_norm = b.enter_norm;
+    }
+    if (b.exit < a.exit) {
+        r.exit = b.exit;
+        r.exit_norm = b.exit_norm;
+    }
+    return r;
+}
+
+// Writes the interval to the hit output.
+// The hit is reported if the interval is not empty and ends ahead of the ray start.
+// The normal is taken at the nearest point ahead: at the enter if it's ahead or at the exit otherwise.
+SHAPE_HIT_RET shape_interval_hit(ShapeInterval si, float *enter, float *exit, float3 *norm) {
+    if (shape_interval_empty(si) || si.exit <= 0.0f) {
+        return false;
+    }
+    *enter = si.enter;
+    *exit = si.exit;
+    *norm = si.enter > 0.0f ? si.enter_norm : si.exit_norm;
+    return true;
+}
+
+// Segment of the ray between two parallel planes `z = lo` and `z = hi`
+ShapeInterval shape_interval_range(float start, float dir, float lo, float hi) {
+    if (dir == 0.0f) {
+        if (start < lo || start > hi) {
+            return shape_interval_new(INFINITY, -INFINITY, (float3)(0.0f), (float3)(0.0f));
+        }
+        return shape_interval_full();
+    }
+    float s = sign(dir);
+    float3 n = (float3)(0.0f, 0.0f, s);
+    if (dir > 0.0f) {
+        return shape_interval_new((lo - start)/dir, (hi - start)/dir, -n, n);
+    } else {
+        return shape_interval_new((hi - start)/dir, (lo - start)/dir, -n, n);
+    }
+}
+
+// Segment of the ray between two parallel planes `z = -1` and `z = 1`
+ShapeInterval shape_interval_slab(float start, float dir) {
+    return shape_interval_range(start, dir, -1.0f, 1.0f);
+}
+
+// Segment of the ray inside the axis-aligned box
+ShapeInterval shape_interval_box(float3 start, float3 dir, float3 lo, float3 hi) {
+    ShapeInterval si = shape_interval_range(start.z, dir.z, lo.z, hi.z);
+    // Ranges are taken along `z`, so normals are rotated to the proper axis
+    ShapeInterval sx = shape_interval_range(start.x, dir.x, lo.x, hi.x);
+    sx.enter_norm = sx.enter_norm.zxy;
+    sx.exit_norm = sx.exit_norm.zxy;
+    si = shape_interval_intersect(si, sx);
+    ShapeInterval sy = shape_interval_range(start.y, dir.y, lo.y, hi.y);
+    sy.enter_norm = sy.enter_norm.yzx;
+    sy.exit_norm = sy.exit_norm.yzx;
+    return shape_interval_intersect(si, sy);
+}
+
+// Segment of the ray inside the sphere
+ShapeInterval shape_interval_sphere(float3 start, float3 dir, float3 center, float radius) {
+    float3 p = start - center;
+    float a = dot(dir, dir);
+    float b = dot(dir, p)/a;
+    float c = (dot(p, p) - radius*radius)/a;
+    float d = b*b - c;
+    if (d < 0.0f) {
+        return shape_interval_new(INFINITY, -INFINITY, (float3)(0.0f), (float3)(0.0f));
+    }
+    float s = sqrt(d);
+    float t0 = -b - s, t1 = -b + s;
+    return shape_interval_new(
+        t0, t1,
+        normalize(p + dir*t0),
+        normalize(p + dir*t1)
+    );
+}
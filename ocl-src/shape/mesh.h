@@ -0,0 +1,115 @@
+#pragma once
+
+#include "shape.h"
+
+// Triangle mesh stored in the shared mesh buffers, so only `hit_ext` finds it
+// ibuf: node offset, triangle offset (in `mesh_ibuf`),
+//       vertex offset, normal offset, texture coordinates offset, node bounds offset (in `mesh_fbuf`)
+//
+// Node is two ints: `(first triangle, count)` for leaves
+// and `(right child, 0)` for inner nodes (left child is the next node).
+// Node bounds are min and max corners (6 floats).
+// Triangle is three vertex indices, normals and texture coordinates are stored per vertex.
+
+// The host limits the hierarchy depth so that the traversal never overflows the stack
+#define MESH_SHAPE_STACK_SIZE 32
+
+// Returns the nearest distance where the ray enters the box or `INFINITY` if it misses
+float _mesh_shape_box(float3 start, float3 inv_dir, __global const float *bounds) {
+    float3 ta = (vload3(0, bounds) - start)*inv_dir;
+    float3 tb = (vload3(1, bounds) - start)*inv_dir;
+    float3 tn = fmin(ta, tb), tf = fmax(ta, tb);
+    float t0 = fmax(fmax(tn.x, tn.y), tn.z);
+    float t1 = fmin(fmin(tf.x, tf.y), tf.z);
+    return (t0 <= t1 && t1 > 0.0f) ? t0 : INFINITY;
+}
+
+SHAPE_HIT_EXT_RET mesh_shape_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    if (mesh_ibuf == 0) {
+        return false;
+    }
+    __global const int *nodes = mesh_ibuf + ibuf[0];
+    __global const int *tris = mesh_ibuf + ibuf[1];
+    __global const float *verts = mesh_fbuf + ibuf[2];
+    __global const float *norms = mesh_fbuf + ibuf[3];
+    __global const float *uvs = mesh_fbuf + ibuf[4];
+    __global const float *bounds = mesh_fbuf + ibuf[5];
+
+    float3 inv_dir = 1.0f/ray.dir;
+    int stack[MESH_SHAPE_STACK_SIZE];
+    int sp = 0;
+    stack[sp++] = 0;
+
+    float best = INFINITY;
+    int best_tri = -1;
+    float2 best_uv = (float2)(0.0f);
+
+    while (sp > 0) {
+        int n = stack[--sp];
+        if (_mesh_shape_box(ray.start, inv_dir, bounds + 6*n) >= best) {
+            continue;
+        }
+        int a = nodes[2*n], b = nodes[2*n + 1];
+        if (b > 0) {
+            for (int i = a; i < a + b; ++i) {
+                // Moller-Trumbore intersection
+                float3 v0 = vload3(tris[3*i + 0], verts);
+                float3 e1 = vload3(tris[3*i + 1], verts) - v0;
+                float3 e2 = vload3(tris[3*i + 2], verts) - v0;
+                float3 p = cross(ray.dir, e2);
+                float det = dot(e1, p);
+                if (det == 0.0f) {
+                    continue;
+                }
+                float idet = 1.0f/det;
+                float3 s = ray.start - v0;
+                float u = dot(s, p)*idet;
+                if (u < 0.0f || u > 1.0f) {
+                    continue;
+                }
+                float3 q = cross(s, e1);
+                float v = dot(ray.dir, q)*idet;
+                if (v < 0.0f || u + v > 1.0f) {
+                    continue;
+                }
+                float t = dot(e2, q)*idet;
+                if (t > 0.0f && t < best) {
+                    best = t;
+                    best_tri = i;
+                    best_uv = (float2)(u, v);
+                }
+            }
+        } else {
+            stack[sp++] = a;
+            stack[sp++] = n + 1;
+        }
+    }
+
+    if (best_tri < 0) {
+        return false;
+    }
+
+    int3 idx = vload3(best_tri, tris);
+    float3 v0 = vload3(idx.x, verts);
+    float3 gn = cross(vload3(idx.y, verts) - v0, vload3(idx.z, verts) - v0);
+    float3 w = (float3)(1.0f - best_uv.x - best_uv.y, best_uv.x, best_uv.y);
+    float3 n = normalize(
+        w.x*vload3(idx.x, norms) +
+        w.y*vload3(idx.y, norms) +
+        w.z*vload3(idx.z, norms)
+    );
+    // Interpolated normal may look away from the geometric one near silhouettes
+    if (dot(n, gn) < 0.0f) {
+        n = normalize(gn);
+    }
+
+    // Faces are oriented outwards, so the back face means that the ray starts inside
+    *enter = dot(gn, ray.dir) < 0.0f ? best : -INFINITY;
+    *exit = best;
+    *norm = n;
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = w.x*vload2(idx.x, uvs) + w.y*vload2(idx.y, uvs) + w.z*vload2(idx.z, uvs);
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(mesh_shape)
@@ -0,0 +1,90 @@
+#pragma once
+
+#include "bound.h"
+#include "interval.h"
+#include "target.h"
+#include "spherical.h"
+
+// Parallelogram `origin + s*u + t*v` for `s, t` in `[0, 1]`
+// fbuf: origin (3), first edge `u` (3), second edge `v` (3)
+
+// Finds the distance to the intersection point, the normal facing the ray
+// and the coordinates of the point in the `(u, v)` basis
+bool parallelogram_intersect(Ray ray, __global const float *fbuf, float *dist, float3 *norm, float2 *uv) {
+    float3 o = vload3(0, fbuf), u = vload3(1, fbuf), v = vload3(2, fbuf);
+    float3 n = cross(u, v);
+    float dn = dot(ray.dir, n);
+    if (dn == 0.0f) {
+        return false;
+    }
+    float t = dot(o - ray.start, n)/dn;
+    float3 p = ray.start + ray.dir*t - o;
+    // Coordinates in the `(u, v)` basis
+    float3 nu = cross(v, n), nv = cross(n, u);
+    float a = dot(p, nu)/dot(u, nu);
+    float b = dot(p, nv)/dot(v, nv);
+    if (a < 0.0f || a > 1.0f || b < 0.0f || b > 1.0f) {
+        return false;
+    }
+    *dist = t;
+    *norm = normalize(dn > 0.0f ? -n : n);
+    *uv = (float2)(a, b);
+    return true;
+}
+
+SHAPE_HIT_EXT_RET parallelogram_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    float t;
+    float3 n;
+    float2 c;
+    if (!parallelogram_intersect(ray, fbuf, &t, &n, &c)) {
+        return false;
+    }
+    if (!shape_interval_hit(shape_interval_new(t, t, n, n), enter, exit, norm)) {
+        return false;
+    }
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = c;
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(parallelogram)
+
+BOUND_BOUND_RET parallelogram_bound(BOUND_BOUND_ARGS_DEF) {
+    float t;
+    float3 n;
+    float2 c;
+    if (!parallelogram_intersect(ray, fbuf, &t, &n, &c) || t < 0.0f) {
+        return false;
+    }
+    *enter = t;
+    return true;
+}
+
+// Uniform sampling of the solid angle of the parallelogram
+TARGET_SAMPLE_RET parallelogram_sample(TARGET_SAMPLE_ARGS_DEF) {
+    float3 verts[6];
+    spherical_push_parallelogram(
+        verts, 0,
+        vload3(0, fbuf), vload3(1, fbuf), vload3(2, fbuf)
+    );
+    return spherical_triangles_sample(seed, pos, verts, 2, dir);
+}
+
+TARGET_PDF_RET parallelogram_pdf(TARGET_PDF_ARGS_DEF) {
+    Ray ray = ray_new();
+    ray.start = pos;
+    ray.dir = dir;
+    float t;
+    float3 n;
+    float2 c;
+    if (!parallelogram_intersect(ray, fbuf, &t, &n, &c) || t <= 0.0f) {
+        return 0.0f;
+    }
+    float3 verts[6];
+    spherical_push_parallelogram(
+        verts, 0,
+        vload3(0, fbuf), vload3(1, fbuf), vload3(2, fbuf)
+    );
+    float angle = spherical_triangles_angle(pos, verts, 2);
+    return angle > 0.0f ? 1.0f/angle : 0.0f;
+}
@@ -0,0 +1,10 @@
+#pragma once
+
+#include "sdf.h"
+
+// fbuf: half size (3)
+
+SDF_DIST_RET cuboid_sdf_dist(SDF_DIST_ARGS_DEF) {
+    float3 q = fabs(p) - vload3(0, fbuf);
+    return length(fmax(q, (float3)(0.0f))) + fmin(fmax(q.x, fmax(q.y, q.z)), 0.0f);
+}
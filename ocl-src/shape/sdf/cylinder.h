@@ -0,0 +1,11 @@
+#pragma once
+
+#include "sdf.h"
+
+// Capped cylinder around the `z` axis
+// fbuf: radius (1), half height (1)
+
+SDF_DIST_RET cylinder_sdf_dist(SDF_DIST_ARGS_DEF) {
+    float2 q = fabs((float2)(length(p.xy), p.z)) - (float2)(fbuf[0], fbuf[1]);
+    return fmin(fmax(q.x, q.y), 0.0f) + length(fmax(q, (float2)(0.0f)));
+}
@@ -0,0 +1,34 @@
+#pragma once
+
+#include "sdf.h"
+
+// Operators over distance functions.
+// Named offsets of the operator parameters and operands should be defined.
+
+// Union with smooth blending of the size `<op>_K_DF`
+#define SDF_SMOOTH_UNION_FN_DEF(op, first, second) \
+    SDF_DIST_RET op##_dist(SDF_DIST_ARGS_DEF) { \
+        float k = fbuf[op##_K_DF]; \
+        float a = first##_dist(SDF_DIST_ARGS_B(op##_FIRST_DI, op##_FIRST_DF)); \
+        float b = second##_dist(SDF_DIST_ARGS_B(op##_SECOND_DI, op##_SECOND_DF)); \
+        if (k <= 0.0f) { \
+            return fmin(a, b); \
+        } \
+        float h = clamp(0.5f + 0.5f*(b - a)/k, 0.0f, 1.0f); \
+        return mix(b, a, h) - k*h*(1.0f - h); \
+    }
+
+// Rounds edges by the radius `<op>_RADIUS_DF`
+#define SDF_ROUND_FN_DEF(op, sdf) \
+    SDF_DIST_RET op##_dist(SDF_DIST_ARGS_DEF) { \
+        return sdf##_dist(SDF_DIST_ARGS_B(op##_SDF_DI, op##_SDF_DF)) - fbuf[op##_RADIUS_DF]; \
+    }
+
+// Twists around the `z` axis by the angle `<op>_RATE_DF` per unit of length
+#define SDF_TWIST_FN_DEF(op, sdf) \
+    SDF_DIST_RET op##_dist(SDF_DIST_ARGS_DEF) { \
+        float a = fbuf[op##_RATE_DF]*p.z; \
+        float c = cos(a), s = sin(a); \
+        float3 q = (float3)(c*p.x - s*p.y, s*p.x + c*p.y, p.z); \
+        return sdf##_dist(SDF_DIST_ARGS_PB(q, op##_SDF_DI, op##_SDF_DF)); \
+    }
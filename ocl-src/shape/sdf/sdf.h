@@ -0,0 +1,22 @@
+#pragma once
+
+// Signed distance to the surface, negative inside the shape
+#define SDF_DIST_RET float
+#define SDF_DIST_RET_BAD INFINITY
+
+#define SDF_DIST_ARGS_DEF \
+    float3 p, \
+    __global const int *ibuf, \
+    __global const float *fbuf
+
+#define SDF_DIST_ARGS \
+    p, ibuf, fbuf
+
+#define SDF_DIST_ARGS_PB(p, di, df) \
+    (p), ibuf + (di), fbuf + (df)
+
+#define SDF_DIST_ARGS_P(p) \
+    SDF_DIST_ARGS_PB(p, 0, 0)
+
+#define SDF_DIST_ARGS_B(di, df) \
+    SDF_DIST_ARGS_PB(p, di, df)
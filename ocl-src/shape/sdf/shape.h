@@ -0,0 +1,63 @@
+#pragma once
+
+#include <clay_core/shape/shape.h>
+#include "sdf.h"
+
+// Shape defined by the signed distance function and found by sphere tracing.
+// Named offsets `<shape>_MAX_STEPS_DI`, `<shape>_EPSILON_DF`, `<shape>_MAX_DISTANCE_DF`,
+// `<shape>_STEP_SCALE_DF` and `<shape>_SDF_DI/DF` should be defined.
+// The surface is textured by the spherical mapping around the origin.
+
+#define SDF_SHAPE_FN_DEF(shape, sdf) \
+    float shape##_dist(float3 p, __global const int *ibuf, __global const float *fbuf) { \
+        return sdf##_dist(SDF_DIST_ARGS_PB(p, shape##_SDF_DI, shape##_SDF_DF)); \
+    } \
+    float3 shape##_grad(float3 p, float h, __global const int *ibuf, __global const float *fbuf) { \
+        const float2 k = (float2)(1.0f, -1.0f); \
+        return normalize( \
+            k.xyy*shape##_dist(p + k.xyy*h, ibuf, fbuf) + \
+            k.yyx*shape##_dist(p + k.yyx*h, ibuf, fbuf) + \
+            k.yxy*shape##_dist(p + k.yxy*h, ibuf, fbuf) + \
+            k.xxx*shape##_dist(p + k.xxx*h, ibuf, fbuf) \
+        ); \
+    } \
+    SHAPE_HIT_EXT_RET shape##_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) { \
+        int max_steps = ibuf[shape##_MAX_STEPS_DI]; \
+        float eps = fbuf[shape##_EPSILON_DF]; \
+        float max_dist = fbuf[shape##_MAX_DISTANCE_DF]; \
+        float scale = fbuf[shape##_STEP_SCALE_DF]; \
+        float dl = length(ray.dir); \
+        float3 d = ray.dir/dl; \
+        \
+        float t = 0.0f, t_enter = 0.0f; \
+        float dist = shape##_dist(ray.start, ibuf, fbuf); \
+        int i = 0; \
+        if (dist > 0.0f) { \
+            for (; i < max_steps && dist >= eps; ++i) { \
+                t += scale*dist; \
+                if (t > max_dist) { \
+                    return false; \
+                } \
+                dist = shape##_dist(ray.start + d*t, ibuf, fbuf); \
+            } \
+            if (dist >= eps) { \
+                return false; \
+            } \
+            t_enter = t; \
+            t += 2.0f*eps; \
+            dist = shape##_dist(ray.start + d*t, ibuf, fbuf); \
+        } \
+        for (; i < max_steps && dist < 0.0f; ++i) { \
+            t += fmax(-scale*dist, eps); \
+            dist = shape##_dist(ray.start + d*t, ibuf, fbuf); \
+        } \
+        \
+        *enter = t_enter/dl; \
+        *exit = t/dl; \
+        float3 p = ray.start + d*(t_enter > 0.0f ? t_enter : t); \
+        *norm = shape##_grad(p, eps, ibuf, fbuf); \
+        shape_surface_hit(surface, ray, *enter, *exit, *norm); \
+        surface->uv = shape_uv_spherical(p); \
+        return true; \
+    } \
+    SHAPE_HIT_FROM_HIT_EXT_FN_DEF(shape)
@@ -0,0 +1,9 @@
+#pragma once
+
+#include "sdf.h"
+
+// fbuf: radius (1)
+
+SDF_DIST_RET sphere_sdf_dist(SDF_DIST_ARGS_DEF) {
+    return length(p) - fbuf[0];
+}
@@ -0,0 +1,11 @@
+#pragma once
+
+#include "sdf.h"
+
+// Torus around the `z` axis
+// fbuf: major radius (1), minor radius (1)
+
+SDF_DIST_RET torus_sdf_dist(SDF_DIST_ARGS_DEF) {
+    float2 q = (float2)(length(p.xy) - fbuf[0], p.z);
+    return length(q) - fbuf[1];
+}
@@ -1,5 +1,12 @@
 #pragma once
 
+#include <clay_core/ray.h>
+
+// Returns `true` if the ray intersects the shape and the intersection is (at least partially) ahead of the ray start.
+// `enter` and `exit` are distances along the ray where it enters and exits the shape
+// (`enter` may be negative if the ray starts inside the shape).
+// `norm` is the outward normal at the nearest point ahead: at `enter` if it's positive or at `exit` otherwise.
+// Flat shapes have `enter == exit` and the normal facing the ray.
 #define SHAPE_HIT_RET bool
 #define SHAPE_HIT_RET_BAD false
 
@@ -17,3 +24,102 @@
 
 #define SHAPE_HIT_ARGS_R(r) \
     seed, (r), ibuf, fbuf, enter, exit, norm
+
+// Properties of the surface at the hit point used for texturing
+typedef struct {
+    // Point and normal in the space of the primitive shape that was hit,
+    // so they move along with the shape when it is mapped
+    float3 pos;
+    float3 norm;
+    // Surface coordinates of the point
+    float2 uv;
+} ShapeSurface;
+
+ShapeSurface shape_surface_new() {
+    ShapeSurface s = {
+        .pos = (float3)(0.0f),
+        .norm = (float3)(0.0f),
+        .uv = (float2)(0.0f)
+    };
+    return s;
+}
+
+// Shared storage of mesh geometry (see `MeshBuffer`)
+#define SHAPE_MESH_ARGS_DEF \
+    __global const int *mesh_ibuf, \
+    __global const float *mesh_fbuf
+
+#define SHAPE_MESH_ARGS \
+    mesh_ibuf, mesh_fbuf
+
+// Storage that is passed when it isn't available, meshes are never hit then
+#define SHAPE_MESH_ARGS_NONE \
+    (__global const int *)0, (__global const float *)0
+
+// Extended `hit` that also gets the mesh storage and describes the surface at the point reported by `norm`.
+// Shapes should fill the point and the normal of `surface` by `shape_surface_hit`,
+// shapes without natural parametrization may leave `uv` untouched.
+// It is optional: shapes that implement only `hit` get the one defined by `SHAPE_HIT_EXT_FROM_HIT_FN_DEF`.
+#define SHAPE_HIT_EXT_RET SHAPE_HIT_RET
+#define SHAPE_HIT_EXT_RET_BAD SHAPE_HIT_RET_BAD
+
+#define SHAPE_HIT_EXT_ARGS_DEF \
+    uint *seed, Ray ray, \
+    __global const int *ibuf, \
+    __global const float *fbuf, \
+    SHAPE_MESH_ARGS_DEF, \
+    float *enter, float *exit, float3 *norm, ShapeSurface *surface
+
+#define SHAPE_HIT_EXT_ARGS \
+    seed, ray, ibuf, fbuf, SHAPE_MESH_ARGS, enter, exit, norm, surface
+
+#define SHAPE_HIT_EXT_ARGS_B(di, df) \
+    seed, ray, ibuf + (di), fbuf + (df), SHAPE_MESH_ARGS, enter, exit, norm, surface
+
+#define SHAPE_HIT_EXT_ARGS_R(r) \
+    seed, (r), ibuf, fbuf, SHAPE_MESH_ARGS, enter, exit, norm, surface
+
+// Defines `hit_ext` of the shape that implements only `hit`
+#define SHAPE_HIT_EXT_FROM_HIT_FN_DEF(shape) \
+    SHAPE_HIT_EXT_RET shape##_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) { \
+        if (!shape##_hit(SHAPE_HIT_ARGS)) { \
+            return false; \
+        } \
+        shape_surface_hit(surface, ray, *enter, *exit, *norm); \
+        return true; \
+    }
+
+// Defines `hit` of the shape that implements `hit_ext`, meshes inside of it are not hit
+#define SHAPE_HIT_FROM_HIT_EXT_FN_DEF(shape) \
+    SHAPE_HIT_RET shape##_hit(SHAPE_HIT_ARGS_DEF) { \
+        ShapeSurface surface = shape_surface_new(); \
+        return shape##_hit_ext(seed, ray, ibuf, fbuf, SHAPE_MESH_ARGS_NONE, enter, exit, norm, &surface); \
+    }
+
+// Point of the hit at the distance reported by `enter` and `exit`
+float3 shape_hit_point(Ray ray, float enter, float exit) {
+    return ray.start + ray.dir*(enter > 0.0f ? enter : exit);
+}
+
+// Fills the point and the normal of the surface at the hit reported by `enter`, `exit` and `norm`
+void shape_surface_hit(ShapeSurface *surface, Ray ray, float enter, float exit, float3 norm) {
+    surface->pos = shape_hit_point(ray, enter, exit);
+    surface->norm = norm;
+}
+
+// Longitude and colatitude of the direction to the point normalized to `[0, 1]`
+float2 shape_uv_spherical(float3 p) {
+    float3 d = normalize(p);
+    return (float2)(
+        0.5f + 0.5f*atan2(d.y, d.x)/M_PI_F,
+        acos(clamp(d.z, -1.0f, 1.0f))/M_PI_F
+    );
+}
+
+// Angle around the `z` axis and the height in `[-1, 1]` normalized to `[0, 1]`
+float2 shape_uv_cylindrical(float3 p) {
+    return (float2)(
+        0.5f + 0.5f*atan2(p.y, p.x)/M_PI_F,
+        0.5f*(p.z + 1.0f)
+    );
+}
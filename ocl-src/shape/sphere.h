@@ -0,0 +1,18 @@
+#pragma once
+
+#include "interval.h"
+
+// Unit sphere centered at the origin
+
+SHAPE_HIT_EXT_RET sphere_shape_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    if (!shape_interval_hit(shape_interval_sphere(
+        ray.start, ray.dir, (float3)(0.0f), 1.0f
+    ), enter, exit, norm)) {
+        return false;
+    }
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = shape_uv_spherical(shape_hit_point(ray, *enter, *exit));
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(sphere_shape)
@@ -0,0 +1,91 @@
+#pragma once
+
+#include <clay_core/random.h>
+
+// Solid angle of the spherical triangle with unit vertices.
+// The angle at the first vertex is written to `alpha`.
+float spherical_triangle_area(float3 a, float3 b, float3 c, float *alpha) {
+    float3 ab = cross(a, b), ac = cross(a, c), bc = cross(b, c);
+    float lab = length(ab), lac = length(ac), lbc = length(bc);
+    if (lab == 0.0f || lac == 0.0f || lbc == 0.0f) {
+        *alpha = 0.0f;
+        return 0.0f;
+    }
+    ab /= lab; ac /= lac; bc /= lbc;
+    float al = acos(clamp(dot(ab, ac), -1.0f, 1.0f));
+    float be = acos(clamp(-dot(bc, ab), -1.0f, 1.0f));
+    float ga = acos(clamp(dot(ac, bc), -1.0f, 1.0f));
+    *alpha = al;
+    return fmax(al + be + ga - M_PI_F, 0.0f);
+}
+
+// Uniform sampling of the spherical triangle (J. Arvo, "Stratified sampling of spherical triangles")
+float3 spherical_triangle_sample(uint *seed, float3 a, float3 b, float3 c, float area, float alpha) {
+    float area_s = random_uniform(seed)*area;
+    float s = sin(area_s - alpha), t = cos(area_s - alpha);
+    float ca = cos(alpha), sa = sin(alpha);
+    float u = t - ca;
+    float v = s + sa*dot(a, b);
+    float q = clamp(((v*t - u*s)*ca - v)/((v*s + u*t)*sa), -1.0f, 1.0f);
+    float3 cs = q*a + sqrt(1.0f - q*q)*normalize(c - dot(c, a)*a);
+    float z = 1.0f - random_uniform(seed)*(1.0f - dot(cs, b));
+    return z*b + sqrt(fmax(1.0f - z*z, 0.0f))*normalize(cs - dot(cs, b)*b);
+}
+
+#define SPHERICAL_MAX_TRIANGLES 6
+
+// Samples the direction uniformly within the solid angle of the set of triangles
+// and returns the total solid angle.
+// `verts` contains `3*count` vertices, triangles shouldn't overlap when viewed from `pos`.
+float spherical_triangles_sample(uint *seed, float3 pos, const float3 *verts, int count, float3 *dir) {
+    float areas[SPHERICAL_MAX_TRIANGLES], alphas[SPHERICAL_MAX_TRIANGLES];
+    float3 dirs[3*SPHERICAL_MAX_TRIANGLES];
+    float total = 0.0f;
+    for (int i = 0; i < count; ++i) {
+        for (int j = 0; j < 3; ++j) {
+            dirs[3*i + j] = normalize(verts[3*i + j] - pos);
+        }
+        areas[i] = spherical_triangle_area(dirs[3*i], dirs[3*i + 1], dirs[3*i + 2], &alphas[i]);
+        total += areas[i];
+    }
+    if (total <= 0.0f) {
+        *dir = normalize(verts[0] - pos);
+        return 0.0f;
+    }
+    float r = random_uniform(seed)*total;
+    int k = 0;
+    for (; k < count - 1; ++k) {
+        if (r < areas[k]) {
+            break;
+        }
+        r -= areas[k];
+    }
+    *dir = spherical_triangle_sample(seed, dirs[3*k], dirs[3*k + 1], dirs[3*k + 2], areas[k], alphas[k]);
+    return total;
+}
+
+// Total solid angle of the set of triangles viewed from `pos`
+float spherical_triangles_angle(float3 pos, const float3 *verts, int count) {
+    float total = 0.0f;
+    for (int i = 0; i < count; ++i) {
+        float alpha;
+        total += spherical_triangle_area(
+            normalize(verts[3*i] - pos),
+            normalize(verts[3*i + 1] - pos),
+            normalize(verts[3*i + 2] - pos),
+            &alpha
+        );
+    }
+    return total;
+}
+
+// Adds two triangles of the parallelogram to the vertex list
+int spherical_push_parallelogram(float3 *verts, int count, float3 o, float3 u, float3 v) {
+    verts[3*count + 0] = o;
+    verts[3*count + 1] = o + u;
+    verts[3*count + 2] = o + u + v;
+    verts[3*count + 3] = o;
+    verts[3*count + 4] = o + u + v;
+    verts[3*count + 5] = o + v;
+    return count + 2;
+}
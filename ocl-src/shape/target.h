@@ -16,3 +16,29 @@
 
 #define TARGET_SAMPLE_ARGS_B(di, df) \
     seed, pos, ibuf + (di), fbuf + (df), dir
+
+// returns the density of the direction `dir` produced by `sample`
+// (the inverse angular size if the direction points to the target and zero otherwise)
+#define TARGET_PDF_RET float
+
+#define TARGET_PDF_ARGS_DEF \
+    float3 pos, float3 dir, \
+    __global const int *ibuf, \
+    __global const float *fbuf
+
+#define TARGET_PDF_ARGS \
+    pos, dir, ibuf, fbuf
+
+#define TARGET_PDF_ARGS_B(di, df) \
+    pos, dir, ibuf + (di), fbuf + (df)
+
+// `pdf` is optional, targets that implement only `sample` get this one.
+// It doesn't check that `dir` points to the target, but the scene asks for the density
+// only of the directions that hit the object the target belongs to.
+#define TARGET_PDF_FROM_SAMPLE_FN_DEF(target) \
+    TARGET_PDF_RET target##_pdf(TARGET_PDF_ARGS_DEF) { \
+        uint seed = 0; \
+        float3 d; \
+        float angle = target##_sample(&seed, pos, ibuf, fbuf, &d); \
+        return angle > 0.0f ? 1.0f/angle : 0.0f; \
+    }
@@ -0,0 +1,83 @@
+#pragma once
+
+#include "interval.h"
+
+// Torus around the `z` axis with unit major radius
+// fbuf: minor radius (1)
+
+#define TORUS_SHAPE_MAX_STEPS 256
+#define TORUS_SHAPE_EPS 1e-4f
+
+float _torus_shape_dist(float3 p, float r) {
+    float2 q = (float2)(length(p.xy) - 1.0f, p.z);
+    return length(q) - r;
+}
+
+float3 _torus_shape_norm(float3 p) {
+    float2 c = normalize(p.xy);
+    return normalize(p - (float3)(c, 0.0f));
+}
+
+SHAPE_HIT_EXT_RET torus_shape_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    float r = fbuf[0];
+    float3 d = normalize(ray.dir);
+    float dl = length(ray.dir);
+
+    // Bounding sphere
+    float R = 1.0f + r;
+    float b = dot(d, ray.start);
+    float e = b*b - dot(ray.start, ray.start) + R*R;
+    if (e < 0.0f) {
+        return false;
+    }
+    float bmax = -b + sqrt(e);
+    if (bmax <= 0.0f) {
+        return false;
+    }
+    float t = max(-b - sqrt(e), 0.0f);
+
+    // Sphere tracing of the torus distance function
+    float dist = _torus_shape_dist(ray.start + d*t, r);
+    float t_enter = t;
+    int i = 0;
+    if (dist > 0.0f) {
+        for (; i < TORUS_SHAPE_MAX_STEPS; ++i) {
+            t += dist;
+            if (t > bmax) {
+                return false;
+            }
+            dist = _torus_shape_dist(ray.start + d*t, r);
+            if (dist < TORUS_SHAPE_EPS) {
+                break;
+            }
+        }
+        if (i >= TORUS_SHAPE_MAX_STEPS) {
+            return false;
+        }
+        t_enter = t;
+        t += 2.0f*TORUS_SHAPE_EPS;
+        dist = _torus_shape_dist(ray.start + d*t, r);
+    }
+    for (; i < TORUS_SHAPE_MAX_STEPS && dist < 0.0f; ++i) {
+        t += max(-dist, TORUS_SHAPE_EPS);
+        dist = _torus_shape_dist(ray.start + d*t, r);
+    }
+
+    if (!shape_interval_hit(shape_interval_new(
+        t_enter/dl, t/dl,
+        _torus_shape_norm(ray.start + d*t_enter),
+        _torus_shape_norm(ray.start + d*t)
+    ), enter, exit, norm)) {
+        return false;
+    }
+    // Angles around the major and the minor circles
+    float3 q = shape_hit_point(ray, *enter, *exit);
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = (float2)(
+        0.5f + 0.5f*atan2(q.y, q.x)/M_PI_F,
+        0.5f + 0.5f*atan2(q.z, length(q.xy) - 1.0f)/M_PI_F
+    );
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(torus_shape)
@@ -0,0 +1,43 @@
+#pragma once
+
+#include "interval.h"
+
+// Single triangle
+// fbuf: vertices (3*3)
+
+SHAPE_HIT_EXT_RET triangle_shape_hit_ext(SHAPE_HIT_EXT_ARGS_DEF) {
+    // Moller-Trumbore intersection
+    float3 v0 = vload3(0, fbuf);
+    float3 e1 = vload3(1, fbuf) - v0;
+    float3 e2 = vload3(2, fbuf) - v0;
+    float3 p = cross(ray.dir, e2);
+    float det = dot(e1, p);
+    if (det == 0.0f) {
+        return false;
+    }
+    float idet = 1.0f/det;
+    float3 s = ray.start - v0;
+    float u = dot(s, p)*idet;
+    if (u < 0.0f || u > 1.0f) {
+        return false;
+    }
+    float3 q = cross(s, e1);
+    float v = dot(ray.dir, q)*idet;
+    if (v < 0.0f || u + v > 1.0f) {
+        return false;
+    }
+    float t = dot(e2, q)*idet;
+    float3 n = normalize(cross(e1, e2));
+    if (dot(n, ray.dir) > 0.0f) {
+        n = -n;
+    }
+    if (!shape_interval_hit(shape_interval_new(t, t, n, n), enter, exit, norm)) {
+        return false;
+    }
+    // Barycentric coordinates of the second and the third vertex
+    shape_surface_hit(surface, ray, *enter, *exit, *norm);
+    surface->uv = (float2)(u, v);
+    return true;
+}
+
+SHAPE_HIT_FROM_HIT_EXT_FN_DEF(triangle_shape)
@@ -0,0 +1,31 @@
+#pragma once
+
+#include "texture.h"
+
+// Image stored in the texture atlas, repeated outside of `[0, 1]^2`.
+// `u` goes right along the rows and `v` goes down from the top row.
+// ibuf: offset in `texture_buf`, width, height
+
+float3 image_texture_texel(__global const float *image, int2 size, int x, int y) {
+    x = ((x % size.x) + size.x) % size.x;
+    y = ((y % size.y) + size.y) % size.y;
+    return vload3(x + y*size.x, image);
+}
+
+// Bilinear interpolation of texels
+TEXTURE_LOOKUP_RET image_texture_lookup(TEXTURE_LOOKUP_ARGS_DEF) {
+    if (texture_buf == 0) {
+        return (float3)(1.0f);
+    }
+    __global const float *image = texture_buf + ibuf[0];
+    int2 size = (int2)(ibuf[1], ibuf[2]);
+    float2 p = surface.uv*convert_float2(size) - 0.5f;
+    float2 f = floor(p);
+    float2 t = p - f;
+    int x = (int)f.x, y = (int)f.y;
+    return mix(
+        mix(image_texture_texel(image, size, x, y), image_texture_texel(image, size, x + 1, y), t.x),
+        mix(image_texture_texel(image, size, x, y + 1), image_texture_texel(image, size, x + 1, y + 1), t.x),
+        t.y
+    );
+}
@@ -0,0 +1,14 @@
+#pragma once
+
+#include <clay_core/map/map.h>
+#include "texture.h"
+
+
+// Evaluates the texture at the surface point transformed back by the map,
+// so the texture is moved relative to the shape, the normal is left as is.
+#define MAP_TEXTURE_FN_DEF(map_texture, texture, map, tdi, tdf) \
+    TEXTURE_LOOKUP_RET map_texture##_lookup(TEXTURE_LOOKUP_ARGS_DEF) { \
+        ShapeSurface s = surface; \
+        s.pos = map##_abs_inv(MAP_ARGS_VB(surface.pos, tdi, tdf)); \
+        return texture##_lookup(pos, s, ibuf, fbuf, TEXTURE_ATLAS_ARGS); \
+    }
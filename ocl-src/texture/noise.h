@@ -0,0 +1,76 @@
+#pragma once
+
+#include "pattern.h"
+
+
+// Integer hash of the lattice point
+uint noise_hash(int3 c) {
+    uint h = ((uint)c.x*73856093u) ^ ((uint)c.y*19349663u) ^ ((uint)c.z*83492791u);
+    h ^= h >> 16;
+    h *= 0x7feb352du;
+    h ^= h >> 15;
+    h *= 0x846ca68bu;
+    h ^= h >> 16;
+    return h;
+}
+
+// Dot product of the offset with one of the cube edge directions (improved Perlin noise)
+float noise_grad(int3 c, float3 p) {
+    uint h = noise_hash(c) & 15;
+    float u = h < 8 ? p.x : p.y;
+    float v = h < 4 ? p.y : (h == 12 || h == 14 ? p.x : p.z);
+    return ((h & 1) ? -u : u) + ((h & 2) ? -v : v);
+}
+
+// Gradient noise with values roughly in `[-1, 1]` and unit lattice
+float noise_perlin(float3 p) {
+    float3 f = floor(p);
+    int3 c = convert_int3(f);
+    float3 t = p - f;
+    float3 s = t*t*t*(t*(6.0f*t - 15.0f) + 10.0f);
+    float n[8];
+    for (int i = 0; i < 8; ++i) {
+        int3 o = (int3)(i & 1, (i >> 1) & 1, i >> 2);
+        n[i] = noise_grad(c + o, t - convert_float3(o));
+    }
+    return mix(
+        mix(mix(n[0], n[1], s.x), mix(n[2], n[3], s.x), s.y),
+        mix(mix(n[4], n[5], s.x), mix(n[6], n[7], s.x), s.y),
+        s.z
+    );
+}
+
+// Fractal Brownian motion: octaves of doubling frequency and halving amplitude,
+// normalized to stay roughly in `[-1, 1]`
+float noise_fbm(float3 p, int octaves) {
+    float sum = 0.0f, amp = 1.0f, total = 0.0f;
+    for (int i = 0; i < octaves; ++i) {
+        sum += amp*noise_perlin(p);
+        total += amp;
+        amp *= 0.5f;
+        p *= 2.0f;
+    }
+    return sum/max(total, 1.0f);
+}
+
+// fbuf[6]: size of features, ibuf[0]: octaves
+TEXTURE_LOOKUP_RET noise_lookup(TEXTURE_LOOKUP_ARGS_DEF) {
+    float n = noise_fbm(surface.pos/fbuf[6], ibuf[0]);
+    return pattern_color(fbuf, clamp(0.5f + 0.5f*n, 0.0f, 1.0f));
+}
+
+// Veins across the x axis distorted by the noise
+// fbuf[6]: period of veins, fbuf[7]: turbulence, ibuf[0]: octaves
+TEXTURE_LOOKUP_RET marble_lookup(TEXTURE_LOOKUP_ARGS_DEF) {
+    float3 p = surface.pos/fbuf[6];
+    float x = p.x + fbuf[7]*noise_fbm(p, ibuf[0]);
+    return pattern_color(fbuf, 0.5f + 0.5f*sin(2.0f*M_PI_F*x));
+}
+
+// Rings around the z axis distorted by the noise
+// fbuf[6]: distance between rings, fbuf[7]: turbulence, ibuf[0]: octaves
+TEXTURE_LOOKUP_RET wood_lookup(TEXTURE_LOOKUP_ARGS_DEF) {
+    float3 p = surface.pos/fbuf[6];
+    float r = length(p.xy) + fbuf[7]*noise_fbm(p, ibuf[0]);
+    return pattern_color(fbuf, r - floor(r));
+}
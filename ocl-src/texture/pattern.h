@@ -0,0 +1,34 @@
+#pragma once
+
+#include "texture.h"
+
+// Two-color patterns evaluated at the point in the space of the primitive shape,
+// fbuf: first color, second color, parameters
+
+float3 pattern_color(__global const float *fbuf, float t) {
+    return mix(vload3(0, fbuf), vload3(1, fbuf), t);
+}
+
+// Shift that keeps the cell boundaries away from the coordinate planes,
+// otherwise a plane lying on a boundary would flicker between two cells.
+#define PATTERN_EPS 1e-3f
+
+// fbuf[6]: size of the cell
+TEXTURE_LOOKUP_RET checker_lookup(TEXTURE_LOOKUP_ARGS_DEF) {
+    int3 c = convert_int3(floor(surface.pos/fbuf[6] + PATTERN_EPS));
+    return pattern_color(fbuf, (float)((c.x + c.y + c.z) & 1));
+}
+
+// Stripes across the x axis
+// fbuf[6]: period, fbuf[7]: fraction of the period filled with the first color
+TEXTURE_LOOKUP_RET stripes_lookup(TEXTURE_LOOKUP_ARGS_DEF) {
+    float x = surface.pos.x/fbuf[6] + PATTERN_EPS;
+    return pattern_color(fbuf, x - floor(x) < fbuf[7] ? 0.0f : 1.0f);
+}
+
+// Linear gradient, clamped outside of the segment
+// fbuf[6..9]: point of the first color, fbuf[9..12]: point of the second color
+TEXTURE_LOOKUP_RET gradient_lookup(TEXTURE_LOOKUP_ARGS_DEF) {
+    float3 a = vload3(2, fbuf), d = vload3(3, fbuf) - a;
+    return pattern_color(fbuf, clamp(dot(surface.pos - a, d)/dot(d, d), 0.0f, 1.0f));
+}
@@ -0,0 +1,35 @@
+#pragma once
+
+#include <clay_core/shape/shape.h>
+
+// Shared storage of texture images (see `TextureBuffer`)
+#define TEXTURE_ATLAS_ARGS_DEF \
+    __global const float *texture_buf
+
+#define TEXTURE_ATLAS_ARGS \
+    texture_buf
+
+// Storage that is passed when it isn't available, textures from it are white then
+#define TEXTURE_ATLAS_ARGS_NONE \
+    (__global const float *)0
+
+// Returns the color of the surface at the point `pos`,
+// `surface` describes that point in the space of the primitive shape that was hit
+#define TEXTURE_LOOKUP_RET float3
+#define TEXTURE_LOOKUP_RET_BAD ((float3)(0.0f))
+
+#define TEXTURE_LOOKUP_ARGS_DEF \
+    float3 pos, ShapeSurface surface, \
+    __global const int *ibuf, \
+    __global const float *fbuf, \
+    TEXTURE_ATLAS_ARGS_DEF
+
+#define TEXTURE_LOOKUP_ARGS \
+    pos, surface, ibuf, fbuf, TEXTURE_ATLAS_ARGS
+
+#define TEXTURE_LOOKUP_ARGS_B(di, df) \
+    pos, surface, ibuf + (di), fbuf + (df), TEXTURE_ATLAS_ARGS
+
+// Mean of the texture color components clamped to `[0, 1]`, used to weight materials
+#define TEXTURE_WEIGHT(texture, tdi, tdf) \
+    clamp(dot(texture##_lookup(TEXTURE_LOOKUP_ARGS_B(tdi, tdf)), (float3)(1.0f/3.0f)), 0.0f, 1.0f)
@@ -0,0 +1,33 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_core/random.h>
+
+// Camera looks along `-z` axis of its orientation with `y` axis pointing up.
+// Mode is 0 for perspective projection (scale is the tangent of the half of vertical field of view)
+// and 1 for orthographic one (scale is the half of vertical size).
+
+#define VIEW_ARGS_DEF \
+    float3 view_pos, \
+    float3 view_x, float3 view_y, float3 view_z, \
+    int view_mode, float view_scale
+
+#define VIEW_ARGS \
+    view_pos, view_x, view_y, view_z, view_mode, view_scale
+
+Ray __view_emit(uint *seed, int2 pos, int2 size, VIEW_ARGS_DEF) {
+    float2 jitter = (float2)(random_uniform(seed), random_uniform(seed));
+    float2 p = 2.0f*(convert_float2(pos) + jitter)/convert_float2(size) - 1.0f;
+    p = view_scale*(float2)(p.x*size.x/size.y, -p.y);
+
+    Ray ray = ray_new();
+    if (view_mode == 0) {
+        ray.start = view_pos;
+        ray.dir = normalize(p.x*view_x + p.y*view_y - view_z);
+    } else {
+        ray.start = view_pos + p.x*view_x + p.y*view_y;
+        ray.dir = -view_z;
+    }
+    ray.color = (float3)(1.0f);
+    return ray;
+}
@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 use ocl::{self, builders::KernelBuilder};
-use crate::{Pack, Push, Context};
+use crate::{Pack, Unpack, Push, Context, layout::Field};
 
 
 /// Device buffer of abstract entities. Entity should implement `Pack`. 
@@ -70,6 +70,51 @@ impl<T: Pack> InstanceBuffer<T> {
         }
     }
     
+    fn read_raw(&self) -> crate::Result<(Vec<i32>, Vec<f32>)> {
+        let mut buffer_int = vec![0i32; self.buffer_int.len()];
+        let mut buffer_float = vec![0.0f32; self.buffer_float.len()];
+
+        self.buffer_int.cmd()
+        .offset(0)
+        .read(&mut buffer_int)
+        .enq()?;
+
+        self.buffer_float.cmd()
+        .offset(0)
+        .read(&mut buffer_float)
+        .enq()?;
+
+        Ok((buffer_int, buffer_float))
+    }
+
+    /// Reads the buffer from the device and prints the fields of each instance
+    /// according to `Pack::layout()` of the instance type.
+    pub fn dump_layout(&self) -> crate::Result<String> {
+        let (buffer_int, buffer_float) = self.read_raw()?;
+        let mut leaves = T::layout().leaves();
+        if leaves.is_empty() {
+            leaves.push(Field {
+                name: "<self>".to_string(),
+                size_int: T::size_int(), size_float: T::size_float(),
+                ..Field::default()
+            });
+        }
+        let mut lines = Vec::new();
+        for i in 0..self.count {
+            let (bi, bf) = (i*T::size_int(), i*T::size_float());
+            lines.push(format!("[{}]", i));
+            for f in leaves.iter() {
+                let (oi, of) = (bi + f.offset_int, bf + f.offset_float);
+                lines.push(format!(
+                    "  {}: int {:?}, float {:?}", f.name,
+                    &buffer_int[oi..(oi + f.size_int)],
+                    &buffer_float[of..(of + f.size_float)],
+                ));
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+
     pub fn buffer_int(&self) -> &ocl::Buffer<i32> {
         &self.buffer_int
     }
@@ -88,6 +133,19 @@ impl<T: Pack> InstanceBuffer<T> {
     }
 }
 
+impl<T: Unpack> InstanceBuffer<T> {
+    /// Reads instances back from the device.
+    pub fn read(&self) -> crate::Result<Vec<T>> {
+        let (buffer_int, buffer_float) = self.read_raw()?;
+        Ok((0..self.count).map(|i| {
+            T::unpack_from(
+                &buffer_int[i*T::size_int()..],
+                &buffer_float[i*T::size_float()..],
+            )
+        }).collect())
+    }
+}
+
 impl<T: Pack> Push for InstanceBuffer<T> {
     fn args_count() -> usize {
         3
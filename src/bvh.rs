@@ -0,0 +1,241 @@
+use crate::shape::BoundingBox;
+
+
+/// Kind of the hierarchy node.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BvhKind {
+    /// Leaf that contains items `order[first..(first + count)]`.
+    Leaf { first: usize, count: usize },
+    /// Inner node. Its left child is the next node and the right one is at the specified index.
+    Inner { right: usize },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BvhNode {
+    pub bound: BoundingBox,
+    pub kind: BvhKind,
+}
+
+/// Bounding volume hierarchy over a set of items.
+///
+/// Nodes are stored in depth-first order, so the left child always follows its parent.
+/// Leaves refer to items via the `order` permutation.
+#[derive(Clone, Debug, Default)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    order: Vec<usize>,
+}
+
+impl Bvh {
+    /// Builds the hierarchy for items with specified bounds.
+    /// Each leaf contains no more than `leaf_size` items
+    /// unless it lies at `max_depth` (the root depth is zero) where splitting stops.
+    ///
+    /// Items are split by the median of their centers along the longest axis.
+    pub fn build(bounds: &[BoundingBox], leaf_size: usize, max_depth: usize) -> Self {
+        Self::build_with(bounds, leaf_size, max_depth, Self::split_median)
+    }
+
+    /// Builds the hierarchy choosing splits by the surface area heuristic (SAH).
+    ///
+    /// It takes longer to build than `build` but the resulting hierarchy
+    /// is much faster to traverse when items differ in size and are distributed unevenly.
+    pub fn build_sah(bounds: &[BoundingBox], leaf_size: usize, max_depth: usize) -> Self {
+        Self::build_with(bounds, leaf_size, max_depth, Self::split_sah)
+    }
+
+    fn build_with<F>(bounds: &[BoundingBox], leaf_size: usize, max_depth: usize, split: F) -> Self
+    where F: Fn(&[BoundingBox], &mut [usize]) -> Option<usize> {
+        let mut order = (0..bounds.len()).collect::<Vec<_>>();
+        let mut nodes = Vec::new();
+        if !bounds.is_empty() {
+            Self::build_node(bounds, &mut order, 0, leaf_size.max(1), max_depth, &split, &mut nodes);
+        }
+        Self { nodes, order }
+    }
+
+    fn build_node<F>(
+        bounds: &[BoundingBox], order: &mut [usize], first: usize,
+        leaf_size: usize, max_depth: usize, split: &F, nodes: &mut Vec<BvhNode>,
+    ) -> usize where F: Fn(&[BoundingBox], &mut [usize]) -> Option<usize> {
+        let bound = Self::merge_bounds(bounds, order);
+        let index = nodes.len();
+        nodes.push(BvhNode { bound, kind: BvhKind::Leaf { first, count: order.len() } });
+        if order.len() <= leaf_size || max_depth == 0 {
+            return index;
+        }
+        let mid = match split(bounds, order) {
+            Some(mid) => mid,
+            None => return index,
+        };
+
+        let (left, right) = order.split_at_mut(mid);
+        Self::build_node(bounds, left, first, leaf_size, max_depth - 1, split, nodes);
+        let right = Self::build_node(bounds, right, first + mid, leaf_size, max_depth - 1, split, nodes);
+        nodes[index].kind = BvhKind::Inner { right };
+        index
+    }
+
+    fn merge_bounds(bounds: &[BoundingBox], order: &[usize]) -> BoundingBox {
+        order[1..].iter().fold(bounds[order[0]].clone(), |b, &i| b.union(&bounds[i]))
+    }
+
+    /// Sorts items by their centers along the axis.
+    ///
+    /// Non-finite centers don't break the build, they are just placed at the ends.
+    fn sort_axis(bounds: &[BoundingBox], order: &mut [usize], axis: usize) {
+        order.sort_by(|&a, &b| {
+            bounds[a].center()[axis].total_cmp(&bounds[b].center()[axis])
+        });
+    }
+
+    fn split_median(bounds: &[BoundingBox], order: &mut [usize]) -> Option<usize> {
+        let centers = BoundingBox::from_points(order.iter().map(|&i| bounds[i].center())).unwrap();
+        let size = centers.size();
+        let axis = size.imax();
+        if size[axis] <= 0.0 {
+            return None;
+        }
+        Self::sort_axis(bounds, order, axis);
+        Some(order.len()/2)
+    }
+
+    fn split_sah(bounds: &[BoundingBox], order: &mut [usize]) -> Option<usize> {
+        let n = order.len();
+        let mut best: Option<(f64, usize, usize)> = None;
+        for axis in 0..3 {
+            Self::sort_axis(bounds, order, axis);
+            // Surface areas of the left parts of each split
+            let mut left_areas = Vec::with_capacity(n);
+            let mut acc = bounds[order[0]].clone();
+            for &i in order.iter() {
+                acc = acc.union(&bounds[i]);
+                left_areas.push(acc.surface_area());
+            }
+            let mut acc = bounds[order[n - 1]].clone();
+            for mid in (1..n).rev() {
+                acc = acc.union(&bounds[order[mid]]);
+                let cost = left_areas[mid - 1]*(mid as f64) + acc.surface_area()*((n - mid) as f64);
+                if best.map_or(true, |(c, _, _)| cost < c) {
+                    best = Some((cost, axis, mid));
+                }
+            }
+        }
+        let (_, axis, mid) = best?;
+        Self::sort_axis(bounds, order, axis);
+        Some(mid)
+    }
+
+    /// Updates node bounds for the moved items keeping the hierarchy structure.
+    ///
+    /// It is much faster than the rebuilding but the hierarchy quality
+    /// degrades if items have moved a lot.
+    pub fn refit(&mut self, bounds: &[BoundingBox]) {
+        // Children always follow their parent, so they are updated first.
+        for index in (0..self.nodes.len()).rev() {
+            let bound = match self.nodes[index].kind {
+                BvhKind::Leaf { first, count } => {
+                    Self::merge_bounds(bounds, &self.order[first..(first + count)])
+                },
+                BvhKind::Inner { right } => {
+                    self.nodes[index + 1].bound.clone().union(&self.nodes[right].bound)
+                },
+            };
+            self.nodes[index].bound = bound;
+        }
+    }
+
+    /// Length of the longest path from the root to a leaf (zero for a single leaf or an empty hierarchy).
+    pub fn depth(&self) -> usize {
+        let mut depths = vec![0; self.nodes.len()];
+        let mut max = 0;
+        for (index, node) in self.nodes.iter().enumerate() {
+            if let BvhKind::Inner { right } = node.kind {
+                depths[index + 1] = depths[index] + 1;
+                depths[right] = depths[index] + 1;
+                max = max.max(depths[index] + 1);
+            }
+        }
+        max
+    }
+
+    pub fn nodes(&self) -> &[BvhNode] {
+        &self.nodes
+    }
+    /// Permutation of items, leaves refer to ranges of it.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use crate::{bvh::*, shape::BoundingBox};
+
+    fn test_bounds(shift: f64) -> Vec<BoundingBox> {
+        (0..37).map(|i| {
+            let p = Vector3::new((i % 5) as f64, (i % 7) as f64, i as f64 + shift*((i % 3) as f64));
+            BoundingBox::new(p, p + Vector3::repeat(0.5 + (i % 4) as f64))
+        }).collect()
+    }
+
+    fn assert_covers(bvh: &Bvh, bounds: &[BoundingBox], leaf_size: usize) {
+        let mut seen = vec![0; bounds.len()];
+        for node in bvh.nodes() {
+            if let BvhKind::Leaf { first, count } = node.kind {
+                assert!(count <= leaf_size);
+                for &i in &bvh.order()[first..(first + count)] {
+                    seen[i] += 1;
+                    let b = &bounds[i];
+                    assert_eq!(node.bound.clone().union(b), node.bound);
+                }
+            }
+        }
+        assert!(seen.iter().all(|&n| n == 1));
+        for (index, node) in bvh.nodes().iter().enumerate() {
+            if let BvhKind::Inner { right } = node.kind {
+                assert_eq!(node.bound.clone().union(&bvh.nodes()[index + 1].bound), node.bound);
+                assert_eq!(node.bound.clone().union(&bvh.nodes()[right].bound), node.bound);
+            }
+        }
+    }
+
+    #[test]
+    fn covers() {
+        let bounds = test_bounds(0.0);
+        assert_covers(&Bvh::build(&bounds, 4, 32), &bounds, 4);
+        assert_covers(&Bvh::build_sah(&bounds, 4, 32), &bounds, 4);
+    }
+
+    #[test]
+    fn depth() {
+        let bounds = test_bounds(0.0);
+        for &max_depth in &[0, 1, 2] {
+            let bvh = Bvh::build_sah(&bounds, 1, max_depth);
+            assert_eq!(bvh.depth(), max_depth);
+            assert_covers(&bvh, &bounds, bounds.len());
+        }
+        assert!(Bvh::build(&bounds, 1, 32).depth() > 2);
+    }
+
+    #[test]
+    fn non_finite() {
+        let mut bounds = test_bounds(0.0);
+        bounds[3].max.x = f64::NAN;
+        bounds[5].min.y = f64::NEG_INFINITY;
+        let bvh = Bvh::build_sah(&bounds, 4, 32);
+        assert_eq!(bvh.order().len(), bounds.len());
+    }
+
+    #[test]
+    fn refit() {
+        let mut bvh = Bvh::build_sah(&test_bounds(0.0), 4, 32);
+        let moved = test_bounds(10.0);
+        bvh.refit(&moved);
+        assert_covers(&bvh, &moved, 4);
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use crate::type_hash::stable_hash;
 
 
 /// An interface in OpenCL code.
@@ -7,15 +8,47 @@ pub trait Class {
     fn name() -> String;
     /// List of methods of the class.
     fn methods() -> Vec<String>;
+    /// Methods that instances may leave unimplemented.
+    ///
+    /// Such methods get the default implementation from `default_source`,
+    /// so new methods could be added to the class without breaking existing instances.
+    fn optional_methods() -> Vec<String> {
+        Vec::new()
+    }
+    /// Device code that implements the optional method for the instance via its other methods.
+    fn default_source(inst_name: &str, method: &str) -> String {
+        panic!("{} method `{}` has no default for `{}`", Self::name(), method, inst_name)
+    }
 }
 
 /// An implementation of an interface in OpenCL.
 pub trait Instance<C: Class>: Sized + 'static {
     // Class of an instance.
     //type Class: Class = C;
-    
+
     /// Associated OpenCL code that contains necessary function definition.
     fn source(cache: &mut HashSet<u64>) -> String;
     /// Name of the instance of the class (e.g. `sphere` as instance of class `shape`).
     fn inst_name() -> String;
+    /// Methods defined in `source`, all the required methods of the class by default.
+    ///
+    /// Instances that also define optional methods should list them here.
+    fn implemented_methods() -> Vec<String> {
+        let optional = C::optional_methods();
+        C::methods().into_iter().filter(|m| !optional.contains(m)).collect()
+    }
+    /// The `source` followed by default implementations of the optional methods it lacks.
+    ///
+    /// Code that calls optional methods of the instance should include this one instead of `source`.
+    fn full_source(cache: &mut HashSet<u64>) -> String {
+        let implemented = Self::implemented_methods();
+        let mut sources = vec![Self::source(cache)];
+        for method in C::optional_methods().into_iter().filter(|m| !implemented.contains(m)) {
+            let name = format!("{}_{}", Self::inst_name(), method);
+            if cache.insert(stable_hash(name.as_bytes())) {
+                sources.push(C::default_source(&Self::inst_name(), &method));
+            }
+        }
+        sources.join("\n")
+    }
 }
@@ -1,5 +1,6 @@
 use std::io;
 use std::fmt;
+use std::path::PathBuf;
 
 use ocl;
 
@@ -8,14 +9,29 @@ use ocl;
 pub enum Error {
     Io(io::Error),
     Ocl(ocl::Error),
+    /// Error in the content of a file.
+    Parse {
+        file: PathBuf,
+        line: usize,
+        message: String,
+    },
     Other(String),
 }
 
+impl Error {
+    pub fn parse<P: Into<PathBuf>, S: Into<String>>(file: P, line: usize, message: S) -> Self {
+        Error::Parse { file: file.into(), line, message: message.into() }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::Io(e) => write!(f, "Io: {:?}\n{}", e.kind(), e),
             Error::Ocl(e) => write!(f, "Ocl:\n{}", e),
+            Error::Parse { file, line, message } => {
+                write!(f, "Parse:\n{}:{}: {}", file.display(), line, message)
+            },
             Error::Other(s) => write!(f, "Other:\n{}", s),
         }
     }
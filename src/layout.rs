@@ -0,0 +1,188 @@
+use std::fmt;
+use crate::pack::*;
+
+
+/// Location of a single field of the packed object.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Field {
+    /// Field name. Nested fields are separated by dots (e.g. `material.color`).
+    pub name: String,
+    /// Offset of the field in the integer buffer.
+    pub offset_int: usize,
+    /// Offset of the field in the float buffer.
+    pub offset_float: usize,
+    /// Length of the field in the integer buffer.
+    pub size_int: usize,
+    /// Length of the field in the float buffer.
+    pub size_float: usize,
+    /// Layout of the field itself (empty if the field is opaque).
+    pub layout: Layout,
+}
+
+impl Field {
+    fn nested(&self, parent: &Field) -> Self {
+        Self {
+            name: format!("{}.{}", parent.name, self.name),
+            offset_int: parent.offset_int + self.offset_int,
+            offset_float: parent.offset_float + self.offset_float,
+            ..self.clone()
+        }
+    }
+}
+
+/// Description of where each field of the object lands in the int and float buffers.
+///
+/// The layout is reported by `Pack::layout()`.
+/// Types that are packed as a whole (e.g. numbers and vectors) have an empty layout.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Layout {
+    fields: Vec<Field>,
+}
+
+impl Layout {
+    /// Empty layout of an opaque object.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn builder() -> LayoutBuilder {
+        LayoutBuilder::new()
+    }
+
+    /// Top-level fields with offsets relative to the object.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Finds the field by its dot-separated path (e.g. `material.color`).
+    ///
+    /// Offsets of the returned field are relative to the object.
+    pub fn field(&self, path: &str) -> Option<Field> {
+        let mut parts = path.splitn(2, '.');
+        let name = parts.next()?;
+        let field = self.fields.iter().find(|f| f.name == name)?;
+        match parts.next() {
+            Some(rest) => field.layout.field(rest).map(|f| f.nested(field)),
+            None => Some(field.clone()),
+        }
+    }
+
+    /// All the fields that have no nested layout with full paths and offsets relative to the object.
+    pub fn leaves(&self) -> Vec<Field> {
+        let mut leaves = Vec::new();
+        for field in self.fields.iter() {
+            if field.layout.is_empty() {
+                leaves.push(field.clone());
+            } else {
+                leaves.extend(field.layout.leaves().iter().map(|f| f.nested(field)));
+            }
+        }
+        leaves
+    }
+
+    /// Device code that defines named offsets of top-level fields.
+    ///
+    /// For the field `map` and the prefix `pref` the following macros are defined:
+    /// `pref_MAP_DI` and `pref_MAP_DF` - offsets in the int and float buffers respectively.
+    pub fn defines(&self, prefix: &str) -> String {
+        self.fields.iter().map(|f| {
+            let name = f.name.to_uppercase();
+            [
+                format!("#define {}_{}_DI {}", prefix, name, f.offset_int),
+                format!("#define {}_{}_DF {}", prefix, name, f.offset_float),
+            ].join("\n")
+        }).collect::<Vec<_>>().join("\n")
+    }
+
+    fn fmt_indent(&self, f: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        for field in self.fields.iter() {
+            writeln!(
+                f, "{}{}: int {}..{}, float {}..{}",
+                "  ".repeat(indent), field.name,
+                field.offset_int, field.offset_int + field.size_int,
+                field.offset_float, field.offset_float + field.size_float,
+            )?;
+            field.layout.fmt_indent(f, indent + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indent(f, 0)
+    }
+}
+
+/// Builds the layout by appending fields one after another in the packing order.
+pub struct LayoutBuilder {
+    fields: Vec<Field>,
+    offset_int: usize,
+    offset_float: usize,
+}
+
+impl LayoutBuilder {
+    pub fn new() -> Self {
+        Self { fields: Vec::new(), offset_int: 0, offset_float: 0 }
+    }
+
+    fn push(mut self, name: &str, size_int: usize, size_float: usize, layout: Layout) -> Self {
+        self.fields.push(Field {
+            name: name.to_string(),
+            offset_int: self.offset_int,
+            offset_float: self.offset_float,
+            size_int, size_float,
+            layout,
+        });
+        self.offset_int += size_int;
+        self.offset_float += size_float;
+        self
+    }
+
+    /// Appends a field packed via `Pack`.
+    pub fn field<T: Pack>(self, name: &str) -> Self {
+        self.push(name, T::size_int(), T::size_float(), T::layout())
+    }
+    /// Appends a field packed via `PackInt`.
+    pub fn field_int<T: PackInt>(self, name: &str) -> Self {
+        self.push(name, T::size(), 0, Layout::new())
+    }
+    /// Appends a field packed via `PackFloat`.
+    pub fn field_float<T: PackFloat>(self, name: &str) -> Self {
+        self.push(name, 0, T::size(), Layout::new())
+    }
+
+    pub fn build(self) -> Layout {
+        Layout { fields: self.fields }
+    }
+}
+
+impl Default for LayoutBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pack::*,
+        shape::test::TestShape,
+        material::{Colored, test::TestMaterial},
+        object::Covered,
+    };
+
+    #[test]
+    fn nested() {
+        type T = (i32, Covered<TestShape<i32>, Colored<TestMaterial<i32>>>);
+        let layout = T::layout();
+        let color = layout.field("1.material.color").unwrap();
+        assert_eq!((color.offset_int, color.offset_float), (1, 0));
+        assert_eq!((color.size_int, color.size_float), (0, 3));
+
+        let names = layout.leaves().into_iter().map(|f| f.name).collect::<Vec<_>>();
+        assert_eq!(names, ["0", "1.shape", "1.material.material", "1.material.color"]);
+    }
+}
@@ -1,5 +1,8 @@
 //! Core functionality for [Clay project](https://clay-rs.github.io/).
 
+// Allows derive macros to refer to `::clay_core` from inside this crate.
+extern crate self as clay_core;
+
 /// Own error type.
 pub mod error;
 /// Own result type.
@@ -7,6 +10,10 @@ pub mod result;
 
 /// Serialization of entities for storing them on the device.
 pub mod pack;
+/// Deserialization of entities packed for the device.
+pub mod unpack;
+/// Introspection of the packed data layout.
+pub mod layout;
 /// Representation of entities that could be stored in the device.
 pub mod store;
 /// Pushing arguments to the device kernel.
@@ -25,8 +32,12 @@ pub mod map;
 pub mod shape;
 /// Material of an object.
 pub mod material;
+/// Texture of an object surface.
+pub mod texture;
 /// Object to render.
 pub mod object;
+/// Bounding volume hierarchy built on the host.
+pub mod bvh;
 
 /// Scene to be rendered.
 pub mod scene;
@@ -43,12 +54,17 @@ pub mod buffer;
 /// Functionality for rendering pipeline.
 pub mod process;
 
+/// Loading meshes, materials and scenes from files.
+#[cfg(any(feature = "loader", feature = "gltf"))]
+pub mod loader;
+
 /// Loading the device OpenCL source code.
 pub mod source;
 
 /// Reexport of the basic traits.
 pub mod prelude {
     pub use crate::pack::*;
+    pub use crate::unpack::*;
     pub use crate::push::*;
     pub use crate::store::*;
     pub use crate::type_hash::*;
@@ -0,0 +1,360 @@
+use std::path::Path;
+use nalgebra::{Vector2, Vector3, Vector4, Matrix3, Matrix4};
+use gltf::{self, image::Format, camera::Projection as GltfProjection};
+use crate::{
+    Error,
+    map::Affine,
+    shape::{Shape, ShapeMapper, mesh::*},
+    material::Material,
+    texture::{TextureStorage, ImageTexture},
+    object::{Covered, ObjectMapper},
+    view::{Camera, Projection},
+    loader::{TextureData, ModelMaterial},
+};
+
+
+/// PBR metallic-roughness material parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PbrMaterialInfo {
+    pub name: String,
+    /// Base color with alpha.
+    pub base_color: Vector4<f64>,
+    pub metallic: f64,
+    pub roughness: f64,
+    pub emission: Vector3<f64>,
+    /// Indices of textures in `GltfScene::textures`.
+    pub base_color_texture: Option<usize>,
+    pub metallic_roughness_texture: Option<usize>,
+    pub emissive_texture: Option<usize>,
+}
+
+impl PbrMaterialInfo {
+    /// Built-in material that approximates these parameters.
+    ///
+    /// Metals become mirrors (glossy if they are rough) and translucent materials become glass.
+    pub fn material(&self) -> ModelMaterial {
+        let color = self.base_color.xyz();
+        let (diffuse, specular) = if self.metallic >= 0.5 {
+            (Vector3::zeros(), color)
+        } else {
+            (color, Vector3::zeros())
+        };
+        ModelMaterial::choose(
+            diffuse, specular, self.emission,
+            self.roughness, 1.5, self.base_color.w,
+        )
+    }
+
+    /// Material with the base color texture applied.
+    ///
+    /// `textures` are images of `GltfScene::textures` added to the storage (see `GltfScene::add_textures`).
+    pub fn textured_material(&self, textures: &[ImageTexture]) -> ModelMaterial {
+        let material = self.material();
+        match self.base_color_texture {
+            Some(i) => material.textured(textures[i].clone()),
+            None => material,
+        }
+    }
+}
+
+/// Placement of a mesh in the scene.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SceneInstance {
+    /// Name of the node.
+    pub name: String,
+    /// Index of the mesh in `GltfScene::meshes`.
+    pub mesh: usize,
+    /// Index of the material in `GltfScene::materials`.
+    pub material: Option<usize>,
+    /// Transformation of the node including all its ancestors.
+    pub map: Affine,
+}
+
+impl SceneInstance {
+    /// Mesh shape placed in the scene.
+    pub fn shape(&self, meshes: &[Mesh]) -> ShapeMapper<Mesh, Affine> {
+        meshes[self.mesh].clone().map(self.map.clone())
+    }
+    /// Mesh covered with the material and placed in the scene.
+    pub fn object<M: Material>(&self, meshes: &[Mesh], material: M) -> ObjectMapper<Covered<Mesh, M>, Affine> {
+        ObjectMapper::new(meshes[self.mesh].clone().cover(material), self.map.clone())
+    }
+}
+
+/// Scene loaded from glTF file.
+///
+/// Node hierarchy is flattened, so each instance has the single affine map.
+#[derive(Clone, Debug, Default)]
+pub struct GltfScene {
+    /// Shapes of mesh primitives.
+    pub meshes: Vec<Mesh>,
+    pub instances: Vec<SceneInstance>,
+    pub materials: Vec<PbrMaterialInfo>,
+    pub textures: Vec<TextureData>,
+    pub cameras: Vec<Camera>,
+}
+
+impl GltfScene {
+    /// Adds all the texture images to the storage and returns corresponding textures.
+    pub fn add_textures(&self, storage: &mut TextureStorage) -> crate::Result<Vec<ImageTexture>> {
+        self.textures.iter().map(|t| t.add_to(storage)).collect()
+    }
+}
+
+fn gltf_error(path: &Path, e: gltf::Error) -> Error {
+    Error::Other(format!("{}: {}", path.display(), e))
+}
+
+fn to_vector3(v: [f32; 3]) -> Vector3<f64> {
+    Vector3::new(v[0] as f64, v[1] as f64, v[2] as f64)
+}
+
+/// Converts the image to 8-bit RGBA.
+fn convert_image(path: &Path, image: &gltf::image::Data) -> crate::Result<TextureData> {
+    let (channels, depth) = match image.format {
+        Format::R8 => (1, 1),
+        Format::R8G8 => (2, 1),
+        Format::R8G8B8 => (3, 1),
+        Format::R8G8B8A8 => (4, 1),
+        Format::R16 => (1, 2),
+        Format::R16G16 => (2, 2),
+        Format::R16G16B16 => (3, 2),
+        Format::R16G16B16A16 => (4, 2),
+        Format::R32G32B32FLOAT => (3, 4),
+        Format::R32G32B32A32FLOAT => (4, 4),
+    };
+    let value = |b: &[u8]| -> u8 {
+        match depth {
+            1 => b[0],
+            2 => (u16::from_le_bytes([b[0], b[1]]) >> 8) as u8,
+            _ => (255.0*f32::from_le_bytes([b[0], b[1], b[2], b[3]]).max(0.0).min(1.0)).round() as u8,
+        }
+    };
+    let pixels = image.pixels.chunks(channels*depth).map(|p| {
+        let c = (0..channels).map(|i| value(&p[(i*depth)..((i + 1)*depth)])).collect::<Vec<_>>();
+        match channels {
+            1 => [c[0], c[0], c[0], 255],
+            2 => [c[0], c[0], c[0], c[1]],
+            3 => [c[0], c[1], c[2], 255],
+            _ => [c[0], c[1], c[2], c[3]],
+        }
+    }).collect::<Vec<_>>();
+    let (width, height) = (image.width as usize, image.height as usize);
+    if pixels.len() != width*height {
+        return Err(Error::Other(format!("{}: bad image size", path.display())));
+    }
+    Ok(TextureData { width, height, pixels })
+}
+
+fn convert_material(material: &gltf::Material) -> PbrMaterialInfo {
+    let pbr = material.pbr_metallic_roughness();
+    let c = pbr.base_color_factor();
+    PbrMaterialInfo {
+        name: material.name().unwrap_or("").to_string(),
+        base_color: Vector4::new(c[0] as f64, c[1] as f64, c[2] as f64, c[3] as f64),
+        metallic: pbr.metallic_factor() as f64,
+        roughness: pbr.roughness_factor() as f64,
+        emission: to_vector3(material.emissive_factor()),
+        base_color_texture: pbr.base_color_texture().map(|t| t.texture().source().index()),
+        metallic_roughness_texture: pbr.metallic_roughness_texture().map(|t| t.texture().source().index()),
+        emissive_texture: material.emissive_texture().map(|t| t.texture().source().index()),
+    }
+}
+
+fn convert_camera(camera: &gltf::Camera, map: &Affine) -> Camera {
+    // Scale of the node is removed from the orientation.
+    let l = map.linear();
+    let x = l.column(0).normalize();
+    let y = (l.column(1) - x*x.dot(&l.column(1))).normalize();
+    let z = x.cross(&y);
+    let projection = match camera.projection() {
+        GltfProjection::Perspective(p) => Projection::Perspective { yfov: p.yfov() as f64 },
+        GltfProjection::Orthographic(o) => Projection::Orthographic { ymag: o.ymag() as f64 },
+    };
+    Camera::new(*map.shift(), Matrix3::from_columns(&[x, y, z]), projection)
+}
+
+/// Loads the default scene (or the first one) from `.gltf` or `.glb` file.
+///
+/// External buffers and images are loaded from local files next to the scene file.
+/// Geometry of mesh primitives is added to the storage, only triangle primitives are supported.
+pub fn load_gltf<P: AsRef<Path>>(path: P, storage: &mut MeshStorage) -> crate::Result<GltfScene> {
+    let path = path.as_ref();
+    let (document, buffers, images) = gltf::import(path).map_err(|e| gltf_error(path, e))?;
+    let mut scene = GltfScene::default();
+
+    scene.textures = images.iter().map(|i| convert_image(path, i)).collect::<crate::Result<_>>()?;
+    scene.materials = document.materials().map(|m| convert_material(&m)).collect();
+
+    // Shape indices and materials of primitives of each mesh
+    let mut primitives = Vec::new();
+    for mesh in document.meshes() {
+        let mut list = Vec::new();
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+            let reader = primitive.reader(|b| Some(&buffers[b.index()]));
+            let vertices = match reader.read_positions() {
+                Some(iter) => iter.map(to_vector3).collect::<Vec<_>>(),
+                None => continue,
+            };
+            let indices = match reader.read_indices() {
+                Some(iter) => iter.into_u32().map(|i| i as usize).collect::<Vec<_>>(),
+                None => (0..vertices.len()).collect(),
+            };
+            let triangles = indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect();
+            let data = match reader.read_normals() {
+                Some(iter) => MeshData::with_normals(vertices, iter.map(to_vector3).collect(), triangles),
+                None => MeshData::new(vertices, triangles),
+            }.and_then(|data| match reader.read_tex_coords(0) {
+                Some(iter) => data.with_uvs(
+                    iter.into_f32().map(|[u, v]| Vector2::new(u as f64, v as f64)).collect()
+                ),
+                None => Ok(data),
+            }).map_err(|e| Error::Other(format!(
+                "{}: mesh '{}': {}", path.display(), mesh.name().unwrap_or(""), e,
+            )))?;
+            scene.meshes.push(storage.add(&data)?);
+            list.push((scene.meshes.len() - 1, primitive.material().index()));
+        }
+        primitives.push(list);
+    }
+
+    let root = match document.default_scene().or_else(|| document.scenes().next()) {
+        Some(s) => s,
+        None => return Ok(scene),
+    };
+    let mut stack = root.nodes().map(|n| (n, Affine::identity())).collect::<Vec<_>>();
+    while let Some((node, parent)) = stack.pop() {
+        let local = node.transform().matrix();
+        let local = Affine::from_homogeneous(&Matrix4::from_fn(|r, c| local[c][r] as f64))?;
+        let map = local.then(&parent);
+        if let Some(mesh) = node.mesh() {
+            for &(index, material) in primitives[mesh.index()].iter() {
+                scene.instances.push(SceneInstance {
+                    name: node.name().unwrap_or("").to_string(),
+                    mesh: index, material,
+                    map: map.clone(),
+                });
+            }
+        }
+        if let Some(camera) = node.camera() {
+            scene.cameras.push(convert_camera(&camera, &map));
+        }
+        stack.extend(node.children().map(|c| (c, map.clone())));
+    }
+
+    Ok(scene)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use nalgebra::{Vector3, Vector4};
+    use crate::{
+        map::Map,
+        shape::mesh::MeshStorage,
+        texture::{TextureStorage, ImageTexture},
+        view::Projection,
+        loader::*,
+    };
+
+    #[test]
+    fn hierarchy() {
+        // Single triangle in the data URI buffer, two nested nodes and a camera
+        let mut bytes = Vec::new();
+        for x in [0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0].iter() {
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+        let buffer = format!(
+            "data:application/octet-stream;base64,{}",
+            base64_encode(&bytes),
+        );
+        let json = format!(r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0, 2] }}],
+            "nodes": [
+                {{ "name": "parent", "translation": [1, 0, 0], "children": [1] }},
+                {{ "name": "child", "scale": [2, 2, 2], "mesh": 0 }},
+                {{ "camera": 0, "translation": [0, 0, 5] }}
+            ],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "material": 0 }}] }}],
+            "materials": [{{ "pbrMetallicRoughness": {{ "metallicFactor": 0.25 }} }}],
+            "cameras": [{{ "type": "perspective", "perspective": {{ "yfov": 0.8, "znear": 0.1 }} }}],
+            "accessors": [{{
+                "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                "min": [0, 0, 0], "max": [1, 1, 0]
+            }}],
+            "bufferViews": [{{ "buffer": 0, "byteLength": 36 }}],
+            "buffers": [{{ "byteLength": 36, "uri": "{}" }}]
+        }}"#, buffer);
+        let path = std::env::temp_dir().join("clay_core_test_hierarchy.gltf");
+        fs::write(&path, json).unwrap();
+
+        let mut storage = MeshStorage::new();
+        let scene = load_gltf(&path, &mut storage).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(scene.meshes.len(), 1);
+        assert_eq!(scene.instances.len(), 1);
+        let instance = &scene.instances[0];
+        assert_eq!(instance.material, Some(0));
+        let p = instance.map.apply_abs(Vector3::new(1.0, 0.0, 0.0));
+        assert!((p - Vector3::new(3.0, 0.0, 0.0)).norm() < 1e-6);
+        assert!((scene.materials[0].metallic - 0.25).abs() < 1e-6);
+        match scene.materials[0].material() {
+            ModelMaterial::Diffuse(_) => (),
+            _ => panic!(),
+        }
+
+        assert_eq!(scene.cameras.len(), 1);
+        assert!((scene.cameras[0].pos - Vector3::new(0.0, 0.0, 5.0)).norm() < 1e-6);
+        match scene.cameras[0].projection {
+            Projection::Perspective { yfov } => assert!((yfov - 0.8).abs() < 1e-6),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn textured() {
+        let scene = GltfScene {
+            textures: vec![TextureData { width: 1, height: 2, pixels: vec![[255; 4], [0, 0, 0, 255]] }],
+            ..GltfScene::default()
+        };
+        let mut storage = TextureStorage::new();
+        let textures = scene.add_textures(&mut storage).unwrap();
+        assert_eq!(textures[0], ImageTexture { offset: 0, width: 1, height: 2 });
+
+        let mut info = PbrMaterialInfo {
+            name: "test".to_string(),
+            base_color: Vector4::repeat(1.0),
+            metallic: 0.0,
+            roughness: 1.0,
+            emission: Vector3::zeros(),
+            base_color_texture: Some(0),
+            metallic_roughness_texture: None,
+            emissive_texture: None,
+        };
+        match info.textured_material(&textures) {
+            ModelMaterial::TexturedDiffuse(m) => assert_eq!(m.texture, textures[0]),
+            _ => panic!(),
+        }
+        info.base_color_texture = None;
+        match info.textured_material(&textures) {
+            ModelMaterial::Diffuse(_) => (),
+            _ => panic!(),
+        }
+    }
+
+    fn base64_encode(bytes: &[u8]) -> String {
+        const TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        bytes.chunks(3).flat_map(|c| {
+            let n = (c[0] as u32) << 16 | (*c.get(1).unwrap_or(&0) as u32) << 8 | *c.get(2).unwrap_or(&0) as u32;
+            (0..4).map(move |i| {
+                if i > c.len() { '=' } else { TABLE[(n >> (18 - 6*i) & 63) as usize] as char }
+            })
+        }).collect()
+    }
+}
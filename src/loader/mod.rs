@@ -0,0 +1,19 @@
+//! Loading of meshes, materials and scenes from files.
+//!
+//! Supported formats are Wavefront OBJ (with MTL materials) and PLY (ASCII and binary)
+//! behind the `loader` feature, and glTF 2.0 behind the `gltf` feature.
+
+mod model;
+pub use model::*;
+#[cfg(feature = "loader")]
+mod obj;
+#[cfg(feature = "loader")]
+pub use obj::*;
+#[cfg(feature = "loader")]
+mod ply;
+#[cfg(feature = "loader")]
+pub use ply::*;
+#[cfg(feature = "gltf")]
+mod gltf_scene;
+#[cfg(feature = "gltf")]
+pub use gltf_scene::*;
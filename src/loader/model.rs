@@ -0,0 +1,207 @@
+use nalgebra::Vector3;
+use crate::{
+    shape::mesh::{MeshData, MeshStorage, Mesh},
+    material::*,
+    texture::{TextureStorage, ImageTexture, srgb_to_linear},
+    material_select,
+};
+
+
+/// Material parameters read from a file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaterialInfo {
+    pub name: String,
+    /// Diffuse color (`Kd`).
+    pub diffuse: Vector3<f64>,
+    /// Specular color (`Ks`).
+    pub specular: Vector3<f64>,
+    /// Emitted light (`Ke`).
+    pub emission: Vector3<f64>,
+    /// Specular exponent (`Ns`).
+    pub shininess: f64,
+    /// Index of refraction (`Ni`).
+    pub ior: f64,
+    /// Opacity (`d` or `1 - Tr`).
+    pub opacity: f64,
+    /// Path to the diffuse texture (`map_Kd`) as written in the file.
+    /// The loaded image is applied by `ModelMaterial::textured` to meshes with texture coordinates.
+    pub diffuse_map: Option<String>,
+}
+
+impl MaterialInfo {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            diffuse: Vector3::repeat(0.8),
+            specular: Vector3::zeros(),
+            emission: Vector3::zeros(),
+            shininess: 0.0,
+            ior: 1.0,
+            opacity: 1.0,
+            diffuse_map: None,
+        }
+    }
+}
+
+// Built-in material that is the closest to the material read from a file.
+material_select!(
+    ModelMaterial {
+        Diffuse(TD = Colored<Diffuse>),
+        TexturedDiffuse(TX = Textured<Colored<Diffuse>>),
+        Reflective(TR = Colored<Reflective>),
+        Refractive(TT = Colored<Refractive>),
+        Glossy(TG = Colored<Glossy>),
+        Luminous(TL = Luminous),
+    }
+);
+
+fn max_component(v: &Vector3<f64>) -> f64 {
+    v.iter().fold(0.0, |a, b| f64::max(a, *b))
+}
+
+impl ModelMaterial {
+    /// Chooses the material by the dominant component of the parameters,
+    /// so mixtures (e.g. diffuse surface with the specular highlight) are simplified.
+    pub(crate) fn choose(
+        color: Vector3<f64>, specular: Vector3<f64>, emission: Vector3<f64>,
+        roughness: f64, ior: f64, opacity: f64,
+    ) -> Self {
+        if max_component(&emission) > 0.0 {
+            ModelMaterial::Luminous(Luminous::new(emission))
+        } else if opacity < 1.0 {
+            ModelMaterial::Refractive(Refractive::new(ior).color_with(Vector3::repeat(1.0)))
+        } else if max_component(&specular) > max_component(&color) {
+            if roughness < 1e-2 {
+                ModelMaterial::Reflective(Reflective::new().color_with(specular))
+            } else {
+                ModelMaterial::Glossy(Glossy::new(roughness).color_with(specular))
+            }
+        } else {
+            ModelMaterial::Diffuse(Diffuse::new().color_with(color))
+        }
+    }
+
+    /// Modulates the color of the diffuse material by the texture, other materials are left untextured.
+    pub fn textured(self, texture: ImageTexture) -> Self {
+        match self {
+            ModelMaterial::Diffuse(m) => ModelMaterial::TexturedDiffuse(m.texture_with(texture)),
+            m => m,
+        }
+    }
+}
+
+impl MaterialInfo {
+    /// Built-in material that approximates these parameters.
+    ///
+    /// The specular exponent is converted to the roughness as `sqrt(2/(Ns + 2))`.
+    pub fn material(&self) -> ModelMaterial {
+        let ior = if self.ior > 1.0 { self.ior } else { 1.5 };
+        ModelMaterial::choose(
+            self.diffuse, self.specular, self.emission,
+            (2.0/(self.shininess.max(0.0) + 2.0)).sqrt(),
+            ior, self.opacity,
+        )
+    }
+}
+
+/// Texture image with 8-bit RGBA pixels stored row by row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureData {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl TextureData {
+    /// Adds the image to the storage converting sRGB colors to linear ones, alpha is dropped.
+    pub fn add_to(&self, storage: &mut TextureStorage) -> crate::Result<ImageTexture> {
+        let pixels = self.pixels.iter().map(|p| {
+            [srgb_to_linear(p[0]), srgb_to_linear(p[1]), srgb_to_linear(p[2])]
+        }).collect::<Vec<_>>();
+        storage.add(self.width, self.height, &pixels)
+    }
+}
+
+/// Named mesh with an optional material.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelMesh {
+    pub name: String,
+    pub data: MeshData,
+    /// Index of the material in `Model::materials`.
+    pub material: Option<usize>,
+}
+
+/// Set of meshes and materials loaded from a file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Model {
+    pub meshes: Vec<ModelMesh>,
+    pub materials: Vec<MaterialInfo>,
+}
+
+impl Model {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds all the meshes to the storage and returns corresponding shapes.
+    pub fn add_to(&self, storage: &mut MeshStorage) -> crate::Result<Vec<Mesh>> {
+        self.meshes.iter().map(|m| storage.add(&m.data)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use crate::{
+        texture::ImageTexture,
+        loader::*,
+    };
+
+    #[test]
+    fn material() {
+        let mut info = MaterialInfo::new("test");
+        match info.material() {
+            ModelMaterial::Diffuse(m) => assert_eq!(m.color, Vector3::repeat(0.8)),
+            _ => panic!(),
+        }
+        info.specular = Vector3::repeat(0.9);
+        info.shininess = 1e6;
+        match info.material() {
+            ModelMaterial::Reflective(_) => (),
+            _ => panic!(),
+        }
+        info.shininess = 30.0;
+        match info.material() {
+            ModelMaterial::Glossy(m) => assert!((m.material.roughness - 0.25).abs() < 1e-9),
+            _ => panic!(),
+        }
+        info.opacity = 0.5;
+        match info.material() {
+            ModelMaterial::Refractive(m) => assert_eq!(m.material.ior, 1.5),
+            _ => panic!(),
+        }
+        info.emission = Vector3::new(0.0, 2.0, 0.0);
+        match info.material() {
+            ModelMaterial::Luminous(m) => assert_eq!(m.emission.y, 2.0),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn textured() {
+        let texture = ImageTexture { offset: 0, width: 2, height: 2 };
+        match MaterialInfo::new("test").material().textured(texture.clone()) {
+            ModelMaterial::TexturedDiffuse(m) => {
+                assert_eq!(m.material.color, Vector3::repeat(0.8));
+                assert_eq!(m.texture, texture);
+            },
+            _ => panic!(),
+        }
+        let mut info = MaterialInfo::new("test");
+        info.emission = Vector3::repeat(1.0);
+        match info.material().textured(texture) {
+            ModelMaterial::Luminous(_) => (),
+            _ => panic!(),
+        }
+    }
+}
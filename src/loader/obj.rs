@@ -0,0 +1,318 @@
+use std::{
+    fs,
+    path::Path,
+    collections::HashMap,
+};
+use nalgebra::{Vector2, Vector3};
+use crate::{
+    Error,
+    shape::mesh::MeshData,
+    loader::*,
+};
+
+
+/// Loads meshes from Wavefront OBJ file along with materials from referenced MTL files.
+///
+/// Each object, group or material switch starts a new mesh.
+/// Polygons are split into triangles.
+/// If some faces of the mesh have no normals then smooth normals are computed for the whole mesh,
+/// texture coordinates are kept only if all faces of the mesh have them.
+/// The `v` coordinate is flipped, so that it goes down from the top row of the image like in `ImageTexture`.
+pub fn load_obj<P: AsRef<Path>>(path: P) -> crate::Result<Model> {
+    let path = path.as_ref();
+    parse_obj(&fs::read_to_string(path)?, path)
+}
+
+/// Loads materials from MTL file.
+pub fn load_mtl<P: AsRef<Path>>(path: P) -> crate::Result<Vec<MaterialInfo>> {
+    let path = path.as_ref();
+    parse_mtl(&fs::read_to_string(path)?, path)
+}
+
+/// Splits the line into keyword and arguments skipping comments.
+fn split_line(line: &str) -> Option<(&str, Vec<&str>)> {
+    let line = match line.find('#') {
+        Some(i) => &line[..i],
+        None => line,
+    };
+    let mut words = line.split_whitespace();
+    words.next().map(|k| (k, words.collect()))
+}
+
+fn parse_floats(args: &[&str], count: usize, path: &Path, line: usize) -> crate::Result<Vec<f64>> {
+    if args.len() < count {
+        return Err(Error::parse(path, line, format!(
+            "expected {} numbers, found {}", count, args.len(),
+        )));
+    }
+    args[..count].iter().map(|a| {
+        a.parse::<f64>().map_err(|_| Error::parse(path, line, format!("bad number '{}'", a)))
+    }).collect()
+}
+
+fn parse_vector(args: &[&str], path: &Path, line: usize) -> crate::Result<Vector3<f64>> {
+    let v = parse_floats(args, 3, path, line)?;
+    Ok(Vector3::new(v[0], v[1], v[2]))
+}
+
+/// Converts OBJ index (1-based or negative relative) to 0-based one.
+fn parse_index(s: &str, len: usize, path: &Path, line: usize) -> crate::Result<usize> {
+    let i = s.parse::<i64>().map_err(|_| Error::parse(path, line, format!("bad index '{}'", s)))?;
+    let r = if i > 0 { i - 1 } else { len as i64 + i };
+    if i == 0 || r < 0 || r >= len as i64 {
+        return Err(Error::parse(path, line, format!("index {} is out of range", i)));
+    }
+    Ok(r as usize)
+}
+
+/// Polygon corner: position index, optional texture coordinates index and optional normal index.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+struct Group {
+    name: String,
+    material: Option<usize>,
+    triangles: Vec<[Corner; 3]>,
+}
+
+impl Group {
+    fn new(name: String, material: Option<usize>) -> Self {
+        Self { name, material, triangles: Vec::new() }
+    }
+
+    fn build(
+        self, positions: &[Vector3<f64>], uvs: &[Vector2<f64>], normals: &[Vector3<f64>],
+    ) -> crate::Result<ModelMesh> {
+        let has_uvs = self.triangles.iter().flat_map(|t| t.iter()).all(|c| c.1.is_some());
+        let has_normals = self.triangles.iter().flat_map(|t| t.iter()).all(|c| c.2.is_some());
+        let mut map = HashMap::new();
+        let (mut vs, mut ts, mut ns) = (Vec::new(), Vec::new(), Vec::new());
+        let triangles = self.triangles.iter().map(|t| {
+            let mut tri = [0; 3];
+            for (k, &(p, t, n)) in t.iter().enumerate() {
+                let key = (p, if has_uvs { t } else { None }, if has_normals { n } else { None });
+                tri[k] = *map.entry(key).or_insert_with(|| {
+                    vs.push(positions[p]);
+                    if let Some(t) = key.1 {
+                        ts.push(uvs[t]);
+                    }
+                    if let Some(n) = key.2 {
+                        ns.push(normals[n]);
+                    }
+                    vs.len() - 1
+                });
+            }
+            tri
+        }).collect();
+        let data = if has_normals {
+            MeshData::with_normals(vs, ns, triangles)?
+        } else {
+            MeshData::new(vs, triangles)?
+        };
+        let data = if has_uvs { data.with_uvs(ts)? } else { data };
+        Ok(ModelMesh { name: self.name, data, material: self.material })
+    }
+}
+
+/// Parses the content of OBJ file. The path is used for error messages and to find MTL files.
+pub fn parse_obj(text: &str, path: &Path) -> crate::Result<Model> {
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut model = Model::new();
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    let mut normals = Vec::new();
+    let mut group = Group::new(String::new(), None);
+
+    for (i, text_line) in text.lines().enumerate() {
+        let line = i + 1;
+        let (keyword, args) = match split_line(text_line) {
+            Some(x) => x,
+            None => continue,
+        };
+        match keyword {
+            "v" => positions.push(parse_vector(&args, path, line)?),
+            "vt" => {
+                // The third coordinate is optional and the second one may be omitted too
+                let t = parse_floats(&args, args.len().max(1).min(2), path, line)?;
+                uvs.push(Vector2::new(t[0], 1.0 - t.get(1).cloned().unwrap_or(0.0)));
+            },
+            "vn" => normals.push(parse_vector(&args, path, line)?),
+            "f" => {
+                if args.len() < 3 {
+                    return Err(Error::parse(path, line, "face has less than 3 vertices"));
+                }
+                let corners = args.iter().map(|a| {
+                    let mut parts = a.split('/');
+                    let p = parse_index(parts.next().unwrap(), positions.len(), path, line)?;
+                    let t = match parts.next() {
+                        Some(s) if !s.is_empty() => Some(parse_index(s, uvs.len(), path, line)?),
+                        _ => None,
+                    };
+                    let n = match parts.next() {
+                        Some(s) if !s.is_empty() => Some(parse_index(s, normals.len(), path, line)?),
+                        _ => None,
+                    };
+                    Ok((p, t, n))
+                }).collect::<crate::Result<Vec<Corner>>>()?;
+                for k in 1..(corners.len() - 1) {
+                    group.triangles.push([corners[0], corners[k], corners[k + 1]]);
+                }
+            },
+            "o" | "g" | "usemtl" => {
+                let (name, material) = if keyword == "usemtl" {
+                    let name = args.join(" ");
+                    let index = model.materials.iter().position(|m| m.name == name)
+                    .ok_or_else(|| Error::parse(path, line, format!("unknown material '{}'", name)))?;
+                    (group.name.clone(), Some(index))
+                } else {
+                    (args.join(" "), group.material)
+                };
+                let prev = std::mem::replace(&mut group, Group::new(name, material));
+                if !prev.triangles.is_empty() {
+                    model.meshes.push(prev.build(&positions, &uvs, &normals)?);
+                }
+            },
+            "mtllib" => {
+                for file in args.iter() {
+                    model.materials.extend(load_mtl(dir.join(file))?);
+                }
+            },
+            // Smoothing groups, lines and points aren't used
+            _ => (),
+        }
+    }
+    if !group.triangles.is_empty() {
+        model.meshes.push(group.build(&positions, &uvs, &normals)?);
+    }
+    Ok(model)
+}
+
+/// Parses the content of MTL file. The path is used for error messages.
+pub fn parse_mtl(text: &str, path: &Path) -> crate::Result<Vec<MaterialInfo>> {
+    let mut materials: Vec<MaterialInfo> = Vec::new();
+    for (i, text_line) in text.lines().enumerate() {
+        let line = i + 1;
+        let (keyword, args) = match split_line(text_line) {
+            Some(x) => x,
+            None => continue,
+        };
+        if keyword == "newmtl" {
+            materials.push(MaterialInfo::new(&args.join(" ")));
+            continue;
+        }
+        let material = match materials.last_mut() {
+            Some(m) => m,
+            None => return Err(Error::parse(path, line, format!("'{}' before 'newmtl'", keyword))),
+        };
+        match keyword {
+            "Kd" => material.diffuse = parse_vector(&args, path, line)?,
+            "Ks" => material.specular = parse_vector(&args, path, line)?,
+            "Ke" => material.emission = parse_vector(&args, path, line)?,
+            "Ns" => material.shininess = parse_floats(&args, 1, path, line)?[0],
+            "Ni" => material.ior = parse_floats(&args, 1, path, line)?[0],
+            "d" => material.opacity = parse_floats(&args, 1, path, line)?[0],
+            "Tr" => material.opacity = 1.0 - parse_floats(&args, 1, path, line)?[0],
+            "map_Kd" => match args.last() {
+                Some(file) => material.diffuse_map = Some(file.to_string()),
+                None => return Err(Error::parse(path, line, "missing texture file")),
+            },
+            _ => (),
+        }
+    }
+    Ok(materials)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use nalgebra::Vector2;
+    use crate::{Error, loader::*};
+
+    #[test]
+    fn quad() {
+        let text = "
+            # comment
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            vn 0 0 1
+            o quad
+            f 1//1 2//1 3//1 -1//1
+            g second
+            f 1 2 3
+        ";
+        let model = parse_obj(text, Path::new("quad.obj")).unwrap();
+        assert_eq!(model.meshes.len(), 2);
+        assert_eq!(model.meshes[0].name, "quad");
+        assert_eq!(model.meshes[0].data.triangles.len(), 2);
+        assert_eq!(model.meshes[0].data.vertices.len(), 4);
+        assert_eq!(model.meshes[1].data.vertices.len(), 3);
+    }
+
+    #[test]
+    fn textured_quad() {
+        let quad = "
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            vt 0 0
+            vt 1 0
+            vt 1 1 0
+            vt 0 1
+            vn 0 0 1
+            f 1/1/1 2/2/1 3/3/1 4/4/1
+        ";
+        let model = parse_obj(quad, Path::new("quad.obj")).unwrap();
+        let data = &model.meshes[0].data;
+        assert_eq!(data.vertices.len(), 4);
+        assert_eq!(data.normals.len(), 4);
+        assert_eq!(data.uvs, [
+            Vector2::new(0.0, 1.0), Vector2::new(1.0, 1.0),
+            Vector2::new(1.0, 0.0), Vector2::new(0.0, 0.0),
+        ]);
+
+        // Same positions with other texture coordinates are separate vertices
+        let text = format!("{}\nf 1/4 2/3 3/2", quad);
+        let model = parse_obj(&text, Path::new("quad.obj")).unwrap();
+        let data = &model.meshes[0].data;
+        assert_eq!(data.vertices.len(), 7);
+        assert_eq!(data.uvs.len(), 7);
+
+        // A face without texture coordinates drops them for the whole mesh
+        let text = format!("{}\nf 1 2 3", quad);
+        let model = parse_obj(&text, Path::new("quad.obj")).unwrap();
+        assert!(model.meshes[0].data.uvs.is_empty());
+
+        assert!(parse_obj("v 0 0 0\nvt 0 0\nf 1/1 1/2 1/1\n", Path::new("bad.obj")).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        match parse_obj("v 0 0 0\nv 1 0 0\nf 1 2 3\n", Path::new("bad.obj")) {
+            Err(Error::Parse { line, file, .. }) => {
+                assert_eq!(line, 3);
+                assert_eq!(file, Path::new("bad.obj"));
+            },
+            _ => panic!(),
+        }
+        match parse_mtl("newmtl a\nKd 1 x 0\n", Path::new("bad.mtl")) {
+            Err(Error::Parse { line, .. }) => assert_eq!(line, 2),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn materials() {
+        let materials = parse_mtl("
+            newmtl light
+            Ke 4 4 4
+            newmtl glass
+            Ni 1.5
+            Tr 0.9
+        ", Path::new("test.mtl")).unwrap();
+        assert_eq!(materials.len(), 2);
+        assert_eq!(materials[0].emission.x, 4.0);
+        assert!((materials[1].opacity - 0.1).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,305 @@
+use std::{
+    fs,
+    path::Path,
+};
+use nalgebra::Vector3;
+use crate::{
+    Error,
+    shape::mesh::MeshData,
+};
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Scalar {
+    I8, U8, I16, U16, I32, U32, F32, F64,
+}
+
+impl Scalar {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Property {
+    name: String,
+    /// Type of the list length if the property is a list.
+    list: Option<Scalar>,
+    scalar: Scalar,
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Reader of the PLY body that yields numbers one by one.
+struct Body<'a> {
+    format: Format,
+    bytes: &'a [u8],
+    pos: usize,
+    /// Current line for ASCII format.
+    line: usize,
+    words: std::vec::IntoIter<&'a str>,
+    path: &'a Path,
+}
+
+impl<'a> Body<'a> {
+    fn error<S: Into<String>>(&self, message: S) -> Error {
+        Error::parse(self.path, self.line, message)
+    }
+
+    /// Moves to the next line of ASCII body.
+    fn next_line(&mut self) -> crate::Result<()> {
+        loop {
+            if self.pos >= self.bytes.len() {
+                return Err(self.error("unexpected end of file"));
+            }
+            let end = self.bytes[self.pos..].iter().position(|&b| b == b'\n')
+            .map(|i| self.pos + i).unwrap_or_else(|| self.bytes.len());
+            let text = std::str::from_utf8(&self.bytes[self.pos..end])
+            .map_err(|_| self.error("bad text encoding"))?;
+            self.pos = end + 1;
+            self.line += 1;
+            let words = text.split_whitespace().collect::<Vec<_>>();
+            if !words.is_empty() {
+                self.words = words.into_iter();
+                return Ok(());
+            }
+        }
+    }
+
+    fn read(&mut self, scalar: Scalar) -> crate::Result<f64> {
+        if self.format == Format::Ascii {
+            let word = match self.words.next() {
+                Some(w) => w,
+                None => return Err(self.error("not enough values in the line")),
+            };
+            return word.parse::<f64>().map_err(|_| self.error(format!("bad number '{}'", word)));
+        }
+        let size = scalar.size();
+        if self.pos + size > self.bytes.len() {
+            return Err(self.error("unexpected end of file"));
+        }
+        let mut b = [0u8; 8];
+        b[..size].copy_from_slice(&self.bytes[self.pos..(self.pos + size)]);
+        self.pos += size;
+        if self.format == Format::BinaryBigEndian {
+            b[..size].reverse();
+        }
+        Ok(match scalar {
+            Scalar::I8 => b[0] as i8 as f64,
+            Scalar::U8 => b[0] as f64,
+            Scalar::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::U16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F64 => f64::from_le_bytes(b),
+        })
+    }
+}
+
+/// Loads a mesh from PLY file in ASCII or binary format.
+///
+/// Vertices should have `x`, `y` and `z` properties and optionally `nx`, `ny` and `nz` normals
+/// (otherwise smooth normals are computed). Faces are split into triangles.
+/// Errors in binary data refer to the line where the data begins.
+pub fn load_ply<P: AsRef<Path>>(path: P) -> crate::Result<MeshData> {
+    let path = path.as_ref();
+    parse_ply(&fs::read(path)?, path)
+}
+
+/// Parses the content of PLY file. The path is used for error messages.
+pub fn parse_ply(bytes: &[u8], path: &Path) -> crate::Result<MeshData> {
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut pos = 0;
+    let mut line = 0;
+    loop {
+        if pos >= bytes.len() {
+            return Err(Error::parse(path, line, "missing 'end_header'"));
+        }
+        let end = bytes[pos..].iter().position(|&b| b == b'\n')
+        .map(|i| pos + i).unwrap_or_else(|| bytes.len());
+        let text = std::str::from_utf8(&bytes[pos..end])
+        .map_err(|_| Error::parse(path, line + 1, "bad header encoding"))?;
+        pos = end + 1;
+        line += 1;
+
+        let words = text.split_whitespace().collect::<Vec<_>>();
+        let bad = || Error::parse(path, line, format!("bad header line '{}'", text.trim()));
+        match words.as_slice() {
+            ["ply"] if line == 1 => (),
+            _ if line == 1 => return Err(Error::parse(path, line, "not a PLY file")),
+            ["format", f, "1.0"] => format = Some(match *f {
+                "ascii" => Format::Ascii,
+                "binary_little_endian" => Format::BinaryLittleEndian,
+                "binary_big_endian" => Format::BinaryBigEndian,
+                _ => return Err(bad()),
+            }),
+            ["comment", ..] | ["obj_info", ..] | [] => (),
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse().map_err(|_| bad())?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", lt, t, name] => elements.last_mut().ok_or_else(bad)?.properties.push(Property {
+                name: name.to_string(),
+                list: Some(Scalar::from_name(lt).ok_or_else(bad)?),
+                scalar: Scalar::from_name(t).ok_or_else(bad)?,
+            }),
+            ["property", t, name] => elements.last_mut().ok_or_else(bad)?.properties.push(Property {
+                name: name.to_string(),
+                list: None,
+                scalar: Scalar::from_name(t).ok_or_else(bad)?,
+            }),
+            ["end_header"] => break,
+            _ => return Err(bad()),
+        }
+    }
+    let format = format.ok_or_else(|| Error::parse(path, line, "missing 'format'"))?;
+
+    let mut body = Body {
+        format, bytes, pos, line: line + 1,
+        words: Vec::new().into_iter(), path,
+    };
+    if format == Format::Ascii {
+        body.line = line;
+    }
+
+    let mut vertices = Vec::new();
+    let mut normals = Vec::new();
+    let mut triangles = Vec::new();
+    for element in elements.iter() {
+        let find = |name: &str| element.properties.iter().position(|p| p.name == name && p.list.is_none());
+        let coords = [find("x"), find("y"), find("z")];
+        let norms = [find("nx"), find("ny"), find("nz")];
+        for _ in 0..element.count {
+            if format == Format::Ascii {
+                body.next_line()?;
+            }
+            let mut values = vec![0.0; element.properties.len()];
+            let mut list = Vec::new();
+            for (k, p) in element.properties.iter().enumerate() {
+                match p.list {
+                    Some(lt) => {
+                        let len = body.read(lt)? as usize;
+                        let items = (0..len).map(|_| body.read(p.scalar)).collect::<crate::Result<Vec<_>>>()?;
+                        if p.name == "vertex_indices" || p.name == "vertex_index" {
+                            list = items;
+                        }
+                    },
+                    None => values[k] = body.read(p.scalar)?,
+                }
+            }
+            match element.name.as_str() {
+                "vertex" => {
+                    if let [Some(x), Some(y), Some(z)] = coords {
+                        vertices.push(Vector3::new(values[x], values[y], values[z]));
+                    } else {
+                        return Err(body.error("vertex has no coordinates"));
+                    }
+                    if let [Some(x), Some(y), Some(z)] = norms {
+                        normals.push(Vector3::new(values[x], values[y], values[z]));
+                    }
+                },
+                "face" => {
+                    if list.len() < 3 {
+                        return Err(body.error("face has less than 3 vertices"));
+                    }
+                    if let Some(&i) = list.iter().find(|&&i| i < 0.0 || i as usize >= vertices.len()) {
+                        return Err(body.error(format!("vertex index {} is out of range", i)));
+                    }
+                    for k in 1..(list.len() - 1) {
+                        triangles.push([list[0] as usize, list[k] as usize, list[k + 1] as usize]);
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    if normals.len() == vertices.len() {
+        MeshData::with_normals(vertices, normals, triangles)
+    } else {
+        MeshData::new(vertices, triangles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::{Error, loader::*};
+
+    const HEADER: &str = "ply
+format {} 1.0
+comment test
+element vertex 4
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_indices
+end_header
+";
+
+    #[test]
+    fn ascii() {
+        let text = HEADER.replace("{}", "ascii") + "0 0 0\n1 0 0\n1 1 0\n0 1 0\n4 0 1 2 3\n";
+        let data = parse_ply(text.as_bytes(), Path::new("quad.ply")).unwrap();
+        assert_eq!(data.vertices.len(), 4);
+        assert_eq!(data.triangles, vec![[0, 1, 2], [0, 2, 3]]);
+
+        let text = HEADER.replace("{}", "ascii") + "0 0 0\n1 0 0\n1 1 0\n0 1 0\n3 0 1 7\n";
+        match parse_ply(text.as_bytes(), Path::new("bad.ply")) {
+            Err(Error::Parse { line, .. }) => assert_eq!(line, 15),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn binary() {
+        let mut bytes = HEADER.replace("{}", "binary_big_endian").into_bytes();
+        for v in [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]].iter() {
+            for x in v.iter() {
+                bytes.extend_from_slice(&x.to_be_bytes());
+            }
+        }
+        bytes.push(3);
+        for i in [0i32, 1, 2].iter() {
+            bytes.extend_from_slice(&i.to_be_bytes());
+        }
+        let data = parse_ply(&bytes, Path::new("tri.ply")).unwrap();
+        assert_eq!(data.vertices[2], nalgebra::Vector3::new(1.0, 1.0, 0.0));
+        assert_eq!(data.triangles, vec![[0, 1, 2]]);
+    }
+}
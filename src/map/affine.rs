@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+use nalgebra::{Vector3, Matrix3, Matrix4, U1, U3};
+use crate::{prelude::*, layout::Layout, map::*};
+
+
+/// Affine transformation: linear transformation followed by translation.
+///
+/// The linear part is invertible, its inverse is computed once on creation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Affine {
+    linear: Matrix3<f64>,
+    inverse: Matrix3<f64>,
+    shift: Vector3<f64>,
+}
+
+impl Affine {
+    /// Creates the transformation, fails if the linear part is singular.
+    pub fn new(linear: Matrix3<f64>, shift: Vector3<f64>) -> crate::Result<Self> {
+        let inverse = linear.try_inverse().ok_or("Affine: linear part is singular")?;
+        Ok(Self { linear, inverse, shift })
+    }
+    /// Creates the transformation from the parts that are known to be consistent.
+    fn from_parts(linear: Matrix3<f64>, inverse: Matrix3<f64>, shift: Vector3<f64>) -> Self {
+        Self { linear, inverse, shift }
+    }
+    pub fn identity() -> Self {
+        Self::from_parts(Matrix3::identity(), Matrix3::identity(), Vector3::zeros())
+    }
+
+    pub fn linear(&self) -> &Matrix3<f64> {
+        &self.linear
+    }
+    pub fn inverse(&self) -> &Matrix3<f64> {
+        &self.inverse
+    }
+    pub fn shift(&self) -> &Vector3<f64> {
+        &self.shift
+    }
+
+    /// Creates the transformation from 4x4 homogeneous matrix.
+    ///
+    /// The last row of the matrix is assumed to be `(0, 0, 0, 1)`.
+    pub fn from_homogeneous(m: &Matrix4<f64>) -> crate::Result<Self> {
+        Self::new(
+            m.fixed_slice::<U3, U3>(0, 0).into_owned(),
+            m.fixed_slice::<U3, U1>(0, 3).into_owned(),
+        )
+    }
+    /// Returns 4x4 homogeneous matrix of the transformation.
+    pub fn to_homogeneous(&self) -> Matrix4<f64> {
+        let mut m = self.linear.to_homogeneous();
+        m.fixed_slice_mut::<U3, U1>(0, 3).copy_from(&self.shift);
+        m
+    }
+
+    /// Composes the transformation where this one is followed by another one.
+    pub fn then(&self, other: &Affine) -> Self {
+        Self::from_parts(
+            other.linear*self.linear,
+            self.inverse*other.inverse,
+            other.linear*self.shift + other.shift,
+        )
+    }
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Map that could be represented as a single affine transformation.
+///
+/// It allows to collapse a chain of such maps into one `Affine`
+/// to reduce the per-ray work and the size of generated device code:
+/// `shape.map(a.chain(b).chain(c).to_affine())`.
+pub trait ToAffine: Map {
+    fn to_affine(&self) -> Affine;
+}
+
+impl ToAffine for Affine {
+    fn to_affine(&self) -> Affine {
+        self.clone()
+    }
+}
+impl ToAffine for Shift {
+    fn to_affine(&self) -> Affine {
+        self.clone().into()
+    }
+}
+impl ToAffine for Scale {
+    fn to_affine(&self) -> Affine {
+        self.clone().into()
+    }
+}
+impl ToAffine for Linear {
+    fn to_affine(&self) -> Affine {
+        self.clone().into()
+    }
+}
+impl ToAffine for Rotation {
+    fn to_affine(&self) -> Affine {
+        self.clone().into()
+    }
+}
+impl<F: ToAffine, S: ToAffine> ToAffine for Chain<F, S> {
+    fn to_affine(&self) -> Affine {
+        self.first.to_affine().then(&self.second.to_affine())
+    }
+}
+
+impl From<Shift> for Affine {
+    fn from(map: Shift) -> Self {
+        Self::from_parts(Matrix3::identity(), Matrix3::identity(), map.offset)
+    }
+}
+impl From<Scale> for Affine {
+    fn from(map: Scale) -> Self {
+        Self::from_parts(
+            Matrix3::from_diagonal(map.factor()),
+            Matrix3::from_diagonal(&map.factor().map(|x| 1.0/x)),
+            Vector3::zeros(),
+        )
+    }
+}
+impl From<Linear> for Affine {
+    fn from(map: Linear) -> Self {
+        Self::from_parts(*map.matrix(), *map.inverse(), Vector3::zeros())
+    }
+}
+impl From<Rotation> for Affine {
+    fn from(map: Rotation) -> Self {
+        let matrix = map.rotation.to_rotation_matrix().into_inner();
+        Self::from_parts(matrix, matrix.transpose(), Vector3::zeros())
+    }
+}
+
+impl Map for Affine {
+    fn apply_abs(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.linear*v + self.shift
+    }
+    fn apply_rel(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.linear*v
+    }
+    fn apply_abs_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.inverse*(v - self.shift)
+    }
+    fn apply_rel_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.inverse*v
+    }
+    fn apply_norm(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.inverse.transpose()*v
+    }
+}
+
+impl Instance<MapClass> for Affine {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/map/affine.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "affine_map".to_string()
+    }
+}
+
+/// Matrices are packed in row-major order to be loaded with `matrix3_load`.
+impl Pack for Affine {
+    fn size_int() -> usize { 0 }
+    fn size_float() -> usize { 3*9 + 3 }
+    fn pack_to(&self, _buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+        buffer_float
+        .pack(&self.linear.transpose())
+        .pack(&self.inverse.transpose())
+        .pack(&self.inverse)
+        .pack(&self.shift);
+    }
+    fn layout() -> Layout {
+        Layout::builder()
+        .field_float::<Matrix3<f64>>("linear")
+        .field_float::<Matrix3<f64>>("inverse")
+        .field_float::<Matrix3<f64>>("normal")
+        .field_float::<Vector3<f64>>("shift")
+        .build()
+    }
+}
+
+impl Unpack for Affine {
+    fn unpack_from(_buffer_int: &[i32], mut buffer_float: &[f32]) -> Self {
+        let linear = buffer_float.unpack::<Matrix3<f64>>().transpose();
+        let inverse = buffer_float.unpack::<Matrix3<f64>>().transpose();
+        buffer_float = &buffer_float[9..];
+        Self::from_parts(linear, inverse, buffer_float.unpack())
+    }
+}
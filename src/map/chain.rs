@@ -1,7 +1,9 @@
 use std::collections::HashSet;
+use nalgebra::Vector3;
 use crate::{prelude::*, map::{Map, MapClass}};
 
 /// Subsequently chains two mappins together.
+#[derive(Pack, Unpack)]
 pub struct Chain<F: Map, S: Map> {
     pub first: F,
     pub second: S,
@@ -13,7 +15,23 @@ impl<F: Map, S: Map> Chain<F, S> {
     }
 }
 
-impl<F: Map, S: Map> Map for Chain<F, S> {}
+impl<F: Map, S: Map> Map for Chain<F, S> {
+    fn apply_abs(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.second.apply_abs(self.first.apply_abs(v))
+    }
+    fn apply_rel(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.second.apply_rel(self.first.apply_rel(v))
+    }
+    fn apply_abs_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.first.apply_abs_inv(self.second.apply_abs_inv(v))
+    }
+    fn apply_rel_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.first.apply_rel_inv(self.second.apply_rel_inv(v))
+    }
+    fn apply_norm(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.second.apply_norm(self.first.apply_norm(v))
+    }
+}
 
 impl<F: Map, S: Map> Instance<MapClass> for Chain<F, S> {
     fn source(cache: &mut HashSet<u64>) -> String {
@@ -24,13 +42,14 @@ impl<F: Map, S: Map> Instance<MapClass> for Chain<F, S> {
             F::source(cache),
             S::source(cache),
             "#include <clay_core/map/chain.h>".to_string(),
+            Self::layout().defines(&Self::inst_name()),
             format!(
-                "MAP_CHAIN({}, {}, {}, {}, {})",
+                "MAP_CHAIN({}, {}, {}, {}_SECOND_DI, {}_SECOND_DF)",
                 Self::inst_name(),
                 F::inst_name(),
                 S::inst_name(),
-                F::size_int(),
-                F::size_float(),
+                Self::inst_name(),
+                Self::inst_name(),
             ),
         ].join("\n")
     }
@@ -43,17 +62,3 @@ impl<F: Map, S: Map> Instance<MapClass> for Chain<F, S> {
         )
     }
 }
-
-impl<F: Map, S: Map> Pack for Chain<F, S> {
-    fn size_int() -> usize {
-        F::size_int() + S::size_int()
-    }
-    fn size_float() -> usize {
-        F::size_float() + S::size_float()
-    }
-    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        Packer::new(buffer_int, buffer_float)
-        .pack(&self.first)
-        .pack(&self.second);
-    }
-}
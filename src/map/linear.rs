@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+use nalgebra::{Vector3, Matrix3};
+use crate::{prelude::*, layout::Layout, map::*};
+
+
+/// Linear transformation defined by the matrix.
+///
+/// The matrix is invertible, its inverse is computed once on creation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Linear {
+    matrix: Matrix3<f64>,
+    inverse: Matrix3<f64>,
+}
+
+impl Linear {
+    /// Creates the transformation, fails if the matrix is singular.
+    pub fn new(matrix: Matrix3<f64>) -> crate::Result<Self> {
+        let inverse = matrix.try_inverse().ok_or("Linear: matrix is singular")?;
+        Ok(Self { matrix, inverse })
+    }
+
+    pub fn matrix(&self) -> &Matrix3<f64> {
+        &self.matrix
+    }
+    pub fn inverse(&self) -> &Matrix3<f64> {
+        &self.inverse
+    }
+}
+
+impl Default for Linear {
+    fn default() -> Self {
+        Self { matrix: Matrix3::identity(), inverse: Matrix3::identity() }
+    }
+}
+
+impl Map for Linear {
+    fn apply_abs(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.matrix*v
+    }
+    fn apply_rel(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.matrix*v
+    }
+    fn apply_abs_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.inverse*v
+    }
+    fn apply_rel_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.inverse*v
+    }
+    fn apply_norm(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.inverse.transpose()*v
+    }
+}
+
+impl Instance<MapClass> for Linear {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/map/linear.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "linear_map".to_string()
+    }
+}
+
+/// Matrices are packed in row-major order to be loaded with `matrix3_load`.
+impl Pack for Linear {
+    fn size_int() -> usize { 0 }
+    fn size_float() -> usize { 3*9 }
+    fn pack_to(&self, _buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+        buffer_float
+        .pack(&self.matrix.transpose())
+        .pack(&self.inverse.transpose())
+        .pack(&self.inverse);
+    }
+    fn layout() -> Layout {
+        Layout::builder()
+        .field_float::<Matrix3<f64>>("matrix")
+        .field_float::<Matrix3<f64>>("inverse")
+        .field_float::<Matrix3<f64>>("normal")
+        .build()
+    }
+}
+
+impl Unpack for Linear {
+    fn unpack_from(_buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        Self {
+            matrix: Matrix3::unpack_float_from(buffer_float).transpose(),
+            inverse: Matrix3::unpack_float_from(&buffer_float[9..]).transpose(),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use nalgebra::Vector3;
 use crate::{
     prelude::*,
     map::Chain,
@@ -5,11 +6,25 @@ use crate::{
 
 
 /// Some invertible mapping that could be applied to vectors.
+///
+/// Host methods mirror the corresponding device ones
+/// to allow to perform the same transformations on the CPU side.
 pub trait Map: Pack + Instance<MapClass> {
     /// Create a new mapping where this one is followed by another one.
     fn chain<M: Map>(self, other: M) -> Chain<Self, M> {
         Chain::<Self, M>::new(self, other)
     }
+
+    /// Maps a point (device `abs`).
+    fn apply_abs(&self, v: Vector3<f64>) -> Vector3<f64>;
+    /// Maps a direction vector, i.e. doesn't take a translation into account (device `rel`).
+    fn apply_rel(&self, v: Vector3<f64>) -> Vector3<f64>;
+    /// Inverse mapping of a point (device `abs_inv`).
+    fn apply_abs_inv(&self, v: Vector3<f64>) -> Vector3<f64>;
+    /// Inverse mapping of a direction vector (device `rel_inv`).
+    fn apply_rel_inv(&self, v: Vector3<f64>) -> Vector3<f64>;
+    /// Maps a surface normal (device `norm`). The result isn't normalized.
+    fn apply_norm(&self, v: Vector3<f64>) -> Vector3<f64>;
 }
 
 /// Device interface for mapping.
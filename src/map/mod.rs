@@ -3,3 +3,17 @@ pub use map::*;
 
 mod chain;
 pub use chain::*;
+
+mod shift;
+pub use shift::*;
+mod scale;
+pub use scale::*;
+mod linear;
+pub use linear::*;
+mod rotation;
+pub use rotation::*;
+mod affine;
+pub use affine::*;
+
+#[cfg(test)]
+mod test;
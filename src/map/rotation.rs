@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use nalgebra::{Vector3, Matrix3, UnitQuaternion, Rotation3};
+use crate::{prelude::*, layout::Layout, map::*};
+
+
+/// Rotation around the origin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rotation {
+    pub rotation: UnitQuaternion<f64>,
+}
+
+impl Rotation {
+    pub fn new(rotation: UnitQuaternion<f64>) -> Self {
+        Self { rotation }
+    }
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Self::new(UnitQuaternion::identity())
+    }
+}
+
+impl Map for Rotation {
+    fn apply_abs(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.rotation*v
+    }
+    fn apply_rel(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.rotation*v
+    }
+    fn apply_abs_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.rotation.inverse_transform_vector(&v)
+    }
+    fn apply_rel_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.rotation.inverse_transform_vector(&v)
+    }
+    fn apply_norm(&self, v: Vector3<f64>) -> Vector3<f64> {
+        self.rotation*v
+    }
+}
+
+impl Instance<MapClass> for Rotation {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/map/rotation.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "rotation_map".to_string()
+    }
+}
+
+/// Matrices are packed in row-major order to be loaded with `matrix3_load`.
+///
+/// The rotation matrix is orthogonal, so its inverse is the transposed one
+/// and the normal matrix is the same as the rotation matrix.
+impl Pack for Rotation {
+    fn size_int() -> usize { 0 }
+    fn size_float() -> usize { 2*9 }
+    fn pack_to(&self, _buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+        let matrix = self.rotation.to_rotation_matrix().into_inner();
+        buffer_float
+        .pack(&matrix.transpose())
+        .pack(&matrix);
+    }
+    fn layout() -> Layout {
+        Layout::builder()
+        .field_float::<Matrix3<f64>>("matrix")
+        .field_float::<Matrix3<f64>>("inverse")
+        .build()
+    }
+}
+
+impl Unpack for Rotation {
+    fn unpack_from(_buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        let matrix = Matrix3::unpack_float_from(buffer_float).transpose();
+        Self::new(UnitQuaternion::from_rotation_matrix(
+            &Rotation3::from_matrix_unchecked(matrix),
+        ))
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use crate::{prelude::*, layout::Layout, map::*};
+
+
+/// Scaling along the coordinate axes.
+///
+/// Factors are nonzero and finite, so the scaling is invertible.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scale {
+    factor: Vector3<f64>,
+}
+
+impl Scale {
+    /// Creates the scaling, fails if some factor is zero or not finite.
+    pub fn new(factor: Vector3<f64>) -> crate::Result<Self> {
+        if factor.iter().any(|&x| x == 0.0 || !x.is_finite()) {
+            return Err("Scale: factors must be nonzero and finite".into());
+        }
+        Ok(Self { factor })
+    }
+    /// The same scaling along all the axes.
+    pub fn uniform(factor: f64) -> crate::Result<Self> {
+        Self::new(Vector3::from_element(factor))
+    }
+
+    pub fn factor(&self) -> &Vector3<f64> {
+        &self.factor
+    }
+}
+
+impl Default for Scale {
+    fn default() -> Self {
+        Self { factor: Vector3::from_element(1.0) }
+    }
+}
+
+impl Map for Scale {
+    fn apply_abs(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v.component_mul(&self.factor)
+    }
+    fn apply_rel(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v.component_mul(&self.factor)
+    }
+    fn apply_abs_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v.component_div(&self.factor)
+    }
+    fn apply_rel_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v.component_div(&self.factor)
+    }
+    fn apply_norm(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v.component_div(&self.factor)
+    }
+}
+
+impl Instance<MapClass> for Scale {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/map/scale.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "scale_map".to_string()
+    }
+}
+
+impl Pack for Scale {
+    fn size_int() -> usize { 0 }
+    fn size_float() -> usize { 6 }
+    fn pack_to(&self, _buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+        buffer_float
+        .pack(&self.factor)
+        .pack(&self.factor.map(|x| 1.0/x));
+    }
+    fn layout() -> Layout {
+        Layout::builder()
+        .field_float::<Vector3<f64>>("factor")
+        .field_float::<Vector3<f64>>("inverse")
+        .build()
+    }
+}
+
+impl Unpack for Scale {
+    fn unpack_from(_buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        Self { factor: Vector3::unpack_float_from(buffer_float) }
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use crate::{prelude::*, layout::Layout, map::*};
+
+
+/// Translation by the specified vector.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shift {
+    pub offset: Vector3<f64>,
+}
+
+impl Shift {
+    pub fn new(offset: Vector3<f64>) -> Self {
+        Self { offset }
+    }
+}
+
+impl Default for Shift {
+    fn default() -> Self {
+        Self::new(Vector3::zeros())
+    }
+}
+
+impl Map for Shift {
+    fn apply_abs(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v + self.offset
+    }
+    fn apply_rel(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v
+    }
+    fn apply_abs_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v - self.offset
+    }
+    fn apply_rel_inv(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v
+    }
+    fn apply_norm(&self, v: Vector3<f64>) -> Vector3<f64> {
+        v
+    }
+}
+
+impl Instance<MapClass> for Shift {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/map/shift.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "shift_map".to_string()
+    }
+}
+
+impl Pack for Shift {
+    fn size_int() -> usize { 0 }
+    fn size_float() -> usize { 3 }
+    fn pack_to(&self, _buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+        self.offset.pack_float_to(buffer_float);
+    }
+    fn layout() -> Layout {
+        Layout::builder()
+        .field_float::<Vector3<f64>>("offset")
+        .build()
+    }
+}
+
+impl Unpack for Shift {
+    fn unpack_from(_buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        Self::new(Vector3::unpack_float_from(buffer_float))
+    }
+}
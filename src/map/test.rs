@@ -0,0 +1,200 @@
+use std::{
+    path::Path,
+    collections::HashSet,
+};
+use nalgebra::{Vector3, Matrix3, UnitQuaternion};
+use ocl::{self, flags};
+use ocl_include::{MemHook, ListHook};
+use crate::{
+    prelude::*,
+    map::*,
+    Context,
+    process::Program,
+    buffer::InstanceBuffer,
+};
+
+
+/// Context on the OpenCL CPU device if there is one.
+fn cpu_context() -> Option<Context> {
+    for platform in ocl::Platform::list() {
+        let devices = ocl::Device::list(&platform, Some(flags::DEVICE_TYPE_CPU)).unwrap_or_default();
+        if let Some(device) = devices.into_iter().next() {
+            return Context::new(platform, device).ok();
+        }
+    }
+    None
+}
+
+const KERNEL: &str = "
+#include <__gen/map.h>
+
+__kernel void map_test(
+    __global const int *ibuf,
+    __global const float *fbuf,
+    int count,
+    __global const float *src,
+    __global float *dst
+) {
+    int i = get_global_id(0);
+    float3 v = vload3(i, src);
+    vstore3(__map_abs(v, ibuf, fbuf), 5*i + 0, dst);
+    vstore3(__map_rel(v, ibuf, fbuf), 5*i + 1, dst);
+    vstore3(__map_abs_inv(v, ibuf, fbuf), 5*i + 2, dst);
+    vstore3(__map_rel_inv(v, ibuf, fbuf), 5*i + 3, dst);
+    vstore3(__map_norm(v, ibuf, fbuf), 5*i + 4, dst);
+}
+";
+
+/// Applies the map to the points on the device.
+fn apply_device<M: Map>(context: &Context, map: &M, points: &[Vector3<f64>]) -> Vec<Vector3<f64>> {
+    let mut cache = HashSet::new();
+    let header = [
+        M::source(&mut cache),
+        MapClass::methods().into_iter().map(|method| {
+            format!("#define __map_{} {}_{}", method, M::inst_name(), method)
+        }).collect::<Vec<_>>().join("\n"),
+    ].join("\n");
+    let hook = ListHook::builder()
+    .add_hook(crate::source())
+    .add_hook(
+        MemHook::builder()
+        .add_file(&Path::new("__gen/map.h"), header).unwrap()
+        .add_file(&Path::new("__gen/map_test.c"), KERNEL.to_string()).unwrap()
+        .build()
+    )
+    .build();
+    let program = Program::new(&hook, &Path::new("__gen/map_test.c")).unwrap();
+    let (ocl_prog, _) = program.build(context).unwrap();
+
+    let mut map_buffer = InstanceBuffer::new(context, std::slice::from_ref(map).iter()).unwrap();
+    let src_data = points.iter().flat_map(|p| p.iter().map(|x| *x as f32)).collect::<Vec<_>>();
+    let src = ocl::Buffer::<f32>::builder()
+    .queue(context.queue().clone())
+    .flags(flags::MEM_READ_ONLY)
+    .len(src_data.len())
+    .fill_val(0f32)
+    .build().unwrap();
+    src.cmd().offset(0).write(&src_data).enq().unwrap();
+    let dst = ocl::Buffer::<f32>::builder()
+    .queue(context.queue().clone())
+    .flags(flags::MEM_WRITE_ONLY)
+    .len(5*src_data.len())
+    .fill_val(0f32)
+    .build().unwrap();
+
+    let mut kb = ocl::Kernel::builder();
+    kb.program(&ocl_prog)
+    .name("map_test")
+    .queue(context.queue().clone());
+    InstanceBuffer::<M>::args_def(&mut kb);
+    kb.arg(&src).arg(&dst);
+    let mut kernel = kb.build().unwrap();
+    map_buffer.args_set(0, &mut kernel).unwrap();
+    unsafe {
+        kernel.cmd()
+        .global_work_size(points.len())
+        .enq().unwrap();
+    }
+
+    let mut dst_data = vec![0f32; dst.len()];
+    dst.cmd().offset(0).read(&mut dst_data).enq().unwrap();
+    dst_data.chunks(3).map(|c| Vector3::new(c[0] as f64, c[1] as f64, c[2] as f64)).collect()
+}
+
+fn apply_host<M: Map>(map: &M, points: &[Vector3<f64>]) -> Vec<Vector3<f64>> {
+    points.iter().flat_map(|p| vec![
+        map.apply_abs(*p),
+        map.apply_rel(*p),
+        map.apply_abs_inv(*p),
+        map.apply_rel_inv(*p),
+        map.apply_norm(*p),
+    ]).collect()
+}
+
+fn compare<M: Map>(context: &Context, map: &M) {
+    let points = [
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(1.0, -2.0, 3.0),
+        Vector3::new(-0.5, 0.25, 4.0),
+    ];
+    let host = apply_host(map, &points);
+    let device = apply_device(context, map, &points);
+    for (h, d) in host.iter().zip(device.iter()) {
+        assert!((h - d).norm() < 1e-4*(1.0 + h.norm()), "host: {}, device: {}", h, d);
+    }
+}
+
+#[test]
+#[ignore = "needs an OpenCL CPU device, run with `cargo test -- --ignored`"]
+fn host_matches_device() {
+    let context = cpu_context().expect("no OpenCL CPU device");
+    let rotation = UnitQuaternion::from_euler_angles(0.1, -0.7, 1.3);
+    let matrix = Matrix3::new(
+        1.0, 0.5, 0.0,
+        -0.2, 2.0, 0.3,
+        0.0, 0.1, 0.5,
+    );
+
+    compare(&context, &Shift::new(Vector3::new(1.0, 2.0, 3.0)));
+    compare(&context, &Scale::new(Vector3::new(0.5, 2.0, -3.0)).unwrap());
+    compare(&context, &Linear::new(matrix).unwrap());
+    compare(&context, &Rotation::new(rotation));
+    compare(&context, &Affine::new(matrix, Vector3::new(-1.0, 0.0, 2.0)).unwrap());
+    compare(
+        &context,
+        &Rotation::new(rotation)
+        .chain(Scale::uniform(2.0).unwrap())
+        .chain(Shift::new(Vector3::new(0.0, 1.0, 0.0))),
+    );
+}
+
+#[test]
+fn collapse_affine() {
+    let map = Shift::new(Vector3::new(1.0, 2.0, 3.0))
+    .chain(Rotation::new(UnitQuaternion::from_euler_angles(0.1, -0.7, 1.3)))
+    .chain(Scale::new(Vector3::new(0.5, 2.0, 3.0)).unwrap())
+    .chain(Shift::new(Vector3::new(-1.0, 0.0, 1.0)));
+    let affine = map.to_affine();
+    let v = Vector3::new(1.0, -2.0, 3.0);
+    assert!((map.apply_abs(v) - affine.apply_abs(v)).norm() < 1e-9);
+    assert!((map.apply_rel(v) - affine.apply_rel(v)).norm() < 1e-9);
+    assert!((map.apply_norm(v) - affine.apply_norm(v)).norm() < 1e-9);
+    let restored = Affine::from_homogeneous(&affine.to_homogeneous()).unwrap();
+    assert!((restored.inverse() - affine.inverse()).norm() < 1e-9);
+    assert_eq!((restored.linear(), restored.shift()), (affine.linear(), affine.shift()));
+}
+
+#[test]
+fn host_inverse() {
+    let matrix = Matrix3::new(
+        1.0, 0.5, 0.0,
+        -0.2, 2.0, 0.3,
+        0.0, 0.1, 0.5,
+    );
+    let map = Rotation::new(UnitQuaternion::from_euler_angles(0.1, -0.7, 1.3))
+    .chain(Scale::new(Vector3::new(0.5, 2.0, 3.0)).unwrap())
+    .chain(Affine::new(matrix, Vector3::new(1.0, 2.0, 3.0)).unwrap());
+    let v = Vector3::new(1.0, -2.0, 3.0);
+    assert!((map.apply_abs_inv(map.apply_abs(v)) - v).norm() < 1e-9);
+    assert!((map.apply_rel_inv(map.apply_rel(v)) - v).norm() < 1e-9);
+    // Normals stay orthogonal to the mapped tangent vectors.
+    let (t, n) = (Vector3::new(1.0, 1.0, 0.0), Vector3::new(1.0, -1.0, 2.0));
+    assert!(map.apply_rel(t).dot(&map.apply_norm(n)).abs() < 1e-9);
+
+    let singular = Matrix3::new(
+        1.0, 2.0, 0.0,
+        2.0, 4.0, 0.0,
+        0.0, 0.0, 1.0,
+    );
+    assert!(Linear::new(singular).is_err());
+    assert!(Affine::new(singular, Vector3::zeros()).is_err());
+}
+
+#[test]
+fn scale_invalid() {
+    assert!(Scale::new(Vector3::new(1.0, 0.0, 2.0)).is_err());
+    assert!(Scale::new(Vector3::new(1.0, std::f64::INFINITY, 2.0)).is_err());
+    assert!(Scale::uniform(std::f64::NAN).is_err());
+    let affine = Scale::new(Vector3::new(0.5, -2.0, 4.0)).unwrap().to_affine();
+    assert!(affine.inverse().iter().all(|x| x.is_finite()));
+}
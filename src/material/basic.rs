@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use crate::{
+    prelude::*,
+    material::*,
+};
+
+
+macro_rules! impl_basic {
+    ($Material:ident, $name:expr) => {
+        impl Instance<MaterialClass> for $Material {
+            fn source(_: &mut HashSet<u64>) -> String {
+                format!("#include <clay_core/material/{}.h>", $name)
+            }
+            fn inst_name() -> String {
+                $name.to_string()
+            }
+            fn implemented_methods() -> Vec<String> {
+                MaterialClass::methods()
+            }
+        }
+    };
+}
+
+/// Lambertian surface that scatters light uniformly in all directions.
+///
+/// It is white, so use `Material::color_with` to give it the color.
+/// Rays are attracted to the target (light source) if `bounce` is offered one,
+/// scenes of this crate gather the light of targets using its `eval` and `pdf`.
+#[derive(Clone, Debug, Default, PartialEq, Pack, Unpack)]
+pub struct Diffuse {}
+
+impl Diffuse {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Material for Diffuse {
+    fn brightness(&self) -> f64 {
+        0.0
+    }
+}
+
+impl_basic!(Diffuse, "diffuse");
+
+/// Ideal mirror.
+#[derive(Clone, Debug, Default, PartialEq, Pack, Unpack)]
+pub struct Reflective {}
+
+impl Reflective {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Material for Reflective {
+    fn brightness(&self) -> f64 {
+        0.0
+    }
+}
+
+impl_basic!(Reflective, "reflective");
+
+/// Smooth transparent dielectric like glass or water.
+///
+/// The ray is either reflected or refracted according to the Fresnel equations.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Refractive {
+    /// Index of refraction relative to the outer medium.
+    pub ior: f64,
+}
+
+impl Refractive {
+    pub fn new(ior: f64) -> Self {
+        Self { ior }
+    }
+}
+
+impl Material for Refractive {
+    fn brightness(&self) -> f64 {
+        0.0
+    }
+}
+
+impl_basic!(Refractive, "refractive");
+
+/// Light source.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Luminous {
+    /// Emitted light, components could be greater than one.
+    pub emission: Vector3<f64>,
+}
+
+impl Luminous {
+    pub fn new(emission: Vector3<f64>) -> Self {
+        Self { emission }
+    }
+}
+
+impl Material for Luminous {
+    fn brightness(&self) -> f64 {
+        self.emission.iter().fold(0.0, |a, b| f64::max(a, *b))
+    }
+}
+
+impl_basic!(Luminous, "luminous");
+
+/// Rough mirror.
+///
+/// Zero roughness corresponds to the ideal mirror, and the reflection becomes blurry as it grows.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Glossy {
+    pub roughness: f64,
+}
+
+impl Glossy {
+    pub fn new(roughness: f64) -> Self {
+        Self { roughness }
+    }
+}
+
+impl Material for Glossy {
+    fn brightness(&self) -> f64 {
+        0.0
+    }
+}
+
+impl_basic!(Glossy, "glossy");
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use nalgebra::Vector3;
+    use crate::{
+        prelude::*,
+        material::*,
+    };
+
+    #[test]
+    fn brightness() {
+        let light = Luminous::new(Vector3::new(1.0, 4.0, 2.0));
+        assert_eq!(light.brightness(), 4.0);
+        assert_eq!(light.clone().color_with(Vector3::repeat(0.5)).brightness(), 2.0);
+        assert_eq!(Diffuse::new().color_with(Vector3::repeat(0.5)).brightness(), 0.0);
+    }
+
+    #[test]
+    fn source() {
+        let src = Colored::<Refractive>::source(&mut HashSet::new());
+        assert!(src.contains("#include <clay_core/material/refractive.h>"));
+        assert!(src.contains("COLORED_MATERIAL_FN_DEF("));
+        assert_eq!(Refractive::size_float(), 1);
+    }
+
+    #[test]
+    fn directed() {
+        let src = Diffuse::source(&mut HashSet::new());
+        assert!(src.contains("#include <clay_core/material/diffuse.h>"));
+        let header = crate::source::file("material/diffuse.h").unwrap();
+        assert!(header.contains("MATERIAL_BOUNCE_RET diffuse_bounce(MATERIAL_BOUNCE_ARGS_DEF) {"));
+        assert!(header.contains("if (directed) {"));
+        assert!(header.contains("new_ray->history |= RAY_TARGETED;"));
+        assert!(header.contains("MATERIAL_BOUNCE_EXT_FROM_BOUNCE_FN_DEF(diffuse)"));
+        assert!(!header.contains("MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF(diffuse)"));
+    }
+}
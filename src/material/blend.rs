@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+use crate::{
+    prelude::*,
+    material::*,
+    texture::*,
+};
+
+/// Mixture of two materials with the weight varying over the surface.
+///
+/// The weight of the second one is given by the texture (mean of its color components,
+/// clamped to `[0, 1]`), so procedural patterns or images could mix, for example,
+/// rusty and clean metal. Use `material_combine!` with textures to mix more materials.
+#[derive(Clone, Debug, Pack, Unpack)]
+pub struct Blend<A: Material, B: Material, T: Texture> {
+    pub first: A,
+    pub second: B,
+    pub weight: T,
+}
+
+impl<A: Material, B: Material, T: Texture> Blend<A, B, T> {
+    pub fn new(first: A, second: B, weight: T) -> Self {
+        Self { first, second, weight }
+    }
+}
+
+impl<A: Material, B: Material, T: Texture> Material for Blend<A, B, T> {
+    fn brightness(&self) -> f64 {
+        self.first.brightness().max(self.second.brightness())
+    }
+}
+
+impl<A: Material, B: Material, T: Texture> Instance<MaterialClass> for Blend<A, B, T> {
+    fn source(cache: &mut HashSet<u64>) -> String {
+        if !cache.insert(Self::type_hash()) {
+            return String::new()
+        }
+        let name = Self::inst_name();
+        [
+            A::full_source(cache),
+            B::full_source(cache),
+            T::source(cache),
+            "#include <clay_core/material/blend.h>".to_string(),
+            Self::layout().defines(&name),
+            format!(
+                "BLEND_MATERIAL_FN_DEF({}, {}, {}, {}, {})",
+                name, A::inst_name(), B::inst_name(), T::inst_name(),
+                ["FIRST", "SECOND", "WEIGHT"].iter()
+                .map(|f| format!("{}_{}_DI, {}_{}_DF", name, f, name, f))
+                .collect::<Vec<_>>().join(", "),
+            ),
+        ].join("\n")
+    }
+    fn inst_name() -> String {
+        format!(
+            "__blend_{:x}",
+            Self::type_hash(),
+        )
+    }
+    fn implemented_methods() -> Vec<String> {
+        MaterialClass::methods()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use nalgebra::Vector3;
+    use crate::{
+        prelude::*,
+        material::*,
+        texture::*,
+        material::test::TestMaterial,
+    };
+
+    type Tiles = Blend<Diffuse, Reflective, Checker>;
+
+    #[test]
+    fn source() {
+        let checker = Checker::new(1.0, Vector3::zeros(), Vector3::new(1.0, 1.0, 1.0));
+        let tiles = Diffuse {}.blend_with(Reflective {}, checker);
+        assert_eq!(tiles.brightness(), 0.0);
+        assert_eq!(Tiles::size_float(), Checker::size_float());
+
+        let name = Tiles::inst_name();
+        let source = Tiles::source(&mut HashSet::new());
+        assert!(source.contains("#include <clay_core/texture/pattern.h>"));
+        assert!(source.contains(&format!(
+            "BLEND_MATERIAL_FN_DEF({}, diffuse, reflective, checker, {}_FIRST_DI, {}_FIRST_DF",
+            name, name, name,
+        )));
+    }
+
+    #[test]
+    fn defaults() {
+        type T = Blend<TestMaterial<i32>, Diffuse, Checker>;
+        let source = T::full_source(&mut HashSet::new());
+        assert_eq!(source.matches("MATERIAL_BOUNCE_EXT_FROM_BOUNCE_FN_DEF(test_material)").count(), 1);
+        assert_eq!(source.matches("MATERIAL_EVAL_DEFAULT_FN_DEF(test_material)").count(), 1);
+        assert_eq!(source.matches("MATERIAL_PDF_DEFAULT_FN_DEF(test_material)").count(), 1);
+        assert!(!source.contains("_FN_DEF(diffuse)"));
+        assert!(!source.contains(&format!("DEFAULT_FN_DEF({})", T::inst_name())));
+    }
+}
@@ -6,9 +6,10 @@ use crate::{
 };
 
 /// Gives the color to an existing material.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Pack, Unpack)]
 pub struct Colored<M: Material> {
     pub material: M,
+    #[pack(float)]
     pub color: Vector3<f64>,
 }
 
@@ -31,14 +32,15 @@ impl<M: Material> Instance<MaterialClass> for Colored<M> {
             return String::new()
         }
         [
-            M::source(cache),
+            M::full_source(cache),
             "#include <clay_core/material/colored.h>".to_string(),
+            Self::layout().defines(&Self::inst_name()),
             format!(
-                "COLORED_MATERIAL_FN_DEF({}, {}, {}, {})",
+                "COLORED_MATERIAL_FN_DEF({}, {}, {}_COLOR_DI, {}_COLOR_DF)",
                 Self::inst_name(),
                 M::inst_name(),
-                M::size_int(),
-                M::size_float(),
+                Self::inst_name(),
+                Self::inst_name(),
             ),
         ].join("\n")
     }
@@ -49,14 +51,7 @@ impl<M: Material> Instance<MaterialClass> for Colored<M> {
             Self::type_hash(),
         )
     }
-}
-
-impl<M: Material> Pack for Colored<M> {
-    fn size_int() -> usize { M::size_int() + 0 }
-    fn size_float() -> usize { M::size_float() + 3 }
-
-    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        self.material.pack_to(buffer_int, buffer_float);
-        self.color.pack_float_to(&mut buffer_float[M::size_float()..]);
+    fn implemented_methods() -> Vec<String> {
+        MaterialClass::methods()
     }
 }
@@ -7,6 +7,10 @@ macro_rules! _replace {
 /// The macro for combining materials.
 ///
 /// You can read more about the technique [here](https://clay-rs.github.io/knowledge/#objects).
+///
+/// Weights are constant over the surface by default. If each material is followed by a texture
+/// in square brackets (e.g. `rust: Diffuse [Noise]`), its weight is multiplied by the mean
+/// of the texture color components (clamped to `[0, 1]`) and the weights are normalized at each point.
 #[macro_export]
 macro_rules! material_combine {
     ($Combine:ident { $( $field:ident : $Material:ty ),+ $(,)? }) => {
@@ -23,12 +27,15 @@ macro_rules! material_combine {
                     sum += $field.0;
                     $field.0 = sum;
                 )+
+                // Cumulative weights normalized to end at 1
                 Self {
-                    $( $field: ($field.0*sum, $field.1), )+
+                    $( $field: ($field.0/sum, $field.1), )+
                 }
             }
 
 
+            /// Sampling (`bounce` and `bounce_ext`) chooses one of the materials randomly,
+            /// other methods return the sum of values of materials multiplied by their weights.
             #[allow(unused_assignments)]
             fn method_source(method: &str) -> String {
                 use $crate::{prelude::*, material::*};
@@ -38,42 +45,65 @@ macro_rules! material_combine {
                     MaterialClass::name(),
                     method,
                 ).to_uppercase();
+                let sample = method.starts_with("bounce");
 
                 let mut cases = Vec::new();
                 let (mut si, mut sf) = (0, 0);
                 $(
                     let inst_name = <$Material as Instance<MaterialClass>>::inst_name();
-                    cases.push([
-                        format!("\tif (alpha < fbuf[{}]) {{", sf),
-                        format!(
-                            "\t\treturn {}_{}({}_ARGS_B({}, {}));",
-                            inst_name, method, cpref, si, sf + 1,
-                        ),
-                        "\t}".to_string(),
-                    ].join("\n"));
+                    let call = format!(
+                        "{}_{}({}_ARGS_B({}, {}))",
+                        inst_name, method, cpref, si, sf + 1,
+                    );
+                    cases.push(if sample {
+                        [
+                            format!("\tif (alpha < fbuf[{}]) {{", sf),
+                            format!("\t\treturn {};", call),
+                            "\t}".to_string(),
+                        ].join("\n")
+                    } else {
+                        [
+                            format!("\tsum += (fbuf[{}] - prev)*{};", sf, call),
+                            format!("\tprev = fbuf[{}];", sf),
+                        ].join("\n")
+                    });
                     si += <$Material>::size_int();
                     sf += 1 + <$Material>::size_float();
                 )+
-                let cases_text = cases.join(" else\n");
+                let body = if sample {
+                    [
+                        "\tfloat alpha = random_uniform(seed);".to_string(),
+                        cases.join(" else\n"),
+                        format!("\treturn {}_RET_BAD;", cpref),
+                    ].join("\n")
+                } else {
+                    [
+                        format!("\t{}_RET sum = {}_RET_BAD;", cpref, cpref),
+                        "\tfloat prev = 0.0f;".to_string(),
+                        cases.join("\n"),
+                        "\treturn sum;".to_string(),
+                    ].join("\n")
+                };
                 [
-                    &format!(
+                    format!(
                         "{}_RET {}_{}({}_ARGS_DEF) {{",
                         cpref, Self::inst_name(), method, cpref,
                     ),
-                    "\tfloat alpha = random_uniform(seed);",
-                    &cases_text,
-                    &format!("\treturn {}_RET_BAD;", cpref),
-                    "}",
+                    body,
+                    "}".to_string(),
                 ].join("\n")
             }
         }
 
         impl $crate::material::Material for $Combine {
+            #[allow(unused_assignments)]
             fn brightness(&self) -> f64 {
+                let (mut sum, mut prev) = (0.0, 0.0);
                 $(
-                    self.$field.0*self.$field.1.brightness() +
+                    sum += (self.$field.0 - prev)*self.$field.1.brightness();
+                    prev = self.$field.0;
                 )+
-                0.0
+                sum
             }
         }
 
@@ -88,7 +118,7 @@ macro_rules! material_combine {
                     ms.push(Self::method_source(&method));
                 }
                 [
-                    $( <$Material as Instance<MaterialClass>>::source(cache), )+
+                    $( <$Material as Instance<MaterialClass>>::full_source(cache), )+
                     ms.join("\n"),
                 ].join("\n")
             }
@@ -97,20 +127,19 @@ macro_rules! material_combine {
                 use $crate::TypeHash;
                 format!("__combine_{:x}", Self::type_hash())
             }
+
+            fn implemented_methods() -> Vec<String> {
+                use $crate::{prelude::*, material::*};
+                MaterialClass::methods()
+            }
         }
 
         impl $crate::Pack for $Combine {
             fn size_int() -> usize {
-                let sizes = [
-                    $( <$Material>::size_int(), )+
-                ];
-                sizes.into_iter().sum::<usize>()
+                0 $( + <$Material>::size_int() )+
             }
             fn size_float() -> usize {
-                let sizes = [
-                    $( 1 + <$Material>::size_float(), )+
-                ];
-                sizes.into_iter().sum::<usize>()
+                0 $( + 1 + <$Material>::size_float() )+
             }
             fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
                 use $crate::pack::*;
@@ -120,6 +149,184 @@ macro_rules! material_combine {
                     .pack(&self.$field.1)
                 )+;
             }
+            fn layout() -> $crate::layout::Layout {
+                $crate::layout::Layout::builder()
+                $( .field::<(f64, $Material)>(stringify!($field)) )+
+                .build()
+            }
+        }
+
+        // `for<'a_>` makes the bounds non-trivial, so the impl is just
+        // skipped if some material doesn't implement `Unpack`.
+        impl $crate::Unpack for $Combine where $( for<'a_> $Material: $crate::Unpack ),+ {
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+                let mut unpacker = $crate::unpack::Unpacker::new(buffer_int, buffer_float);
+                Self {
+                    $( $field: (unpacker.unpack(), unpacker.unpack()), )+
+                }
+            }
+        }
+    };
+    ($Combine:ident { $( $field:ident : $Material:ty [ $Texture:ty ] ),+ $(,)? }) => {
+        pub struct $Combine {
+            $( pub $field: (f64, $Material, $Texture), )+
+        }
+
+        impl $Combine {
+            pub fn new(
+                $( $field: (f64, $Material, $Texture), )+
+            ) -> Self {
+                Self {
+                    $( $field, )+
+                }
+            }
+
+            /// Sampling (`bounce_ext`) chooses one of the materials randomly,
+            /// other methods return the sum of values of materials multiplied by their weights.
+            /// `bounce` knows nothing about the surface, so it is defined via `bounce_ext`.
+            #[allow(unused_assignments)]
+            fn method_source(method: &str) -> String {
+                use $crate::{prelude::*, material::*, texture::*};
+
+                let cpref = format!(
+                    "{}_{}",
+                    MaterialClass::name(),
+                    method,
+                ).to_uppercase();
+                let sample = method == "bounce_ext";
+
+                let mut weights = Vec::new();
+                let mut cases = Vec::new();
+                let (mut si, mut sf) = (0, 0);
+                $(
+                    let i = weights.len();
+                    weights.push(format!(
+                        "\tfloat w_{} = fbuf[{}]*TEXTURE_WEIGHT({}, {}, {});",
+                        i, sf, <$Texture as Instance<TextureClass>>::inst_name(),
+                        si + <$Material>::size_int(), sf + 1 + <$Material>::size_float(),
+                    ));
+                    let call = format!(
+                        "{}_{}({}_ARGS_B({}, {}))",
+                        <$Material as Instance<MaterialClass>>::inst_name(),
+                        method, cpref, si, sf + 1,
+                    );
+                    cases.push(if sample {
+                        [
+                            format!("\tif (alpha < w_{}) {{", i),
+                            format!("\t\treturn {};", call),
+                            "\t}".to_string(),
+                            format!("\talpha -= w_{};", i),
+                        ].join("\n")
+                    } else {
+                        format!("\tsum += w_{}*{};", i, call)
+                    });
+                    si += <$Material>::size_int() + <$Texture>::size_int();
+                    sf += 1 + <$Material>::size_float() + <$Texture>::size_float();
+                )+
+                let total = format!(
+                    "\tfloat total = {};",
+                    (0..weights.len()).map(|i| format!("w_{}", i)).collect::<Vec<_>>().join(" + "),
+                );
+                let body = if sample {
+                    [
+                        "\tfloat alpha = total*random_uniform(seed);".to_string(),
+                        cases.join("\n"),
+                        format!("\treturn {}_RET_BAD;", cpref),
+                    ].join("\n")
+                } else {
+                    [
+                        format!("\t{}_RET sum = {}_RET_BAD;", cpref, cpref),
+                        cases.join("\n"),
+                        format!("\treturn total > 0.0f ? sum/total : {}_RET_BAD;", cpref),
+                    ].join("\n")
+                };
+                [
+                    format!(
+                        "{}_RET {}_{}({}_ARGS_DEF) {{",
+                        cpref, Self::inst_name(), method, cpref,
+                    ),
+                    weights.join("\n"),
+                    total,
+                    body,
+                    "}".to_string(),
+                ].join("\n")
+            }
+        }
+
+        impl $crate::material::Material for $Combine {
+            fn brightness(&self) -> f64 {
+                // Weights vary over the surface, so the brightest material is taken
+                let mut brightness = 0.0f64;
+                $(
+                    if self.$field.0 > 0.0 {
+                        brightness = brightness.max(self.$field.1.brightness());
+                    }
+                )+
+                brightness
+            }
+        }
+
+        impl $crate::Instance<$crate::material::MaterialClass> for $Combine {
+            fn source(cache: &mut std::collections::HashSet<u64>) -> String {
+                use $crate::{prelude::*, material::*, texture::*};
+                if !cache.insert(Self::type_hash()) {
+                    return String::new()
+                }
+                let mut ms = Vec::new();
+                for method in MaterialClass::methods().into_iter().filter(|m| m != "bounce") {
+                    ms.push(Self::method_source(&method));
+                }
+                ms.push(format!("MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF({})", Self::inst_name()));
+                [
+                    $( <$Material as Instance<MaterialClass>>::full_source(cache), )+
+                    $( <$Texture as Instance<TextureClass>>::source(cache), )+
+                    "#include <clay_core/random.h>".to_string(),
+                    ms.join("\n"),
+                ].join("\n")
+            }
+
+            fn inst_name() -> String {
+                use $crate::TypeHash;
+                format!("__combine_{:x}", Self::type_hash())
+            }
+
+            fn implemented_methods() -> Vec<String> {
+                use $crate::{prelude::*, material::*};
+                MaterialClass::methods()
+            }
+        }
+
+        impl $crate::Pack for $Combine {
+            fn size_int() -> usize {
+                0 $( + <$Material>::size_int() + <$Texture>::size_int() )+
+            }
+            fn size_float() -> usize {
+                0 $( + 1 + <$Material>::size_float() + <$Texture>::size_float() )+
+            }
+            fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+                use $crate::pack::*;
+                Packer::new(buffer_int, buffer_float)
+                $(
+                    .pack(&self.$field.0)
+                    .pack(&self.$field.1)
+                    .pack(&self.$field.2)
+                )+;
+            }
+            fn layout() -> $crate::layout::Layout {
+                $crate::layout::Layout::builder()
+                $( .field::<(f64, $Material, $Texture)>(stringify!($field)) )+
+                .build()
+            }
+        }
+
+        impl $crate::Unpack for $Combine
+        where $( for<'a_> $Material: $crate::Unpack, for<'a_> $Texture: $crate::Unpack ),+ {
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+                let mut unpacker = $crate::unpack::Unpacker::new(buffer_int, buffer_float);
+                Self {
+                    $( $field: (unpacker.unpack(), unpacker.unpack(), unpacker.unpack()), )+
+                }
+            }
         }
     };
 }
@@ -129,6 +336,7 @@ macro_rules! material_combine {
 mod check {
     use crate::{
         material::test::TestMaterial,
+        texture::Checker,
         material_combine,
     };
 
@@ -136,4 +344,76 @@ mod check {
         m1: TestMaterial<i32>,
         m2: TestMaterial<f32>,
     });
+
+    material_combine!(TestTexturedCombine {
+        m1: TestMaterial<i32> [Checker],
+        m2: TestMaterial<f32> [Checker],
+    });
+
+    #[test]
+    fn source() {
+        use std::collections::HashSet;
+        use crate::{prelude::*, material::MaterialClass};
+        let src = <TestCombine as Instance<MaterialClass>>::source(&mut HashSet::new());
+        assert!(src.contains("if (alpha < fbuf[0]) {\n\t\treturn test_material_bounce(MATERIAL_BOUNCE_ARGS_B(0, 1));"));
+        assert!(src.contains("sum += (fbuf[1] - prev)*test_material_eval(MATERIAL_EVAL_ARGS_B(0, 2));"));
+        assert!(src.contains("MATERIAL_PDF_RET sum = MATERIAL_PDF_RET_BAD;"));
+    }
+
+    #[test]
+    fn weights() {
+        use nalgebra::Vector3;
+        use crate::material::{Material, Luminous};
+        material_combine!(LightCombine {
+            dim: Luminous,
+            bright: Luminous,
+        });
+        let x = LightCombine::new(
+            (1.0, Luminous::new(Vector3::repeat(1.0))),
+            (3.0, Luminous::new(Vector3::repeat(2.0))),
+        );
+        assert_eq!((x.dim.0, x.bright.0), (0.25, 1.0));
+        assert_eq!(x.brightness(), 1.75);
+    }
+
+    #[test]
+    fn unpack() {
+        use crate::{Pack, Unpack};
+        let x = TestCombine {
+            m1: (0.25, TestMaterial::new()),
+            m2: (1.0, TestMaterial::new()),
+        };
+        let mut buffer_float = vec![0.0; TestCombine::size_float()];
+        x.pack_to(&mut [], &mut buffer_float);
+        let y = TestCombine::unpack_from(&[], &buffer_float);
+        assert_eq!((y.m1.0, y.m2.0), (0.25, 1.0));
+    }
+
+    #[test]
+    fn textured() {
+        use std::collections::HashSet;
+        use nalgebra::Vector3;
+        use crate::{prelude::*, material::MaterialClass};
+        let checker = Checker::new(1.0, Vector3::zeros(), Vector3::new(1.0, 1.0, 1.0));
+        let x = TestTexturedCombine::new(
+            (0.5, TestMaterial::new(), checker.clone()),
+            (2.0, TestMaterial::new(), checker.clone()),
+        );
+        assert_eq!(TestTexturedCombine::size_float(), 2*(1 + Checker::size_float()));
+        let mut buffer_float = vec![0.0; TestTexturedCombine::size_float()];
+        x.pack_to(&mut [], &mut buffer_float);
+        let y = TestTexturedCombine::unpack_from(&[], &buffer_float);
+        assert_eq!((y.m1.0, y.m2.0), (0.5, 2.0));
+        assert_eq!(y.m2.2, checker);
+
+        let src = <TestTexturedCombine as Instance<MaterialClass>>::full_source(&mut HashSet::new());
+        assert!(src.contains("float w_1 = fbuf[8]*TEXTURE_WEIGHT(checker, 0, 9);"));
+        assert!(src.contains("float alpha = total*random_uniform(seed);"));
+        assert!(src.contains("if (alpha < w_0) {\n\t\treturn test_material_bounce_ext(MATERIAL_BOUNCE_EXT_ARGS_B(0, 1));"));
+        assert!(src.contains("sum += w_1*test_material_pdf(MATERIAL_PDF_ARGS_B(0, 9));"));
+        assert!(src.contains(&format!(
+            "MATERIAL_BOUNCE_FROM_BOUNCE_EXT_FN_DEF({})", TestTexturedCombine::inst_name(),
+        )));
+        assert!(!src.contains(&format!("{}_bounce(", TestTexturedCombine::inst_name())));
+    }
 }
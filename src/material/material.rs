@@ -1,7 +1,8 @@
 use nalgebra::{Vector3};
 use crate::{
     prelude::*,
-    material::Colored,
+    material::{Colored, Textured, Blend},
+    texture::Texture,
 };
 
 
@@ -22,6 +23,16 @@ pub trait Material: Pack + Instance<MaterialClass> {
     fn color_with(self, color: Vector3<f64>) -> Colored<Self> {
         Colored::new(self, color)
     }
+
+    /// Modulates the color of the material by the texture
+    fn texture_with<T: Texture>(self, texture: T) -> Textured<Self, T> {
+        Textured::new(self, texture)
+    }
+
+    /// Mixes the material with another one, the texture gives the weight of the other material
+    fn blend_with<M: Material, T: Texture>(self, other: M, weight: T) -> Blend<Self, M, T> {
+        Blend::new(self, other, weight)
+    }
 }
 
 /// Device interface for material.
@@ -35,13 +46,54 @@ pub trait Material: Pack + Instance<MaterialClass> {
 /// ) {
 ///     ...
 /// }
+///
+/// MATERIAL_EVAL_RET <material>_eval(
+///     MATERIAL_EVAL_ARGS_DEF
+/// ) {
+///     ...
+/// }
+///
+/// MATERIAL_PDF_RET <material>_pdf(
+///     MATERIAL_PDF_ARGS_DEF
+/// ) {
+///     ...
+/// }
 /// ```
+///
+/// `bounce` samples the new direction of the ray, `eval` and `pdf` return the BSDF value
+/// and the density of `bounce` for an arbitrary direction, so the scene could weight
+/// the light source sampling against the material sampling.
+/// Specular materials return zero from both of them.
+///
+/// `bounce_ext` is the optional extension of `bounce` that also gets the surface
+/// reported by the shape and the texture atlas, materials that don't implement it
+/// get the one that calls `bounce`.
+/// `eval` and `pdf` are optional too, materials that don't implement them
+/// are treated as specular ones.
 pub enum MaterialClass {}
 impl Class for MaterialClass {
     fn name() -> String {
         "material".to_string()
     }
     fn methods() -> Vec<String> {
-        vec!["bounce".to_string()]
+        vec![
+            "bounce".to_string(),
+            "bounce_ext".to_string(),
+            "eval".to_string(),
+            "pdf".to_string(),
+        ]
+    }
+    fn optional_methods() -> Vec<String> {
+        vec![
+            "bounce_ext".to_string(),
+            "eval".to_string(),
+            "pdf".to_string(),
+        ]
+    }
+    fn default_source(inst_name: &str, method: &str) -> String {
+        match method {
+            "bounce_ext" => format!("MATERIAL_BOUNCE_EXT_FROM_BOUNCE_FN_DEF({})", inst_name),
+            _ => format!("MATERIAL_{}_DEFAULT_FN_DEF({})", method.to_uppercase(), inst_name),
+        }
     }
 }
@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use crate::{
+    prelude::*,
+    material::*,
+};
+
+
+/// Rough metal described by the GGX (Trowbridge-Reitz) microfacet model.
+///
+/// The reflectance is given by the complex index of refraction `eta + i*k` for red, green and blue,
+/// so the metal gets its color from the Fresnel equations. It could be tinted further by `Colored`.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Conductor {
+    /// Perceptual roughness, zero corresponds to the ideal mirror.
+    pub roughness: f64,
+    /// Real part of the index of refraction.
+    pub eta: Vector3<f64>,
+    /// Imaginary part of the index of refraction (extinction coefficient).
+    pub k: Vector3<f64>,
+}
+
+impl Conductor {
+    pub fn new(roughness: f64, eta: Vector3<f64>, k: Vector3<f64>) -> Self {
+        Self { roughness, eta, k }
+    }
+
+    pub fn gold(roughness: f64) -> Self {
+        Self::new(roughness, Vector3::new(0.143, 0.374, 1.442), Vector3::new(3.983, 2.386, 1.603))
+    }
+    pub fn silver(roughness: f64) -> Self {
+        Self::new(roughness, Vector3::new(0.155, 0.117, 0.138), Vector3::new(4.828, 3.122, 2.147))
+    }
+    pub fn copper(roughness: f64) -> Self {
+        Self::new(roughness, Vector3::new(0.200, 0.924, 1.102), Vector3::new(3.912, 2.452, 2.142))
+    }
+    pub fn aluminium(roughness: f64) -> Self {
+        Self::new(roughness, Vector3::new(1.657, 0.880, 0.521), Vector3::new(9.224, 6.270, 4.837))
+    }
+
+    /// Reflectance at the normal incidence.
+    pub fn reflectance(&self) -> Vector3<f64> {
+        self.eta.zip_map(&self.k, |n, k| {
+            ((n - 1.0).powi(2) + k*k)/((n + 1.0).powi(2) + k*k)
+        })
+    }
+}
+
+impl Material for Conductor {
+    fn brightness(&self) -> f64 {
+        0.0
+    }
+}
+
+impl Instance<MaterialClass> for Conductor {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/material/conductor.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "conductor".to_string()
+    }
+    fn implemented_methods() -> Vec<String> {
+        MaterialClass::methods()
+    }
+}
+
+/// Diffuse surface under the rough clear coating, like plastic or varnished wood.
+///
+/// The coating is a GGX microfacet dielectric, so the surface has the white specular highlight
+/// that becomes stronger at grazing angles. `Colored` tints the highlight too,
+/// so the base color is usually given here.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Plastic {
+    /// Perceptual roughness of the coating.
+    pub roughness: f64,
+    /// Index of refraction of the coating.
+    pub ior: f64,
+    /// Color of the diffuse base.
+    pub color: Vector3<f64>,
+}
+
+impl Plastic {
+    pub fn new(roughness: f64, ior: f64, color: Vector3<f64>) -> Self {
+        Self { roughness, ior, color }
+    }
+}
+
+impl Material for Plastic {
+    fn brightness(&self) -> f64 {
+        0.0
+    }
+}
+
+impl Instance<MaterialClass> for Plastic {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/material/plastic.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "plastic".to_string()
+    }
+    fn implemented_methods() -> Vec<String> {
+        MaterialClass::methods()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use nalgebra::Vector3;
+    use crate::{
+        prelude::*,
+        material::*,
+    };
+
+    #[test]
+    fn reflectance() {
+        let gold = Conductor::gold(0.2).reflectance();
+        assert!(gold.x > gold.y && gold.y > gold.z);
+        assert!(gold.x > 0.9 && gold.z < 0.5);
+        let silver = Conductor::silver(0.0).reflectance();
+        assert!(silver.iter().all(|&r| r > 0.9));
+    }
+
+    #[test]
+    fn source() {
+        assert_eq!(Conductor::size_float(), 7);
+        assert_eq!(Plastic::size_float(), 5);
+
+        let src = Colored::<Conductor>::source(&mut HashSet::new());
+        assert!(src.contains("#include <clay_core/material/conductor.h>"));
+        assert!(src.contains("COLORED_MATERIAL_FN_DEF(__conductor_colored_"));
+        let plastic = Plastic::new(0.3, 1.5, Vector3::new(0.8, 0.1, 0.1));
+        assert_eq!(plastic.color_with(Vector3::repeat(0.5)).brightness(), 0.0);
+    }
+}
@@ -3,6 +3,14 @@ pub use material::*;
 
 mod colored;
 pub use colored::*;
+mod textured;
+pub use textured::*;
+mod blend;
+pub use blend::*;
+mod basic;
+pub use basic::*;
+mod microfacet;
+pub use microfacet::*;
 
 mod select;
 mod combine;
@@ -36,3 +36,7 @@ impl<T> Pack for TestMaterial<T> {
     fn size_float() -> usize { 0 }
     fn pack_to(&self, _buffer_int: &mut [i32], _buffer_float: &mut [f32]) {}
 }
+
+impl<T> Unpack for TestMaterial<T> {
+    fn unpack_from(_buffer_int: &[i32], _buffer_float: &[f32]) -> Self { Self::new() }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+use crate::{
+    prelude::*,
+    material::*,
+    texture::*,
+};
+
+/// Modulates the color of an existing material by the texture.
+#[derive(Clone, Debug, Pack, Unpack)]
+pub struct Textured<M: Material, T: Texture = ImageTexture> {
+    pub material: M,
+    pub texture: T,
+}
+
+impl<M: Material, T: Texture> Textured<M, T> {
+    pub fn new(material: M, texture: T) -> Self {
+        Self { material, texture }
+    }
+}
+
+impl<M: Material, T: Texture> Material for Textured<M, T> {
+    fn brightness(&self) -> f64 {
+        self.material.brightness()
+    }
+}
+
+impl<M: Material, T: Texture> Instance<MaterialClass> for Textured<M, T> {
+    fn source(cache: &mut HashSet<u64>) -> String {
+        if !cache.insert(Self::type_hash()) {
+            return String::new()
+        }
+        let name = Self::inst_name();
+        [
+            M::full_source(cache),
+            T::source(cache),
+            "#include <clay_core/material/textured.h>".to_string(),
+            Self::layout().defines(&name),
+            format!(
+                "TEXTURED_MATERIAL_FN_DEF({}, {}, {}, {}_MATERIAL_DI, {}_MATERIAL_DF, {}_TEXTURE_DI, {}_TEXTURE_DF)",
+                name, M::inst_name(), T::inst_name(),
+                name, name, name, name,
+            ),
+        ].join("\n")
+    }
+    fn inst_name() -> String {
+        format!(
+            "__{}_textured_{:x}",
+            M::inst_name(),
+            Self::type_hash(),
+        )
+    }
+    fn implemented_methods() -> Vec<String> {
+        MaterialClass::methods()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use crate::{
+        prelude::*,
+        material::*,
+        texture::*,
+    };
+
+    #[test]
+    fn source() {
+        let texture = ImageTexture { offset: 6, width: 4, height: 2 };
+        let material = Diffuse {}.texture_with(texture.clone());
+        assert_eq!(material.texture, texture);
+        assert_eq!(Textured::<Diffuse>::size_int(), 3);
+
+        let name = Textured::<Diffuse>::inst_name();
+        let source = Textured::<Diffuse>::source(&mut HashSet::new());
+        assert!(source.contains("#include <clay_core/texture/image.h>"));
+        assert!(source.contains(&format!("#define {}_TEXTURE_DI 0", name)));
+        assert!(source.contains(&format!(
+            "TEXTURED_MATERIAL_FN_DEF({}, diffuse, image_texture", name,
+        )));
+    }
+}
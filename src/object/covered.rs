@@ -7,7 +7,7 @@ use crate::{
 };
 
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Pack, Unpack)]
 /// Object obtained by covering a shape with a material.
 pub struct Covered<S: Shape, M: Material> {
     pub shape: S,
@@ -21,7 +21,7 @@ impl<S: Shape, M: Material> Covered<S, M> {
 
     fn shape_source(cache: &mut HashSet<u64>) -> String {
         [
-            S::source(cache),
+            S::full_source(cache),
             ShapeClass::methods().into_iter().map(|method| {
                 format!(
                     "#define {}_{} {}_{}",
@@ -34,7 +34,7 @@ impl<S: Shape, M: Material> Covered<S, M> {
 
     fn material_source(cache: &mut HashSet<u64>) -> String {
         [
-            M::source(cache),
+            M::full_source(cache),
             MaterialClass::methods().into_iter().map(|method| {
                 let cpref = format!("{}_{}", MaterialClass::name(), method).to_uppercase();
                 [
@@ -42,8 +42,8 @@ impl<S: Shape, M: Material> Covered<S, M> {
                     &format!("\t{}_ARGS_DEF", cpref),
                     ") {",
                     &format!(
-                        "\treturn {}_{}({}_ARGS_B({}, {}));",
-                        M::inst_name(), method, cpref, S::size_int(), S::size_float(),
+                        "\treturn {}_{}({}_ARGS_B({}_MATERIAL_DI, {}_MATERIAL_DF));",
+                        M::inst_name(), method, cpref, Self::inst_name(), Self::inst_name(),
                     ),
                     "}",
                 ].join("\n")
@@ -60,6 +60,7 @@ impl<S: Shape, M: Material> Instance<ObjectClass> for Covered<S, M> {
             return String::new()
         }
         [
+            Self::layout().defines(&Self::inst_name()),
             Self::shape_source(cache),
             Self::material_source(cache),
         ].join("\n")
@@ -67,20 +68,8 @@ impl<S: Shape, M: Material> Instance<ObjectClass> for Covered<S, M> {
     fn inst_name() -> String {
         format!("__covered_{:x}", Self::type_hash())
     }
-}
-
-impl<S: Shape, M: Material> Pack for Covered<S, M> {
-    fn size_int() -> usize {
-        S::size_int() + M::size_int()
-    }
-    fn size_float() -> usize {
-        S::size_float() + M::size_float()
-    }
-
-    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        Packer::new(buffer_int, buffer_float)
-        .pack(&self.shape)
-        .pack(&self.material);
+    fn implemented_methods() -> Vec<String> {
+        ObjectClass::methods()
     }
 }
 
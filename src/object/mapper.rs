@@ -2,10 +2,12 @@ use std::collections::HashSet;
 use crate::{
     prelude::*,
     map::*,
+    shape::*,
     object::*,
 };
 
 /// A new object obtained by applying some mapping to another object.
+#[derive(Pack, Unpack)]
 pub struct ObjectMapper<O: Object, M: Map> {
     pub object: O,
     pub map: M,
@@ -19,21 +21,28 @@ impl<O: Object, M: Map> ObjectMapper<O, M> {
 
 impl<O: Object, M: Map> Object for ObjectMapper<O, M> {}
 
+impl<B: MapBound, O: Object + Bounded<B>, M: Map> Bounded<B> for ObjectMapper<O, M> {
+    fn bound(&self) -> Option<B> {
+        self.object.bound().map(|b| b.map_bound(&self.map))
+    }
+}
+
 impl<O: Object, M: Map> Instance<ObjectClass> for ObjectMapper<O, M> {
     fn source(cache: &mut HashSet<u64>) -> String {
         if !cache.insert(Self::type_hash()) {
             return String::new()
         }
         [
-            O::source(cache),
+            O::full_source(cache),
             M::source(cache),
             "#include <clay_core/object/mapper.h>".to_string(),
+            Self::layout().defines(&Self::inst_name()),
             format!(
-                "MAP_OBJECT_FN_DEF({}, {}, {}, {}, {})",
+                "MAP_OBJECT_FN_DEF({}, {}, {}, {}_MAP_DI, {}_MAP_DF)",
                 Self::inst_name(),
                 O::inst_name(),
                 M::inst_name(),
-                O::size_int(), O::size_float(),
+                Self::inst_name(), Self::inst_name(),
             ),
         ].join("\n")
     }
@@ -43,19 +52,7 @@ impl<O: Object, M: Map> Instance<ObjectClass> for ObjectMapper<O, M> {
             Self::type_hash(),
         )
     }
-}
-
-
-impl<O: Object, M: Map> Pack for ObjectMapper<O, M> {
-    fn size_int() -> usize {
-        O::size_int() + M::size_int()
-    }
-    fn size_float() -> usize {
-        O::size_float() + M::size_float()
-    }
-    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        Packer::new(buffer_int, buffer_float)
-        .pack(&self.object)
-        .pack(&self.map);
+    fn implemented_methods() -> Vec<String> {
+        ObjectClass::methods()
     }
 }
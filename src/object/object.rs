@@ -19,4 +19,17 @@ impl Class for ObjectClass {
         methods.append(&mut MaterialClass::methods());
         methods
     }
+    fn optional_methods() -> Vec<String> {
+        let mut methods = ShapeClass::optional_methods();
+        methods.append(&mut MaterialClass::optional_methods());
+        methods
+    }
+    fn default_source(inst_name: &str, method: &str) -> String {
+        // Object macros are the same as the shape and material ones.
+        if ShapeClass::optional_methods().iter().any(|m| m == method) {
+            ShapeClass::default_source(inst_name, method)
+        } else {
+            MaterialClass::default_source(inst_name, method)
+        }
+    }
 }
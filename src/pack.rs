@@ -1,4 +1,13 @@
-use nalgebra::{Scalar, Vector3, Matrix3};
+use nalgebra::{
+    Scalar, RealField,
+    Vector2, Vector3, Vector4,
+    Matrix2, Matrix3, Matrix4,
+    Point2, Point3,
+    UnitQuaternion, Isometry3,
+};
+
+pub use clay_core_derive::Pack;
+use crate::layout::Layout;
 
 
 /// Something that could be packed to `i32` buffers
@@ -35,6 +44,13 @@ pub trait Pack {
     ///
     /// Buffers *must* be of size greater or equal to object's one.
     fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]);
+
+    /// Layout of the object fields in the buffers.
+    ///
+    /// Empty by default that means the object is opaque.
+    fn layout() -> Layout {
+        Layout::new()
+    }
 }
 
 pub trait PackerInt {
@@ -72,6 +88,20 @@ impl<'a> Packer<'a> {
             buffer_float: &mut self.buffer_float[T::size_float()..],
         }
     }
+    /// Packs an object into integer buffer only.
+    pub fn pack_int<T: PackInt>(self, t: &T) -> Self {
+        Self {
+            buffer_int: self.buffer_int.pack(t),
+            buffer_float: self.buffer_float,
+        }
+    }
+    /// Packs an object into float buffer only.
+    pub fn pack_float<T: PackFloat>(self, t: &T) -> Self {
+        Self {
+            buffer_int: self.buffer_int,
+            buffer_float: self.buffer_float.pack(t),
+        }
+    }
 }
 
 
@@ -129,41 +159,253 @@ impl Pack for f64 {
     }
 }
 
-impl<T: PackFloat + Scalar> PackFloat for Vector3<T> {
-    fn size() -> usize { 3*T::size() }
+impl PackInt for bool {
+    fn size() -> usize { 1 }
+    fn pack_int_to(&self, buffer: &mut [i32]) {
+        buffer[0] = *self as i32;
+    }
+}
+impl Pack for bool {
+    fn size_int() -> usize { 1 }
+    fn size_float() -> usize { 0 }
+    fn pack_to(&self, buffer_int: &mut [i32], _buffer_float: &mut [f32]) {
+        buffer_int[0] = *self as i32;
+    }
+}
+
+macro_rules! impl_matrix {
+    ($( $Matrix:ident: $n:expr ),+ $(,)?) => { $(
+        impl<T: PackFloat + Scalar> PackFloat for $Matrix<T> {
+            fn size() -> usize { $n*T::size() }
+            fn pack_float_to(&self, mut buffer: &mut [f32]) {
+                for x in self.as_slice() {
+                    buffer = buffer.pack(x);
+                }
+            }
+        }
+        impl<T: Pack + Scalar> Pack for $Matrix<T> {
+            fn size_int() -> usize { $n*T::size_int() }
+            fn size_float() -> usize { $n*T::size_float() }
+            fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+                let mut packer = Packer::new(buffer_int, buffer_float);
+                for x in self.as_slice() {
+                    packer = packer.pack(x);
+                }
+            }
+        }
+    )+ };
+}
+impl_matrix!(
+    Vector2: 2, Vector3: 3, Vector4: 4,
+    Matrix2: 4, Matrix3: 9, Matrix4: 16,
+);
+
+macro_rules! impl_point {
+    ($( $Point:ident: $Vector:ident ),+ $(,)?) => { $(
+        impl<T: PackFloat + Scalar> PackFloat for $Point<T> {
+            fn size() -> usize { <$Vector<T> as PackFloat>::size() }
+            fn pack_float_to(&self, buffer: &mut [f32]) {
+                self.coords.pack_float_to(buffer);
+            }
+        }
+        impl<T: Pack + Scalar> Pack for $Point<T> {
+            fn size_int() -> usize { $Vector::<T>::size_int() }
+            fn size_float() -> usize { $Vector::<T>::size_float() }
+            fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+                self.coords.pack_to(buffer_int, buffer_float);
+            }
+        }
+    )+ };
+}
+impl_point!(Point2: Vector2, Point3: Vector3);
+
+/// Quaternion is packed as `(x, y, z, w)` where `w` is the scalar part.
+impl<T: PackFloat + RealField> PackFloat for UnitQuaternion<T> {
+    fn size() -> usize { <Vector4<T> as PackFloat>::size() }
+    fn pack_float_to(&self, buffer: &mut [f32]) {
+        self.coords.pack_float_to(buffer);
+    }
+}
+impl<T: Pack + RealField> Pack for UnitQuaternion<T> {
+    fn size_int() -> usize { Vector4::<T>::size_int() }
+    fn size_float() -> usize { Vector4::<T>::size_float() }
+    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+        self.coords.pack_to(buffer_int, buffer_float);
+    }
+}
+
+/// Isometry is packed as translation vector followed by rotation quaternion.
+impl<T: PackFloat + RealField> PackFloat for Isometry3<T> {
+    fn size() -> usize {
+        <Vector3<T> as PackFloat>::size() + <UnitQuaternion<T> as PackFloat>::size()
+    }
+    fn pack_float_to(&self, buffer: &mut [f32]) {
+        buffer
+        .pack(&self.translation.vector)
+        .pack(&self.rotation);
+    }
+}
+impl<T: Pack + RealField> Pack for Isometry3<T> {
+    fn size_int() -> usize {
+        Vector3::<T>::size_int() + UnitQuaternion::<T>::size_int()
+    }
+    fn size_float() -> usize {
+        Vector3::<T>::size_float() + UnitQuaternion::<T>::size_float()
+    }
+    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+        Packer::new(buffer_int, buffer_float)
+        .pack(&self.translation.vector)
+        .pack(&self.rotation);
+    }
+}
+
+impl<T: PackInt, const N: usize> PackInt for [T; N] {
+    fn size() -> usize { N*T::size() }
+    fn pack_int_to(&self, mut buffer: &mut [i32]) {
+        for x in self.iter() {
+            buffer = buffer.pack(x);
+        }
+    }
+}
+impl<T: PackFloat, const N: usize> PackFloat for [T; N] {
+    fn size() -> usize { N*T::size() }
     fn pack_float_to(&self, mut buffer: &mut [f32]) {
-        for x in self.as_slice() {
+        for x in self.iter() {
             buffer = buffer.pack(x);
         }
     }
 }
-impl<T: Pack + Scalar> Pack for Vector3<T> {
-    fn size_int() -> usize { 3*T::size_int() }
-    fn size_float() -> usize { 3*T::size_float() }
+impl<T: Pack, const N: usize> Pack for [T; N] {
+    fn size_int() -> usize { N*T::size_int() }
+    fn size_float() -> usize { N*T::size_float() }
     fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
         let mut packer = Packer::new(buffer_int, buffer_float);
-        for x in self.as_slice() {
+        for x in self.iter() {
             packer = packer.pack(x);
         }
     }
 }
 
+macro_rules! impl_tuple {
+    ($( ( $( $T:ident $i:tt ),+ ) ),+ $(,)?) => { $(
+        impl<$( $T: PackInt ),+> PackInt for ($( $T, )+) {
+            fn size() -> usize { 0 $( + $T::size() )+ }
+            fn pack_int_to(&self, buffer: &mut [i32]) {
+                buffer
+                $( .pack(&self.$i) )+;
+            }
+        }
+        impl<$( $T: PackFloat ),+> PackFloat for ($( $T, )+) {
+            fn size() -> usize { 0 $( + $T::size() )+ }
+            fn pack_float_to(&self, buffer: &mut [f32]) {
+                buffer
+                $( .pack(&self.$i) )+;
+            }
+        }
+        impl<$( $T: Pack ),+> Pack for ($( $T, )+) {
+            fn size_int() -> usize { 0 $( + $T::size_int() )+ }
+            fn size_float() -> usize { 0 $( + $T::size_float() )+ }
+            fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+                Packer::new(buffer_int, buffer_float)
+                $( .pack(&self.$i) )+;
+            }
+            fn layout() -> Layout {
+                Layout::builder()
+                $( .field::<$T>(stringify!($i)) )+
+                .build()
+            }
+        }
+    )+ };
+}
+impl_tuple!(
+    (A 0),
+    (A 0, B 1),
+    (A 0, B 1, C 2),
+    (A 0, B 1, C 2, D 3),
+    (A 0, B 1, C 2, D 3, E 4),
+    (A 0, B 1, C 2, D 3, E 4, F 5),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7),
+);
 
-impl<T: PackFloat + Scalar> PackFloat for Matrix3<T> {
-    fn size() -> usize { 9*T::size() }
-    fn pack_float_to(&self, mut buffer: &mut [f32]) {
-        for x in self.as_slice() {
-            buffer = buffer.pack(x);
+/// Optional value is packed as a presence flag followed by the value.
+///
+/// The size of the packed value doesn't depend on its presence
+/// so that the option could be stored in `InstanceBuffer`.
+/// If the value is absent then the space for it is filled with zeros.
+impl<T: PackInt> PackInt for Option<T> {
+    fn size() -> usize { 1 + T::size() }
+    fn pack_int_to(&self, buffer: &mut [i32]) {
+        let buffer = buffer.pack(&self.is_some());
+        match self {
+            Some(x) => x.pack_int_to(buffer),
+            None => buffer[..T::size()].iter_mut().for_each(|x| *x = 0),
         }
     }
 }
-impl<T: Pack + Scalar> Pack for Matrix3<T> {
-    fn size_int() -> usize { 9*T::size_int() }
-    fn size_float() -> usize { 9*T::size_float() }
+/// The same as the `PackInt` one but the flag is stored as `0.0` or `1.0`.
+impl<T: PackFloat> PackFloat for Option<T> {
+    fn size() -> usize { 1 + T::size() }
+    fn pack_float_to(&self, buffer: &mut [f32]) {
+        let flag = if self.is_some() { 1.0f32 } else { 0.0f32 };
+        let buffer = buffer.pack(&flag);
+        match self {
+            Some(x) => x.pack_float_to(buffer),
+            None => buffer[..T::size()].iter_mut().for_each(|x| *x = 0.0),
+        }
+    }
+}
+/// The presence flag is stored in the integer buffer.
+impl<T: Pack> Pack for Option<T> {
+    fn size_int() -> usize { 1 + T::size_int() }
+    fn size_float() -> usize { T::size_float() }
     fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        let mut packer = Packer::new(buffer_int, buffer_float);
-        for x in self.as_slice() {
-            packer = packer.pack(x);
+        let buffer_int = buffer_int.pack(&self.is_some());
+        match self {
+            Some(x) => x.pack_to(buffer_int, buffer_float),
+            None => {
+                buffer_int[..T::size_int()].iter_mut().for_each(|x| *x = 0);
+                buffer_float[..T::size_float()].iter_mut().for_each(|x| *x = 0.0);
+            },
         }
     }
+    fn layout() -> Layout {
+        Layout::builder()
+        .field_int::<bool>("is_some")
+        .field::<T>("value")
+        .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use crate::pack::*;
+
+    #[derive(Pack)]
+    struct Derived {
+        a: i32,
+        #[pack(float)]
+        b: Vector3<f64>,
+        #[pack(skip)]
+        _c: String,
+        #[pack(int)]
+        d: u32,
+        e: f32,
+    }
+
+    #[test]
+    fn derive() {
+        assert_eq!(Derived::size_int(), 2);
+        assert_eq!(Derived::size_float(), 4);
+
+        let x = Derived {
+            a: 1, b: Vector3::new(2.0, 3.0, 4.0),
+            _c: String::new(), d: 5, e: 6.0,
+        };
+        let (mut ibuf, mut fbuf) = (vec![0; 2], vec![0.0; 4]);
+        x.pack_to(&mut ibuf, &mut fbuf);
+        assert_eq!(ibuf, [1, 5]);
+        assert_eq!(fbuf, [2.0, 3.0, 4.0, 6.0]);
+    }
 }
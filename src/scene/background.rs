@@ -1,10 +1,28 @@
 use std::collections::HashSet;
+use nalgebra::Vector3;
+use ocl::prm;
 use crate::Store;
 
 
 /// Background of the scene.
 ///
 /// It defines that happens to the ray is it hasn't hit any object in the scene.
+///
+/// The device source should define `BACKGROUND_ARGS_DEF` and `BACKGROUND_ARGS` macros
+/// for the arguments of its data (there should be at least one)
+/// and the function `float3 __background(Ray ray, BACKGROUND_ARGS_DEF)`
+/// that returns the light coming from the direction of the ray.
+///
+/// The background could also be sampled as a light source, then the source should
+/// `#define BACKGROUND_SAMPLE` and provide the functions
+/// `float __background_sample(uint *seed, float3 pos, BACKGROUND_ARGS_DEF, float3 *dir)`
+/// that samples the direction and returns its density with respect to the solid angle,
+/// and `float __background_pdf(float3 pos, float3 dir, BACKGROUND_ARGS_DEF)`
+/// that returns the density for an arbitrary direction.
 pub trait Background: Store {
     fn source(cache: &mut HashSet<u64>) -> String;
 }
+
+pub(crate) fn prm_color(c: Vector3<f64>) -> prm::Float3 {
+    prm::Float3::new(c.x as f32, c.y as f32, c.z as f32)
+}
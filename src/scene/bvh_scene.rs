@@ -0,0 +1,385 @@
+use std::collections::HashSet;
+use ocl::{self, builders::KernelBuilder};
+use crate::{
+    prelude::*,
+    Context,
+    shape::*,
+    shape::mesh::{MeshStorage, MeshBuffer},
+    texture::{TextureStorage, TextureBuffer},
+    object::*,
+    scene::*,
+    scene::list_scene::{scene_source, read_only_buffer, TargetBuffer},
+    buffer::InstanceBuffer,
+    bvh::{Bvh, BvhKind},
+};
+
+
+/// Maximum number of objects in the hierarchy leaf.
+const LEAF_SIZE: usize = 2;
+/// Maximum depth of the hierarchy, the traversal stack `BVH_SCENE_STACK_SIZE` in `bvh_scene.h`
+/// holds one more node than the depth.
+const MAX_DEPTH: usize = 63;
+
+/// Function that gives the target of the object along with its brightness.
+type TargetFn<O, T> = fn(&O) -> Option<(T, f64)>;
+
+/// Scene that puts objects into the bounding volume hierarchy.
+///
+/// The hierarchy is built on the host using the surface area heuristic
+/// and traversed on the device, so the rendering time grows logarithmically with the number of objects.
+/// Unbounded objects (e.g. planes) are tested for every ray.
+///
+/// If objects are moved via `objects_mut` the hierarchy should be updated
+/// by `refit` (fast, keeps the structure) or `rebuild` (slow, optimal)
+/// before the `Store::update_data` call, targets are recomputed there as well.
+/// Without that the packed hierarchy is still refitted to the moved objects, but targets stay stale.
+pub struct BvhScene<O: Object + Bounded<BoundingBox>, T: Target, B: Background> {
+    objects: Vec<O>,
+    /// Objects added via `add_targeted` along with the function that gives their target.
+    targeted: Vec<(usize, TargetFn<O, T>)>,
+    targets: Vec<(T, f64)>,
+    /// Index of the object of each target.
+    target_objects: Vec<usize>,
+    bvh: Option<Bvh>,
+    /// Indices of objects that are placed in the hierarchy, the rest are unbounded.
+    bounded: Vec<usize>,
+    /// Geometry of the meshes referred by objects.
+    pub meshes: MeshStorage,
+    /// Images of the textures referred by materials.
+    pub textures: TextureStorage,
+    pub background: B,
+    /// Maximum number of bounces of the ray.
+    pub max_depth: usize,
+    /// Depth from which diffuse rays are terminated randomly (Russian roulette).
+    pub roulette_depth: usize,
+}
+
+impl<O: Object + Bounded<BoundingBox>, T: Target, B: Background> BvhScene<O, T, B> {
+    pub fn new(background: B) -> Self {
+        Self {
+            objects: Vec::new(),
+            targeted: Vec::new(),
+            targets: Vec::new(),
+            target_objects: Vec::new(),
+            bvh: None,
+            bounded: Vec::new(),
+            meshes: MeshStorage::new(),
+            textures: TextureStorage::new(),
+            background,
+            max_depth: 8,
+            roulette_depth: 3,
+        }
+    }
+
+    /// Adds the object. The hierarchy will be rebuilt on the next data update.
+    pub fn add(&mut self, object: O) {
+        self.objects.push(object);
+        self.bvh = None;
+    }
+
+    /// Adds the object and makes it a target while it emits some light.
+    pub fn add_targeted(&mut self, object: O) where O: Targeted<T> {
+        self.targeted.push((self.objects.len(), <O as Targeted<T>>::target));
+        self.add(object);
+        self.update_targets();
+    }
+
+    /// Takes targets from the current state of targeted objects.
+    fn update_targets(&mut self) {
+        self.targets.clear();
+        self.target_objects.clear();
+        for &(index, target) in self.targeted.iter() {
+            if let Some((target, brightness)) = target(&self.objects[index]) {
+                if brightness > 0.0 {
+                    self.targets.push((target, brightness));
+                    self.target_objects.push(index);
+                }
+            }
+        }
+    }
+
+    pub fn objects(&self) -> &[O] {
+        &self.objects
+    }
+    /// Objects for modification. Call `refit` or `rebuild` after they are moved.
+    pub fn objects_mut(&mut self) -> &mut [O] {
+        &mut self.objects
+    }
+    pub fn targets(&self) -> &[(T, f64)] {
+        &self.targets
+    }
+
+    fn bounds(&self) -> (Vec<usize>, Vec<BoundingBox>) {
+        self.objects.iter().enumerate()
+        .filter_map(|(i, o)| o.bound().map(|b| (i, b)))
+        .unzip()
+    }
+
+    fn build(&self) -> (Vec<usize>, Bvh) {
+        let (bounded, bounds) = self.bounds();
+        (bounded, Bvh::build_sah(&bounds, LEAF_SIZE, MAX_DEPTH))
+    }
+
+    /// Builds the hierarchy from scratch.
+    pub fn rebuild(&mut self) {
+        let (bounded, bvh) = self.build();
+        self.bounded = bounded;
+        self.bvh = Some(bvh);
+        self.update_targets();
+    }
+
+    /// Updates bounds of the hierarchy nodes for moved objects.
+    ///
+    /// The hierarchy is rebuilt if it hasn't been built yet
+    /// or if some object has become bounded or unbounded.
+    pub fn refit(&mut self) {
+        let (bounded, bounds) = self.bounds();
+        match self.bvh {
+            Some(ref mut bvh) if bounded == self.bounded => {
+                bvh.refit(&bounds);
+                self.update_targets();
+            },
+            _ => self.rebuild(),
+        }
+    }
+
+    /// Packs the hierarchy for the device.
+    ///
+    /// Int buffer: nodes count, unbounded objects count, nodes (2 ints each),
+    /// object indices in leaf order, unbounded object indices.
+    /// Float buffer: node bounds (6 floats each).
+    ///
+    /// The stored hierarchy may be stale if objects were moved without `refit`,
+    /// so its copy is refitted to the current bounds (or rebuilt if the set of bounded objects has changed).
+    fn pack_bvh(&self) -> (Vec<i32>, Vec<f32>) {
+        let (bounded, bounds) = self.bounds();
+        let bvh = match self.bvh {
+            Some(ref bvh) if bounded == self.bounded => {
+                let mut bvh = bvh.clone();
+                bvh.refit(&bounds);
+                bvh
+            },
+            _ => Bvh::build_sah(&bounds, LEAF_SIZE, MAX_DEPTH),
+        };
+        let unbounded = (0..self.objects.len())
+        .filter(|i| bounded.binary_search(i).is_err())
+        .collect::<Vec<_>>();
+
+        let mut buffer_int = vec![bvh.nodes().len() as i32, unbounded.len() as i32];
+        let mut buffer_float = Vec::new();
+        for node in bvh.nodes() {
+            let (a, b) = match node.kind {
+                BvhKind::Leaf { first, count } => (first, count),
+                BvhKind::Inner { right } => (right, 0),
+            };
+            buffer_int.extend_from_slice(&[a as i32, b as i32]);
+            let b = &node.bound;
+            buffer_float.extend(b.min.iter().chain(b.max.iter()).map(|&x| x as f32));
+        }
+        buffer_int.extend(bvh.order().iter().map(|&i| bounded[i] as i32));
+        buffer_int.extend(unbounded.iter().map(|&i| i as i32));
+        (buffer_int, buffer_float)
+    }
+}
+
+impl<O: Object + Bounded<BoundingBox>, T: Target, B: Background> Scene for BvhScene<O, T, B> {
+    fn source(cache: &mut HashSet<u64>) -> String {
+        [
+            scene_source::<O, T, B>(cache),
+            "#include <clay_core/scene/bvh_scene.h>".to_string(),
+        ].join("\n")
+    }
+}
+
+impl<O: Object + Bounded<BoundingBox>, T: Target, B: Background> Store for BvhScene<O, T, B> {
+    type Data = BvhSceneData<O, T, B>;
+
+    fn create_data(&self, context: &Context) -> crate::Result<Self::Data> {
+        let (buffer_int, buffer_float) = self.pack_bvh();
+        Ok(Self::Data {
+            objects: InstanceBuffer::new(context, self.objects.iter())?,
+            bvh: BvhBuffer::new(context, &buffer_int, &buffer_float)?,
+            targets: TargetBuffer::new(context, &self.targets, &self.target_objects)?,
+            meshes: self.meshes.create_data(context)?,
+            textures: self.textures.create_data(context)?,
+            max_depth: self.max_depth as i32,
+            roulette_depth: self.roulette_depth as i32,
+            background: self.background.create_data(context)?,
+        })
+    }
+
+    fn update_data(&self, context: &Context, data: &mut Self::Data) -> crate::Result<()> {
+        if data.objects.count() == self.objects.len() {
+            data.objects.write(self.objects.iter())?;
+        } else {
+            data.objects = InstanceBuffer::new(context, self.objects.iter())?;
+        }
+        let (buffer_int, buffer_float) = self.pack_bvh();
+        data.bvh = BvhBuffer::new(context, &buffer_int, &buffer_float)?;
+        data.targets.update(context, &self.targets, &self.target_objects)?;
+        self.meshes.update_data(context, &mut data.meshes)?;
+        self.textures.update_data(context, &mut data.textures)?;
+        data.max_depth = self.max_depth as i32;
+        data.roulette_depth = self.roulette_depth as i32;
+        self.background.update_data(context, &mut data.background)
+    }
+}
+
+/// Device buffers of the scene hierarchy.
+struct BvhBuffer {
+    buffer_int: ocl::Buffer<i32>,
+    buffer_float: ocl::Buffer<f32>,
+}
+
+impl BvhBuffer {
+    fn new(context: &Context, buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        Ok(Self {
+            buffer_int: read_only_buffer(context, buffer_int)?,
+            buffer_float: read_only_buffer(context, buffer_float)?,
+        })
+    }
+}
+
+impl Push for BvhBuffer {
+    fn args_count() -> usize {
+        2
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        kb
+        .arg(None::<&ocl::Buffer<i32>>) // int buffer
+        .arg(None::<&ocl::Buffer<f32>>); // float buffer
+    }
+    fn args_set(&mut self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        k.set_arg(i + 0, &self.buffer_int)?;
+        k.set_arg(i + 1, &self.buffer_float)?;
+        Ok(())
+    }
+}
+
+/// Device data of the hierarchy scene.
+pub struct BvhSceneData<O: Object + 'static, T: Target + 'static, B: Background> {
+    objects: InstanceBuffer<O>,
+    bvh: BvhBuffer,
+    targets: TargetBuffer<T>,
+    meshes: MeshBuffer,
+    textures: TextureBuffer,
+    max_depth: i32,
+    roulette_depth: i32,
+    background: B::Data,
+}
+
+impl<O: Object, T: Target, B: Background> Push for BvhSceneData<O, T, B> {
+    fn args_count() -> usize {
+        InstanceBuffer::<O>::args_count() +
+        BvhBuffer::args_count() +
+        TargetBuffer::<T>::args_count() +
+        MeshBuffer::args_count() +
+        TextureBuffer::args_count() +
+        2 +
+        B::Data::args_count()
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        InstanceBuffer::<O>::args_def(kb);
+        BvhBuffer::args_def(kb);
+        TargetBuffer::<T>::args_def(kb);
+        MeshBuffer::args_def(kb);
+        TextureBuffer::args_def(kb);
+        kb
+        .arg(0i32) // max depth
+        .arg(0i32); // roulette depth
+        B::Data::args_def(kb);
+    }
+    fn args_set(&mut self, mut i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        self.objects.args_set(i, k)?;
+        i += InstanceBuffer::<O>::args_count();
+        self.bvh.args_set(i, k)?;
+        i += BvhBuffer::args_count();
+        self.targets.args_set(i, k)?;
+        i += TargetBuffer::<T>::args_count();
+        self.meshes.args_set(i, k)?;
+        i += MeshBuffer::args_count();
+        self.textures.args_set(i, k)?;
+        i += TextureBuffer::args_count();
+        k.set_arg(i + 0, self.max_depth)?;
+        k.set_arg(i + 1, self.roulette_depth)?;
+        i += 2;
+        self.background.args_set(i, k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use crate::{
+        prelude::*,
+        shape::{*, primitives::*},
+        material::{Luminous, test::TestMaterial},
+        object::Covered,
+        map::*,
+        scene::{*, test::TestBackground},
+    };
+
+    type TestObject = Covered<ShapeMapper<Sphere, Shift>, TestMaterial<i32>>;
+
+    fn sphere(x: f64) -> TestObject {
+        Covered::new(Sphere::new().map(Shift::new(Vector3::new(x, 0.0, 0.0))), TestMaterial::new())
+    }
+
+    #[test]
+    fn pack() {
+        let mut scene = BvhScene::<TestObject, BoundingSphere, TestBackground>::new(TestBackground::new());
+        for i in 0..5 {
+            scene.add(sphere(3.0*i as f64));
+        }
+        scene.rebuild();
+        let (buffer_int, buffer_float) = scene.pack_bvh();
+        let nodes = buffer_int[0] as usize;
+        assert_eq!(buffer_int[1], 0);
+        assert_eq!(buffer_int.len(), 2 + 2*nodes + 5);
+        assert_eq!(buffer_float.len(), 6*nodes);
+        // The root covers all the spheres.
+        assert_eq!(&buffer_float[..6], &[-1.0, -1.0, -1.0, 13.0, 1.0, 1.0]);
+
+        scene.objects_mut()[0] = sphere(-5.0);
+        scene.refit();
+        let (_, buffer_float) = scene.pack_bvh();
+        assert_eq!(&buffer_float[..6], &[-6.0, -1.0, -1.0, 13.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn pack_stale() {
+        let mut scene = BvhScene::<TestObject, BoundingSphere, TestBackground>::new(TestBackground::new());
+        for i in 0..5 {
+            scene.add(sphere(3.0*i as f64));
+        }
+        scene.rebuild();
+
+        // Moved without `refit`, as `update_data` sees the scene.
+        scene.objects_mut()[4] = sphere(20.0);
+        let (buffer_int, buffer_float) = scene.pack_bvh();
+        assert_eq!(&buffer_float[..6], &[-1.0, -1.0, -1.0, 21.0, 1.0, 1.0]);
+        assert_eq!(buffer_int[0] as usize, scene.bvh.as_ref().unwrap().nodes().len());
+    }
+
+    #[test]
+    fn targets() {
+        type LightObject = Covered<ShapeMapper<Sphere, Shift>, Luminous>;
+        let light = |x: f64| -> LightObject {
+            Covered::new(
+                Sphere::new().map(Shift::new(Vector3::new(x, 0.0, 0.0))),
+                Luminous::new(Vector3::repeat(1.0)),
+            )
+        };
+        let mut scene = BvhScene::<LightObject, BoundingSphere, TestBackground>::new(TestBackground::new());
+        scene.add(light(0.0));
+        scene.add_targeted(light(3.0));
+        assert_eq!(scene.target_objects, [1]);
+
+        scene.objects_mut()[1] = light(-4.0);
+        scene.refit();
+        assert_eq!(scene.target_objects, [1]);
+        let mut buffer_float = vec![0.0; BoundingSphere::size_float()];
+        scene.targets()[0].0.pack_to(&mut [], &mut buffer_float);
+        assert_eq!(buffer_float, [-4.0, 0.0, 0.0, 1.0]);
+    }
+}
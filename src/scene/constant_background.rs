@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use ocl::{self, prm, builders::KernelBuilder};
+use crate::{
+    prelude::*,
+    Context,
+    scene::*,
+};
+
+
+/// Background of the same color in every direction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstantBackground {
+    pub color: Vector3<f64>,
+}
+
+impl ConstantBackground {
+    pub fn new(color: Vector3<f64>) -> Self {
+        Self { color }
+    }
+}
+
+impl Background for ConstantBackground {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/scene/background/constant.h>".to_string()
+    }
+}
+
+impl Store for ConstantBackground {
+    type Data = Self;
+
+    fn create_data(&self, _context: &Context) -> crate::Result<Self::Data> {
+        Ok(self.clone())
+    }
+
+    fn update_data(&self, _context: &Context, data: &mut Self::Data) -> crate::Result<()> {
+        *data = self.clone();
+        Ok(())
+    }
+}
+
+impl Push for ConstantBackground {
+    fn args_count() -> usize {
+        1
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        kb.arg(prm::Float3::zero()); // color
+    }
+    fn args_set(&mut self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        k.set_arg(i, &prm_color(self.color))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    f64::consts::PI,
+    path::Path,
+    collections::HashSet,
+};
+use nalgebra::Vector3;
+use ocl::{self, prm, builders::KernelBuilder};
+use crate::{
+    prelude::*,
+    Context,
+    scene::*,
+    texture::load_image,
+};
+
+
+/// Background defined by the HDR image in the equirectangular projection.
+///
+/// The `z` axis points up, so the top row of the image is the zenith
+/// and the center of the image looks along the `x` axis.
+///
+/// The map is also used as a light source: directions are importance-sampled
+/// proportional to the pixel luminance, so small bright regions like the sun are found quickly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvironmentMap {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 3]>,
+    /// Sampling distribution of the pixels, see `cdf`.
+    cdf: Vec<f64>,
+    /// Brightness multiplier.
+    pub scale: f64,
+}
+
+impl EnvironmentMap {
+    /// Creates the map from the pixels stored row by row.
+    pub fn new(width: usize, height: usize, pixels: Vec<[f32; 3]>) -> crate::Result<Self> {
+        if width == 0 || height == 0 || pixels.len() != width*height {
+            return Err(format!(
+                "environment map: {} pixels doesn't match the size {}x{}",
+                pixels.len(), width, height,
+            ).into());
+        }
+        let cdf = Self::compute_cdf(width, height, &pixels);
+        Ok(Self { width, height, pixels, cdf, scale: 1.0 })
+    }
+
+    /// Loads the map from the image file.
+    ///
+    /// Radiance `.hdr` files are preferred, other formats are treated as sRGB and limited to `[0, 1]`.
+    pub fn load<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let (width, height, pixels) = load_image(path.as_ref())?;
+        Self::new(width, height, pixels)
+    }
+
+    pub fn dims(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    pub fn pixels(&self) -> &[[f32; 3]] {
+        &self.pixels
+    }
+
+    /// Sampling weights of pixels: luminance multiplied by the solid angle factor of the row.
+    fn weights(w: usize, h: usize, pixels: &[[f32; 3]]) -> Vec<f64> {
+        (0..(w*h)).map(|i| {
+            let [r, g, b] = pixels[i];
+            let lum = 0.2126*r as f64 + 0.7152*g as f64 + 0.0722*b as f64;
+            let theta = ((i/w) as f64 + 0.5)/h as f64*PI;
+            lum.max(0.0)*theta.sin()
+        }).collect()
+    }
+
+    /// Cumulative distribution for the direction sampling.
+    ///
+    /// It contains the marginal CDF of rows (`height` values)
+    /// followed by conditional CDFs of pixels in each row (`width` values per row).
+    /// All values are zero if the map is black.
+    ///
+    /// The distribution is computed once when the map is created.
+    pub fn cdf(&self) -> &[f64] {
+        &self.cdf
+    }
+
+    fn compute_cdf(w: usize, h: usize, pixels: &[[f32; 3]]) -> Vec<f64> {
+        let weights = Self::weights(w, h, pixels);
+        let rows = weights.chunks(w).map(|r| r.iter().sum::<f64>()).collect::<Vec<_>>();
+        let total = rows.iter().sum::<f64>();
+
+        let mut cdf = Vec::with_capacity(h + w*h);
+        let cumulative = |values: &[f64], sum: f64, cdf: &mut Vec<f64>| {
+            let mut acc = 0.0;
+            for v in values {
+                acc += v;
+                cdf.push(if sum > 0.0 { acc/sum } else { 0.0 });
+            }
+            // Rounding must not leave a gap above the last entry
+            if sum > 0.0 {
+                *cdf.last_mut().unwrap() = 1.0;
+            }
+        };
+        cumulative(&rows, total, &mut cdf);
+        for (row, sum) in weights.chunks(w).zip(rows.iter()) {
+            cumulative(row, *sum, &mut cdf);
+        }
+        cdf
+    }
+
+    /// Host version of the device sampling.
+    ///
+    /// Maps a pair of uniform random numbers to the direction
+    /// and returns it along with its density with respect to the solid angle.
+    pub fn sample(&self, u: f64, v: f64) -> Option<(Vector3<f64>, f64)> {
+        let (w, h) = (self.width, self.height);
+        let cdf = &self.cdf;
+        if cdf[h - 1] <= 0.0 {
+            return None;
+        }
+        let search = |c: &[f64], u: f64| c.iter().position(|&x| x > u).unwrap_or(c.len() - 1);
+        let y = search(&cdf[..h], u);
+        let y0 = if y > 0 { cdf[y - 1] } else { 0.0 };
+        let row = &cdf[(h + y*w)..(h + (y + 1)*w)];
+        let x = search(row, v);
+        let x0 = if x > 0 { row[x - 1] } else { 0.0 };
+        let (px, py) = (row[x] - x0, cdf[y] - y0);
+        if px <= 0.0 || py <= 0.0 {
+            return None;
+        }
+
+        let phi = (2.0*(x as f64 + (v - x0)/px)/w as f64 - 1.0)*PI;
+        let theta = (y as f64 + (u - y0)/py)/h as f64*PI;
+        let dir = Vector3::new(phi.cos()*theta.sin(), phi.sin()*theta.sin(), theta.cos());
+        Some((dir, px*py*(w*h) as f64/(2.0*PI*PI*theta.sin())))
+    }
+
+    /// Density of the direction produced by `sample`.
+    pub fn pdf(&self, dir: Vector3<f64>) -> f64 {
+        let (w, h) = (self.width, self.height);
+        let cdf = &self.cdf;
+        let dir = dir.normalize();
+        let sin_theta = (1.0 - dir.z*dir.z).max(0.0).sqrt();
+        if cdf[h - 1] <= 0.0 || sin_theta <= 0.0 {
+            return 0.0;
+        }
+        let (x, y) = self.coords(dir);
+        let py = cdf[y] - if y > 0 { cdf[y - 1] } else { 0.0 };
+        let row = &cdf[(h + y*w)..(h + (y + 1)*w)];
+        let px = row[x] - if x > 0 { row[x - 1] } else { 0.0 };
+        px*py*(w*h) as f64/(2.0*PI*PI*sin_theta)
+    }
+
+    fn coords(&self, dir: Vector3<f64>) -> (usize, usize) {
+        let phi = dir.y.atan2(dir.x);
+        let theta = dir.z.max(-1.0).min(1.0).acos();
+        let x = ((0.5 + 0.5*phi/PI)*self.width as f64) as usize;
+        let y = (theta/PI*self.height as f64) as usize;
+        (x.min(self.width - 1), y.min(self.height - 1))
+    }
+
+    /// Pixel that the direction points to (without interpolation and scaling).
+    pub fn texel(&self, dir: Vector3<f64>) -> [f32; 3] {
+        let (x, y) = self.coords(dir.normalize());
+        self.pixels[x + y*self.width]
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/scene/background/environment.h>".to_string()
+    }
+}
+
+impl Store for EnvironmentMap {
+    type Data = EnvironmentMapData;
+
+    fn create_data(&self, context: &Context) -> crate::Result<Self::Data> {
+        let create = |len: usize| {
+            ocl::Buffer::<f32>::builder()
+            .queue(context.queue().clone())
+            .flags(ocl::flags::MEM_READ_ONLY)
+            .len(len)
+            .fill_val(0f32)
+            .build()
+        };
+        let mut data = EnvironmentMapData {
+            buffer: create(3*self.pixels.len())?,
+            cdf: create(self.height + self.pixels.len())?,
+            size: prm::Int2::zero(),
+            scale: 0.0,
+        };
+        self.update_data(context, &mut data)?;
+        Ok(data)
+    }
+
+    fn update_data(&self, context: &Context, data: &mut Self::Data) -> crate::Result<()> {
+        if data.buffer.len() != 3*self.pixels.len() {
+            *data = self.create_data(context)?;
+            return Ok(());
+        }
+        let flat = self.pixels.iter().flat_map(|p| p.iter().cloned()).collect::<Vec<_>>();
+        data.buffer.cmd()
+        .offset(0)
+        .write(&flat)
+        .enq()?;
+        let cdf = self.cdf.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        data.cdf.cmd()
+        .offset(0)
+        .write(&cdf)
+        .enq()?;
+        data.size = prm::Int2::new(self.width as i32, self.height as i32);
+        data.scale = self.scale as f32;
+        Ok(())
+    }
+}
+
+/// Device data of the environment map.
+pub struct EnvironmentMapData {
+    buffer: ocl::Buffer<f32>,
+    cdf: ocl::Buffer<f32>,
+    size: prm::Int2,
+    scale: f32,
+}
+
+impl Push for EnvironmentMapData {
+    fn args_count() -> usize {
+        4
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        kb
+        .arg(None::<&ocl::Buffer<f32>>) // pixels
+        .arg(None::<&ocl::Buffer<f32>>) // sampling distribution
+        .arg(prm::Int2::zero()) // size
+        .arg(0f32); // scale
+    }
+    fn args_set(&mut self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        k.set_arg(i + 0, &self.buffer)?;
+        k.set_arg(i + 1, &self.cdf)?;
+        k.set_arg(i + 2, &self.size)?;
+        k.set_arg(i + 3, self.scale)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs::File};
+    use nalgebra::Vector3;
+    use image::{Rgb, hdr::HDREncoder};
+    use crate::scene::*;
+
+    #[test]
+    fn load() {
+        let (width, height) = (4, 2);
+        let data = (0..(width*height)).map(|i| Rgb([i as f32, 0.5, 2.0])).collect::<Vec<_>>();
+        let path = env::temp_dir().join("clay_core_environment_map_test.hdr");
+        HDREncoder::new(File::create(&path).unwrap()).encode(&data, width, height).unwrap();
+
+        let map = EnvironmentMap::load(&path).unwrap();
+        assert_eq!(map.dims(), (4, 2));
+        assert_eq!(map.pixels()[5], [5.0, 0.5, 2.0]);
+
+        // The center of the image looks along `x`, the zenith is in the top row.
+        assert_eq!(map.texel(Vector3::new(1.0, 0.0, -0.1))[0], 6.0);
+        assert_eq!(map.texel(Vector3::new(-1.0, -1e-3, 0.1))[0], 0.0);
+        assert!(EnvironmentMap::new(2, 2, vec![[0.0; 3]; 3]).is_err());
+    }
+
+    #[test]
+    fn sample() {
+        let (width, height) = (8, 4);
+        let mut pixels = vec![[0.01; 3]; width*height];
+        // The sun
+        pixels[5 + 1*width] = [1000.0; 3];
+        let map = EnvironmentMap::new(width, height, pixels).unwrap();
+
+        let cdf = map.cdf();
+        assert_eq!(cdf.len(), height + width*height);
+        assert!((cdf[height - 1] - 1.0).abs() < 1e-9);
+        assert!(cdf[..height].windows(2).all(|w| w[0] <= w[1]));
+
+        let mut sun = 0;
+        for i in 0..100 {
+            let (u, v) = ((i as f64 + 0.5)/100.0, ((37*i) % 100) as f64/100.0);
+            let (dir, pdf) = map.sample(u, v).unwrap();
+            assert!((dir.norm() - 1.0).abs() < 1e-9);
+            assert!(pdf > 0.0);
+            assert!((map.pdf(dir) - pdf).abs() < 1e-6*pdf);
+            if map.texel(dir)[0] > 1.0 {
+                sun += 1;
+            }
+        }
+        assert!(sun > 90);
+
+        let black = EnvironmentMap::new(2, 1, vec![[0.0; 3]; 2]).unwrap();
+        assert!(black.sample(0.5, 0.5).is_none());
+    }
+
+    #[test]
+    fn cdf_end() {
+        let (width, height) = (3, 3);
+        let mut pixels = vec![[0.1; 3]; width*height];
+        // Black last row and last column
+        for i in 0..width {
+            pixels[i + (height - 1)*width] = [0.0; 3];
+        }
+        for j in 0..height {
+            pixels[(width - 1) + j*width] = [0.0; 3];
+        }
+        let map = EnvironmentMap::new(width, height, pixels).unwrap();
+
+        let cdf = map.cdf();
+        assert_eq!(cdf[height - 1], 1.0);
+        for j in 0..(height - 1) {
+            assert_eq!(cdf[height + (j + 1)*width - 1], 1.0);
+        }
+
+        let last = 1.0 - 1e-12;
+        for &(u, v) in &[(last, 0.5), (0.5, last), (last, last)] {
+            if let Some((dir, pdf)) = map.sample(u, v) {
+                assert!(pdf.is_finite() && pdf > 0.0);
+                assert!(map.texel(dir)[0] > 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use ocl::{self, prm, builders::KernelBuilder};
+use crate::{
+    prelude::*,
+    Context,
+    scene::*,
+};
+
+
+/// Simple sky that changes its color from the horizon to the zenith.
+///
+/// The `z` axis points up, all directions below the horizon have the ground color.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradientBackground {
+    pub zenith: Vector3<f64>,
+    pub horizon: Vector3<f64>,
+    pub ground: Vector3<f64>,
+}
+
+impl GradientBackground {
+    pub fn new(zenith: Vector3<f64>, horizon: Vector3<f64>, ground: Vector3<f64>) -> Self {
+        Self { zenith, horizon, ground }
+    }
+}
+
+impl Background for GradientBackground {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/scene/background/gradient.h>".to_string()
+    }
+}
+
+impl Store for GradientBackground {
+    type Data = Self;
+
+    fn create_data(&self, _context: &Context) -> crate::Result<Self::Data> {
+        Ok(self.clone())
+    }
+
+    fn update_data(&self, _context: &Context, data: &mut Self::Data) -> crate::Result<()> {
+        *data = self.clone();
+        Ok(())
+    }
+}
+
+impl Push for GradientBackground {
+    fn args_count() -> usize {
+        3
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        kb
+        .arg(prm::Float3::zero()) // zenith
+        .arg(prm::Float3::zero()) // horizon
+        .arg(prm::Float3::zero()); // ground
+    }
+    fn args_set(&mut self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        k.set_arg(i + 0, &prm_color(self.zenith))?;
+        k.set_arg(i + 1, &prm_color(self.horizon))?;
+        k.set_arg(i + 2, &prm_color(self.ground))?;
+        Ok(())
+    }
+}
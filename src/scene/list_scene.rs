@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+use ocl::{self, builders::KernelBuilder};
+use crate::{
+    prelude::*,
+    Context,
+    shape::*,
+    shape::mesh::{MeshStorage, MeshBuffer},
+    texture::{TextureStorage, TextureBuffer},
+    object::*,
+    scene::*,
+    buffer::InstanceBuffer,
+};
+
+
+/// Scene that stores objects in a plain list and tests each of them for every ray.
+///
+/// Objects that emit light could also be added as targets, then they are sampled
+/// directly from non-specular surfaces and combined with the material sampling
+/// by the multiple importance sampling.
+pub struct ListScene<O: Object, T: Target, B: Background> {
+    objects: Vec<O>,
+    targets: Vec<(T, f64)>,
+    /// Index of the object of each target.
+    target_objects: Vec<usize>,
+    /// Geometry of the meshes referred by objects.
+    pub meshes: MeshStorage,
+    /// Images of the textures referred by materials.
+    pub textures: TextureStorage,
+    pub background: B,
+    /// Maximum number of bounces of the ray.
+    pub max_depth: usize,
+    /// Depth from which diffuse rays are terminated randomly (Russian roulette).
+    pub roulette_depth: usize,
+}
+
+impl<O: Object, T: Target, B: Background> ListScene<O, T, B> {
+    pub fn new(background: B) -> Self {
+        Self {
+            objects: Vec::new(),
+            targets: Vec::new(),
+            target_objects: Vec::new(),
+            meshes: MeshStorage::new(),
+            textures: TextureStorage::new(),
+            background,
+            max_depth: 8,
+            roulette_depth: 3,
+        }
+    }
+
+    pub fn add(&mut self, object: O) {
+        self.objects.push(object);
+    }
+
+    /// Adds the object and makes it a target if it emits some light.
+    pub fn add_targeted(&mut self, object: O) where O: Targeted<T> {
+        if let Some((target, brightness)) = object.target() {
+            if brightness > 0.0 {
+                self.targets.push((target, brightness));
+                self.target_objects.push(self.objects.len());
+            }
+        }
+        self.add(object);
+    }
+
+    pub fn objects(&self) -> &[O] {
+        &self.objects
+    }
+    pub fn targets(&self) -> &[(T, f64)] {
+        &self.targets
+    }
+}
+
+/// Cumulative probabilities of choosing each target proportional to its brightness.
+pub(crate) fn target_cdf<I: Iterator<Item=f64>>(brightness: I) -> Vec<f32> {
+    let mut sum = 0.0;
+    let cdf = brightness.map(|b| { sum += b; sum }).collect::<Vec<_>>();
+    cdf.into_iter().map(|c| (c/sum) as f32).collect()
+}
+
+/// Creates the device buffer filled with the data.
+///
+/// Empty data gives the buffer of a single element because OpenCL doesn't allow empty buffers.
+pub(crate) fn read_only_buffer<P: ocl::OclPrm>(context: &Context, data: &[P]) -> crate::Result<ocl::Buffer<P>> {
+    let buffer = ocl::Buffer::<P>::builder()
+    .queue(context.queue().clone())
+    .flags(ocl::flags::MEM_READ_ONLY)
+    .len(data.len().max(1))
+    .fill_val(P::default())
+    .build()?;
+    if !data.is_empty() {
+        buffer.cmd()
+        .offset(0)
+        .write(data)
+        .enq()?;
+    }
+    Ok(buffer)
+}
+
+/// Device code shared by scenes that store objects and targets in instance buffers.
+pub(crate) fn scene_source<O: Object, T: Target, B: Background>(cache: &mut HashSet<u64>) -> String {
+    [
+        O::full_source(cache),
+        T::full_source(cache),
+        B::source(cache),
+        ObjectClass::methods().into_iter().map(|method| {
+            format!("#define __object_{} {}_{}", method, O::inst_name(), method)
+        }).collect::<Vec<_>>().join("\n"),
+        TargetClass::methods().into_iter().map(|method| {
+            format!("#define __target_{} {}_{}", method, T::inst_name(), method)
+        }).collect::<Vec<_>>().join("\n"),
+        format!("#define SCENE_OBJECT_SIZE_INT {}", O::size_int()),
+        format!("#define SCENE_OBJECT_SIZE_FLOAT {}", O::size_float()),
+        format!("#define SCENE_TARGET_SIZE_INT {}", T::size_int()),
+        format!("#define SCENE_TARGET_SIZE_FLOAT {}", T::size_float()),
+    ].join("\n")
+}
+
+impl<O: Object, T: Target, B: Background> Scene for ListScene<O, T, B> {
+    fn source(cache: &mut HashSet<u64>) -> String {
+        [
+            scene_source::<O, T, B>(cache),
+            "#include <clay_core/scene/list_scene.h>".to_string(),
+        ].join("\n")
+    }
+}
+
+impl<O: Object, T: Target, B: Background> Store for ListScene<O, T, B> {
+    type Data = ListSceneData<O, T, B>;
+
+    fn create_data(&self, context: &Context) -> crate::Result<Self::Data> {
+        Ok(Self::Data {
+            objects: InstanceBuffer::new(context, self.objects.iter())?,
+            targets: TargetBuffer::new(context, &self.targets, &self.target_objects)?,
+            meshes: self.meshes.create_data(context)?,
+            textures: self.textures.create_data(context)?,
+            max_depth: self.max_depth as i32,
+            roulette_depth: self.roulette_depth as i32,
+            background: self.background.create_data(context)?,
+        })
+    }
+
+    fn update_data(&self, context: &Context, data: &mut Self::Data) -> crate::Result<()> {
+        if data.objects.count() == self.objects.len() {
+            data.objects.write(self.objects.iter())?;
+        } else {
+            data.objects = InstanceBuffer::new(context, self.objects.iter())?;
+        }
+        data.targets.update(context, &self.targets, &self.target_objects)?;
+        self.meshes.update_data(context, &mut data.meshes)?;
+        self.textures.update_data(context, &mut data.textures)?;
+        data.max_depth = self.max_depth as i32;
+        data.roulette_depth = self.roulette_depth as i32;
+        self.background.update_data(context, &mut data.background)
+    }
+}
+
+/// Device buffers of targets along with probabilities of their choice
+/// and indices of objects they belong to.
+pub(crate) struct TargetBuffer<T: Target + 'static> {
+    targets: InstanceBuffer<T>,
+    cdf: ocl::Buffer<f32>,
+    objects: ocl::Buffer<i32>,
+}
+
+impl<T: Target> TargetBuffer<T> {
+    pub(crate) fn new(context: &Context, targets: &[(T, f64)], objects: &[usize]) -> crate::Result<Self> {
+        let cdf = target_cdf(targets.iter().map(|(_, b)| *b));
+        let objects = objects.iter().map(|&i| i as i32).collect::<Vec<_>>();
+        Ok(Self {
+            targets: InstanceBuffer::new(context, targets.iter().map(|(t, _)| t))?,
+            cdf: read_only_buffer(context, &cdf)?,
+            objects: read_only_buffer(context, &objects)?,
+        })
+    }
+
+    pub(crate) fn update(
+        &mut self, context: &Context,
+        targets: &[(T, f64)], objects: &[usize],
+    ) -> crate::Result<()> {
+        *self = Self::new(context, targets, objects)?;
+        Ok(())
+    }
+}
+
+impl<T: Target> Push for TargetBuffer<T> {
+    fn args_count() -> usize {
+        InstanceBuffer::<T>::args_count() + 2
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        InstanceBuffer::<T>::args_def(kb);
+        kb
+        .arg(None::<&ocl::Buffer<f32>>) // cumulative probabilities
+        .arg(None::<&ocl::Buffer<i32>>); // object indices
+    }
+    fn args_set(&mut self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        self.targets.args_set(i, k)?;
+        let i = i + InstanceBuffer::<T>::args_count();
+        k.set_arg(i + 0, &self.cdf)?;
+        k.set_arg(i + 1, &self.objects)?;
+        Ok(())
+    }
+}
+
+/// Device data of the list scene.
+pub struct ListSceneData<O: Object + 'static, T: Target + 'static, B: Background> {
+    objects: InstanceBuffer<O>,
+    targets: TargetBuffer<T>,
+    meshes: MeshBuffer,
+    textures: TextureBuffer,
+    max_depth: i32,
+    roulette_depth: i32,
+    background: B::Data,
+}
+
+impl<O: Object, T: Target, B: Background> Push for ListSceneData<O, T, B> {
+    fn args_count() -> usize {
+        InstanceBuffer::<O>::args_count() +
+        TargetBuffer::<T>::args_count() +
+        MeshBuffer::args_count() +
+        TextureBuffer::args_count() +
+        2 +
+        B::Data::args_count()
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        InstanceBuffer::<O>::args_def(kb);
+        TargetBuffer::<T>::args_def(kb);
+        MeshBuffer::args_def(kb);
+        TextureBuffer::args_def(kb);
+        kb
+        .arg(0i32) // max depth
+        .arg(0i32); // roulette depth
+        B::Data::args_def(kb);
+    }
+    fn args_set(&mut self, mut i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        self.objects.args_set(i, k)?;
+        i += InstanceBuffer::<O>::args_count();
+        self.targets.args_set(i, k)?;
+        i += TargetBuffer::<T>::args_count();
+        self.meshes.args_set(i, k)?;
+        i += MeshBuffer::args_count();
+        self.textures.args_set(i, k)?;
+        i += TextureBuffer::args_count();
+        k.set_arg(i + 0, self.max_depth)?;
+        k.set_arg(i + 1, self.roulette_depth)?;
+        i += 2;
+        self.background.args_set(i, k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use crate::{
+        shape::{*, test::TestShape},
+        material::test::TestMaterial,
+        object::Covered,
+        scene::{*, test::TestBackground},
+    };
+
+    type TestScene = ListScene<
+        Covered<TestShape<i32>, TestMaterial<i32>>,
+        BoundingSphere,
+        TestBackground,
+    >;
+
+    #[test]
+    fn cdf() {
+        let cdf = target_cdf(vec![1.0, 3.0, 4.0].into_iter());
+        assert_eq!(cdf, [0.125, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn source() {
+        let mut scene = TestScene::new(TestBackground::new());
+        scene.add(Covered::new(TestShape::new(), TestMaterial::new()));
+        assert_eq!(scene.objects().len(), 1);
+        assert!(scene.targets().is_empty());
+
+        let src = TestScene::source(&mut HashSet::new());
+        assert!(src.contains("#define __object_hit __covered_"));
+        assert!(src.contains("#define __target_sample bounding_sphere_sample"));
+        assert!(src.contains("#define __target_pdf bounding_sphere_pdf"));
+        assert!(src.contains("#define __object_eval __covered_"));
+        assert!(src.contains("#include <clay_core/scene/list_scene.h>"));
+    }
+}
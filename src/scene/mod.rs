@@ -2,3 +2,18 @@ mod scene;
 pub use scene::*;
 mod background;
 pub use background::*;
+
+mod constant_background;
+pub use constant_background::*;
+mod gradient_background;
+pub use gradient_background::*;
+mod environment_map;
+pub use environment_map::*;
+
+mod list_scene;
+pub use list_scene::*;
+mod bvh_scene;
+pub use bvh_scene::*;
+
+#[cfg(test)]
+pub mod test;
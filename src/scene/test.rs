@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+use ocl::{self, builders::KernelBuilder};
+use crate::{
+    prelude::*,
+    Context,
+    scene::*,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct TestBackground {}
+
+impl TestBackground {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Background for TestBackground {
+    fn source(_: &mut HashSet<u64>) -> String { String::new() }
+}
+
+impl Store for TestBackground {
+    type Data = TestBackground;
+    fn create_data(&self, _context: &Context) -> crate::Result<Self::Data> { Ok(Self::new()) }
+    fn update_data(&self, _context: &Context, _data: &mut Self::Data) -> crate::Result<()> { Ok(()) }
+}
+
+impl Push for TestBackground {
+    fn args_count() -> usize { 1 }
+    fn args_def(kb: &mut KernelBuilder) { kb.arg(0i32); }
+    fn args_set(&mut self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        k.set_arg(i, 0i32)?;
+        Ok(())
+    }
+}
@@ -79,7 +79,7 @@ macro_rules! instance_select {
                     ms.push(Self::method_source(&method));
                 }
                 [
-                    $( <$Instance as $crate::Instance<$Class>>::source(cache), )+
+                    $( <$Instance as $crate::Instance<$Class>>::full_source(cache), )+
                     ms.join("\n"),
                 ].join("\n")
             }
@@ -88,6 +88,11 @@ macro_rules! instance_select {
                 use $crate::TypeHash;
                 format!("__select_{:x}", Self::type_hash())
             }
+
+            fn implemented_methods() -> Vec<String> {
+                use $crate::class::*;
+                <$Class>::methods()
+            }
         }
 
         impl $crate::Pack for $Select {
@@ -111,6 +116,32 @@ macro_rules! instance_select {
                     $( $Select::$Enum(x) => x.pack_to(buffer_int, buffer_float), )+
                 }
             }
+            fn layout() -> $crate::layout::Layout {
+                // Variants overlap each other, so only the index is described.
+                $crate::layout::Layout::builder()
+                .field_int::<u32>("index")
+                .build()
+            }
+        }
+
+        // `for<'a_>` makes the bounds non-trivial, so the impl is just
+        // skipped if some instance doesn't implement `Unpack`.
+        impl $crate::Unpack for $Select where $( for<'a_> $Instance: $crate::Unpack ),+ {
+            #[allow(unused_assignments)]
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+                let sel_idx = buffer_int[0] as u32;
+                let buffer_int = &buffer_int[1..];
+                let mut i = 0;
+                $(
+                    if sel_idx == i {
+                        return $Select::$Enum(
+                            <$Instance as $crate::Unpack>::unpack_from(buffer_int, buffer_float)
+                        );
+                    }
+                    i += 1;
+                )+
+                panic!("bad select index: {}", sel_idx)
+            }
         }
 
         $(
@@ -139,4 +170,16 @@ mod check {
             Shape2(T2 = TestShape<f32>),
         }
     );
+
+    #[test]
+    fn unpack() {
+        use crate::{Pack, Unpack};
+        let x = TestSelect::from(TestShape::<f32>::new());
+        let mut buffer_int = vec![0; TestSelect::size_int()];
+        x.pack_to(&mut buffer_int, &mut []);
+        match TestSelect::unpack_from(&buffer_int, &[]) {
+            TestSelect::Shape2(_) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{
     prelude::*,
+    map::Map,
     shape::*,
 };
 
@@ -8,6 +9,17 @@ use crate::{
 pub trait Bound: Pack + Instance<BoundClass> {}
 
 /// Device interface for bound.
+///
+/// How to implement in OpenCL:
+/// ```c
+/// #include <clay_core/shape/bound.h>
+///
+/// BOUND_BOUND_RET <bound>_bound(
+///     BOUND_BOUND_ARGS_DEF
+/// ) {
+///     ...
+/// }
+/// ```
 pub enum BoundClass {}
 impl Class for BoundClass {
     fn name() -> String {
@@ -18,6 +30,14 @@ impl Class for BoundClass {
     }
 }
 
+/// Bound that could be transformed by a mapping on the host side.
+pub trait MapBound: Bound + Sized {
+    /// Returns a bound containing the original one transformed by the map.
+    ///
+    /// The map is assumed to be affine.
+    fn map_bound<M: Map>(&self, map: &M) -> Self;
+}
+
 /// The shape that could be put inside the specified bound.
 pub trait Bounded<B: Bound> {
     /// Returns bounding shape instance.
@@ -32,3 +52,30 @@ impl<T: Bound + Shape + Clone> Bounded<T> for T {
         Some(self.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use crate::{
+        shape::{*, primitives::*},
+        map::*,
+    };
+
+    #[test]
+    fn mapped() {
+        let shape = Cube::new()
+        .map(Scale::new(Vector3::new(1.0, 2.0, 3.0)).unwrap())
+        .map(Shift::new(Vector3::new(1.0, 0.0, -1.0)));
+
+        let bbox: BoundingBox = shape.bound().unwrap();
+        assert!((bbox.min - Vector3::new(0.0, -2.0, -4.0)).norm() < 1e-9);
+        assert!((bbox.max - Vector3::new(2.0, 2.0, 2.0)).norm() < 1e-9);
+
+        let bsphere: BoundingSphere = shape.bound().unwrap();
+        assert!((bsphere.center - Vector3::new(1.0, 0.0, -1.0)).norm() < 1e-9);
+        assert!((bsphere.radius - 3.0*3f64.sqrt()).abs() < 1e-9);
+
+        let plane: Option<BoundingBox> = HalfSpace::new().map(Scale::uniform(2.0).unwrap()).bound();
+        assert!(plane.is_none());
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use crate::{
+    prelude::*,
+    map::Map,
+    shape::*,
+};
+
+
+/// Axis-aligned bounding box.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct BoundingBox {
+    pub min: Vector3<f64>,
+    pub max: Vector3<f64>,
+}
+
+impl BoundingBox {
+    pub fn new(min: Vector3<f64>, max: Vector3<f64>) -> Self {
+        Self { min, max }
+    }
+
+    /// The smallest box containing all the points.
+    ///
+    /// Returns `None` if there are no points.
+    pub fn from_points<I: IntoIterator<Item=Vector3<f64>>>(points: I) -> Option<Self> {
+        let mut iter = points.into_iter();
+        let first = iter.next()?;
+        Some(iter.fold(Self::new(first, first), |b, p| {
+            Self::new(b.min.zip_map(&p, f64::min), b.max.zip_map(&p, f64::max))
+        }))
+    }
+
+    /// The smallest box containing both boxes.
+    ///
+    /// The box is taken by value, so this method is not shadowed by `Shape::union`.
+    pub fn union(self, other: &Self) -> Self {
+        Self::new(self.min.zip_map(&other.min, f64::min), self.max.zip_map(&other.max, f64::max))
+    }
+
+    pub fn center(&self) -> Vector3<f64> {
+        0.5*(self.min + self.max)
+    }
+    pub fn size(&self) -> Vector3<f64> {
+        self.max - self.min
+    }
+    pub fn surface_area(&self) -> f64 {
+        let s = self.size();
+        2.0*(s.x*s.y + s.y*s.z + s.z*s.x)
+    }
+
+    /// All eight corners of the box.
+    pub fn corners(&self) -> [Vector3<f64>; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Vector3::new(a.x, a.y, a.z), Vector3::new(b.x, a.y, a.z),
+            Vector3::new(a.x, b.y, a.z), Vector3::new(b.x, b.y, a.z),
+            Vector3::new(a.x, a.y, b.z), Vector3::new(b.x, a.y, b.z),
+            Vector3::new(a.x, b.y, b.z), Vector3::new(b.x, b.y, b.z),
+        ]
+    }
+}
+
+impl Bound for BoundingBox {}
+
+impl MapBound for BoundingBox {
+    fn map_bound<M: Map>(&self, map: &M) -> Self {
+        Self::from_points(self.corners().iter().map(|c| map.apply_abs(*c))).unwrap()
+    }
+}
+
+impl Instance<BoundClass> for BoundingBox {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/bounding_box.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "bounding_box".to_string()
+    }
+}
+
+impl Target for BoundingBox {}
+
+impl Instance<TargetClass> for BoundingBox {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/bounding_box.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "bounding_box".to_string()
+    }
+    fn implemented_methods() -> Vec<String> {
+        TargetClass::methods()
+    }
+}
+
+impl Shape for BoundingBox {}
+
+impl Instance<ShapeClass> for BoundingBox {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/bounding_box.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "bounding_box".to_string()
+    }
+}
+
+impl Bounded<BoundingSphere> for BoundingBox {
+    fn bound(&self) -> Option<BoundingSphere> {
+        Some(BoundingSphere::new(self.center(), 0.5*self.size().norm()))
+    }
+}
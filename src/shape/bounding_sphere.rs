@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+use nalgebra::{Vector3, Matrix3};
+use crate::{
+    prelude::*,
+    map::Map,
+    shape::*,
+};
+
+
+/// Bounding sphere.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct BoundingSphere {
+    pub center: Vector3<f64>,
+    pub radius: f64,
+}
+
+impl BoundingSphere {
+    pub fn new(center: Vector3<f64>, radius: f64) -> Self {
+        Self { center, radius }
+    }
+
+    /// Sphere containing all the points.
+    ///
+    /// It is centered at the center of their bounding box, so it isn't necessarily the smallest one.
+    /// Returns `None` if there are no points.
+    pub fn from_points<I: IntoIterator<Item=Vector3<f64>>>(points: I) -> Option<Self> {
+        let points = points.into_iter().collect::<Vec<_>>();
+        let center = BoundingBox::from_points(points.iter().cloned())?.center();
+        let radius = points.iter().map(|p| (p - center).norm()).fold(0.0, f64::max);
+        Some(Self::new(center, radius))
+    }
+
+    /// The smallest sphere containing both spheres.
+    ///
+    /// The sphere is taken by value, so this method is not shadowed by `Shape::union`.
+    pub fn union(self, other: &Self) -> Self {
+        let d = other.center - self.center;
+        let dist = d.norm();
+        if dist + other.radius <= self.radius {
+            self
+        } else if dist + self.radius <= other.radius {
+            other.clone()
+        } else {
+            let radius = 0.5*(dist + self.radius + other.radius);
+            Self::new(self.center + d*((radius - self.radius)/dist), radius)
+        }
+    }
+}
+
+impl Bound for BoundingSphere {}
+
+impl MapBound for BoundingSphere {
+    fn map_bound<M: Map>(&self, map: &M) -> Self {
+        // The radius is scaled by the largest singular value of the linear part.
+        let linear = Matrix3::from_columns(&[
+            map.apply_rel(Vector3::x()),
+            map.apply_rel(Vector3::y()),
+            map.apply_rel(Vector3::z()),
+        ]);
+        let stretch = (linear.transpose()*linear).symmetric_eigenvalues().max().max(0.0).sqrt();
+        Self::new(map.apply_abs(self.center), self.radius*stretch)
+    }
+}
+
+impl Instance<BoundClass> for BoundingSphere {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/bounding_sphere.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "bounding_sphere".to_string()
+    }
+}
+
+impl Target for BoundingSphere {}
+
+impl Instance<TargetClass> for BoundingSphere {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/bounding_sphere.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "bounding_sphere".to_string()
+    }
+    fn implemented_methods() -> Vec<String> {
+        TargetClass::methods()
+    }
+}
+
+impl Shape for BoundingSphere {}
+
+impl Instance<ShapeClass> for BoundingSphere {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/bounding_sphere.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "bounding_sphere".to_string()
+    }
+}
+
+impl Bounded<BoundingBox> for BoundingSphere {
+    fn bound(&self) -> Option<BoundingBox> {
+        let r = Vector3::repeat(self.radius);
+        Some(BoundingBox::new(self.center - r, self.center + r))
+    }
+}
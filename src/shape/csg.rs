@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+use crate::{
+    prelude::*,
+    shape::*,
+};
+
+
+macro_rules! csg_shape {
+    ($Csg:ident, $name:expr, $op:expr) => {
+        impl<A: Shape, B: Shape> $Csg<A, B> {
+            pub fn new(first: A, second: B) -> Self {
+                Self { first, second }
+            }
+        }
+
+        impl<A: Shape, B: Shape> Shape for $Csg<A, B> {}
+
+        impl<A: Shape, B: Shape> Instance<ShapeClass> for $Csg<A, B> {
+            fn source(cache: &mut HashSet<u64>) -> String {
+                if !cache.insert(Self::type_hash()) {
+                    return String::new()
+                }
+                let inst_name = Self::inst_name();
+                [
+                    A::full_source(cache),
+                    B::full_source(cache),
+                    "#include <clay_core/shape/csg.h>".to_string(),
+                    Self::layout().defines(&inst_name),
+                    format!(
+                        "CSG_SHAPE_FN_DEF({}, {}, {}, {}_FIRST_DI, {}_FIRST_DF, {}, {}_SECOND_DI, {}_SECOND_DF)",
+                        inst_name, $op,
+                        A::inst_name(), inst_name, inst_name,
+                        B::inst_name(), inst_name, inst_name,
+                    ),
+                ].join("\n")
+            }
+            fn inst_name() -> String {
+                format!("__{}_{:x}", $name, Self::type_hash())
+            }
+            fn implemented_methods() -> Vec<String> {
+                ShapeClass::methods()
+            }
+        }
+    };
+}
+
+/// Union of two shapes, i.e. the points that belong to any of them.
+#[derive(Clone, Debug, Default, Pack, Unpack)]
+pub struct Union<A: Shape, B: Shape> {
+    pub first: A,
+    pub second: B,
+}
+
+csg_shape!(Union, "union", "UNION");
+
+impl<A, B> Bounded<BoundingBox> for Union<A, B> where
+A: Shape + Bounded<BoundingBox>, B: Shape + Bounded<BoundingBox> {
+    fn bound(&self) -> Option<BoundingBox> {
+        Some(self.first.bound()?.union(&self.second.bound()?))
+    }
+}
+
+impl<A, B> Bounded<BoundingSphere> for Union<A, B> where
+A: Shape + Bounded<BoundingSphere>, B: Shape + Bounded<BoundingSphere> {
+    fn bound(&self) -> Option<BoundingSphere> {
+        Some(self.first.bound()?.union(&self.second.bound()?))
+    }
+}
+
+/// Intersection of two shapes, i.e. the points that belong to both of them.
+#[derive(Clone, Debug, Default, Pack, Unpack)]
+pub struct Intersection<A: Shape, B: Shape> {
+    pub first: A,
+    pub second: B,
+}
+
+csg_shape!(Intersection, "intersection", "INTERSECTION");
+
+impl<T: Bound, A: Shape + Bounded<T>, B: Shape + Bounded<T>> Bounded<T> for Intersection<A, B> {
+    fn bound(&self) -> Option<T> {
+        self.first.bound().or_else(|| self.second.bound())
+    }
+}
+
+/// Difference of two shapes, i.e. the points of the first one that don't belong to the second one.
+#[derive(Clone, Debug, Default, Pack, Unpack)]
+pub struct Difference<A: Shape, B: Shape> {
+    pub first: A,
+    pub second: B,
+}
+
+csg_shape!(Difference, "difference", "DIFFERENCE");
+
+impl<T: Bound, A: Shape + Bounded<T>, B: Shape> Bounded<T> for Difference<A, B> {
+    fn bound(&self) -> Option<T> {
+        self.first.bound()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+    use crate::{
+        prelude::*,
+        shape::{*, primitives::*},
+        map::*,
+    };
+
+    #[test]
+    fn bound() {
+        let lens = Sphere::new().map(Shift::new(Vector3::new(0.0, 0.0, 0.5)))
+        .intersect(Sphere::new().map(Shift::new(Vector3::new(0.0, 0.0, -0.5))));
+        let bsphere: BoundingSphere = lens.bound().unwrap();
+        assert!((bsphere.center - Vector3::new(0.0, 0.0, 0.5)).norm() < 1e-9);
+
+        let pair = Sphere::new().union(Sphere::new().map(Shift::new(Vector3::new(2.0, 0.0, 0.0))));
+        let bsphere: BoundingSphere = pair.bound().unwrap();
+        assert!((bsphere.center - Vector3::new(1.0, 0.0, 0.0)).norm() < 1e-9);
+        assert!((bsphere.radius - 2.0).abs() < 1e-9);
+
+        let cut = HalfSpace::new().subtract(Cube::new());
+        assert!(Bounded::<BoundingBox>::bound(&cut).is_none());
+    }
+
+    #[test]
+    fn source() {
+        type T = Difference<Cube, Sphere>;
+        let source = T::full_source(&mut std::collections::HashSet::new());
+        assert!(source.contains(&format!(
+            "CSG_SHAPE_FN_DEF({}, DIFFERENCE, cube_shape,", T::inst_name(),
+        )));
+        assert!(!source.contains("SHAPE_HIT_EXT_FROM_HIT_FN_DEF"));
+
+        type U = Union<BoundingBox, T>;
+        let source = U::full_source(&mut std::collections::HashSet::new());
+        assert_eq!(source.matches("SHAPE_HIT_EXT_FROM_HIT_FN_DEF(bounding_box)").count(), 1);
+        assert!(!source.contains(&format!("SHAPE_HIT_EXT_FROM_HIT_FN_DEF({})", U::inst_name())));
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
 };
 
 /// A new shape obtained by applying some mapping to another shape.
+#[derive(Pack, Unpack)]
 pub struct ShapeMapper<S: Shape, M: Map> {
     pub shape: S,
     pub map: M,
@@ -19,21 +20,28 @@ impl<S: Shape, M: Map> ShapeMapper<S, M> {
 
 impl<S: Shape, M: Map> Shape for ShapeMapper<S, M> {}
 
+impl<B: MapBound, S: Shape + Bounded<B>, M: Map> Bounded<B> for ShapeMapper<S, M> {
+    fn bound(&self) -> Option<B> {
+        self.shape.bound().map(|b| b.map_bound(&self.map))
+    }
+}
+
 impl<S: Shape, M: Map> Instance<ShapeClass> for ShapeMapper<S, M> {
     fn source(cache: &mut HashSet<u64>) -> String {
         if !cache.insert(Self::type_hash()) {
             return String::new()
         }
         [
-            S::source(cache),
+            S::full_source(cache),
             M::source(cache),
             "#include <clay_core/shape/mapper.h>".to_string(),
+            Self::layout().defines(&Self::inst_name()),
             format!(
-                "MAP_SHAPE_FN_DEF({}, {}, {}, {}, {})",
+                "MAP_SHAPE_FN_DEF({}, {}, {}, {}_MAP_DI, {}_MAP_DF)",
                 Self::inst_name(),
                 S::inst_name(),
                 M::inst_name(),
-                S::size_int(), S::size_float(),
+                Self::inst_name(), Self::inst_name(),
             ),
         ].join("\n")
     }
@@ -43,19 +51,7 @@ impl<S: Shape, M: Map> Instance<ShapeClass> for ShapeMapper<S, M> {
             Self::type_hash(),
         )
     }
-}
-
-
-impl<S: Shape, M: Map> Pack for ShapeMapper<S, M> {
-    fn size_int() -> usize {
-        S::size_int() + M::size_int()
-    }
-    fn size_float() -> usize {
-        S::size_float() + M::size_float()
-    }
-    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        Packer::new(buffer_int, buffer_float)
-        .pack(&self.shape)
-        .pack(&self.map);
+    fn implemented_methods() -> Vec<String> {
+        ShapeClass::methods()
     }
 }
@@ -0,0 +1,100 @@
+use nalgebra::{Vector2, Vector3};
+use crate::shape::BoundingBox;
+
+
+/// Triangle mesh geometry on the host.
+///
+/// Faces are expected to be oriented outwards with counter-clockwise winding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshData {
+    pub vertices: Vec<Vector3<f64>>,
+    /// Normals per vertex.
+    pub normals: Vec<Vector3<f64>>,
+    /// Vertex indices of each triangle.
+    pub triangles: Vec<[usize; 3]>,
+    /// Texture coordinates per vertex, empty if the mesh isn't textured.
+    pub uvs: Vec<Vector2<f64>>,
+}
+
+impl MeshData {
+    /// Creates a mesh with smooth normals computed from the faces.
+    pub fn new(vertices: Vec<Vector3<f64>>, triangles: Vec<[usize; 3]>) -> crate::Result<Self> {
+        let normals = Self::smooth_normals(&vertices, &triangles)?;
+        Self::with_normals(vertices, normals, triangles)
+    }
+
+    /// Creates a mesh with the specified vertex normals.
+    pub fn with_normals(
+        vertices: Vec<Vector3<f64>>,
+        normals: Vec<Vector3<f64>>,
+        triangles: Vec<[usize; 3]>,
+    ) -> crate::Result<Self> {
+        if triangles.is_empty() {
+            return Err("mesh has no triangles".into());
+        }
+        if normals.len() != vertices.len() {
+            return Err(format!(
+                "mesh has {} vertices but {} normals",
+                vertices.len(), normals.len(),
+            ).into());
+        }
+        Self::check_indices(&vertices, &triangles)?;
+        Ok(Self { vertices, normals, triangles, uvs: Vec::new() })
+    }
+
+    /// Sets texture coordinates of vertices.
+    pub fn with_uvs(mut self, uvs: Vec<Vector2<f64>>) -> crate::Result<Self> {
+        if uvs.len() != self.vertices.len() {
+            return Err(format!(
+                "mesh has {} vertices but {} texture coordinates",
+                self.vertices.len(), uvs.len(),
+            ).into());
+        }
+        self.uvs = uvs;
+        Ok(self)
+    }
+
+    fn check_indices(vertices: &[Vector3<f64>], triangles: &[[usize; 3]]) -> crate::Result<()> {
+        match triangles.iter().flat_map(|t| t.iter()).find(|&&i| i >= vertices.len()) {
+            Some(i) => Err(format!(
+                "mesh vertex index {} is out of range, there are {} vertices",
+                i, vertices.len(),
+            ).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Vertex normals obtained by averaging normals of adjacent faces weighted by their area.
+    pub fn smooth_normals(
+        vertices: &[Vector3<f64>],
+        triangles: &[[usize; 3]],
+    ) -> crate::Result<Vec<Vector3<f64>>> {
+        Self::check_indices(vertices, triangles)?;
+        let mut normals = vec![Vector3::zeros(); vertices.len()];
+        for t in triangles.iter() {
+            let (a, b, c) = (vertices[t[0]], vertices[t[1]], vertices[t[2]]);
+            // The length of the cross product is twice the area.
+            let n = (b - a).cross(&(c - a));
+            for &i in t.iter() {
+                normals[i] += n;
+            }
+        }
+        Ok(normals.into_iter().map(|n| {
+            let l = n.norm();
+            if l > 0.0 { n/l } else { n }
+        }).collect())
+    }
+
+    /// Bounding box of the specified triangle.
+    pub fn triangle_bound(&self, index: usize) -> BoundingBox {
+        let t = &self.triangles[index];
+        BoundingBox::from_points(t.iter().map(|&i| self.vertices[i])).unwrap()
+    }
+
+    /// Bounding box of the whole mesh, `None` if it has no triangles.
+    pub fn bound(&self) -> Option<BoundingBox> {
+        BoundingBox::from_points(
+            self.triangles.iter().flat_map(|t| t.iter()).map(|&i| self.vertices[i])
+        )
+    }
+}
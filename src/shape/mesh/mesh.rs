@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+use crate::{
+    prelude::*,
+    shape::*,
+};
+
+
+/// Triangle mesh which geometry is located in `MeshStorage`.
+///
+/// The mesh is obtained via `MeshStorage::add`.
+/// Its geometry is available only in `hit_ext`, so `hit` never finds the mesh.
+/// Normals are interpolated between vertices, so the surface looks smooth.
+/// Texture coordinates are interpolated too (they are zero if the mesh data has none).
+#[derive(Clone, Debug, Pack, Unpack)]
+pub struct Mesh {
+    /// Offset of hierarchy nodes in the int buffer of the storage.
+    pub node_offset: u32,
+    /// Offset of triangles in the int buffer of the storage.
+    pub triangle_offset: u32,
+    /// Offset of vertices in the float buffer of the storage.
+    pub vertex_offset: u32,
+    /// Offset of normals in the float buffer of the storage.
+    pub normal_offset: u32,
+    /// Offset of texture coordinates in the float buffer of the storage.
+    pub uv_offset: u32,
+    /// Offset of node bounds in the float buffer of the storage.
+    pub bound_offset: u32,
+    #[pack(skip)]
+    pub bound: Option<BoundingBox>,
+}
+
+impl Shape for Mesh {}
+
+impl Instance<ShapeClass> for Mesh {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/mesh.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "mesh_shape".to_string()
+    }
+    fn implemented_methods() -> Vec<String> {
+        ShapeClass::methods()
+    }
+}
+
+impl Bounded<BoundingBox> for Mesh {
+    fn bound(&self) -> Option<BoundingBox> {
+        self.bound.clone()
+    }
+}
+
+impl Bounded<BoundingSphere> for Mesh {
+    fn bound(&self) -> Option<BoundingSphere> {
+        self.bound.as_ref().and_then(|b| b.bound())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Vector2, Vector3};
+    use crate::{
+        prelude::*,
+        shape::{*, mesh::*},
+        map::Shift,
+    };
+
+    fn tetrahedron() -> MeshData {
+        MeshData::new(
+            vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+                Vector3::new(0.0, 0.0, 1.0),
+            ],
+            vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]],
+        ).unwrap()
+    }
+
+    #[test]
+    fn normals() {
+        let data = tetrahedron();
+        let n = Vector3::repeat(1.0).normalize();
+        assert!((data.normals[0] + n).norm() < 1e-9);
+        assert!(MeshData::new(data.vertices.clone(), vec![[0, 1, 4]]).is_err());
+        assert!(data.clone().with_uvs(vec![Vector2::zeros(); 3]).is_err());
+        assert_eq!(data.with_uvs(vec![Vector2::zeros(); 4]).unwrap().uvs.len(), 4);
+    }
+
+    #[test]
+    fn storage() {
+        let data = tetrahedron();
+        let mut storage = MeshStorage::new();
+        let first = storage.add(&data).unwrap();
+        let second = storage.add(&data).unwrap();
+        assert_eq!(first.node_offset, 0);
+        assert_eq!(second.node_offset as usize, storage.size_int()/2);
+        assert_eq!(Mesh::size_int(), 6);
+
+        let shape = second.map(Shift::new(Vector3::new(1.0, 0.0, 0.0)));
+        let bound: BoundingBox = shape.bound().unwrap();
+        assert!((bound.min - Vector3::new(1.0, 0.0, 0.0)).norm() < 1e-9);
+
+        let mut empty = data.clone();
+        empty.triangles.clear();
+        assert!(empty.bound().is_none());
+        assert!(storage.add(&empty).is_err());
+    }
+}
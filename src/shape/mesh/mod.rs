@@ -0,0 +1,12 @@
+//! Triangle meshes.
+//!
+//! Mesh geometry is uploaded to the shared `MeshStorage`,
+//! and the `Mesh` shape refers to its location in the storage,
+//! so the same mesh could be placed multiple times via `Shape::map`.
+
+mod data;
+pub use data::*;
+mod storage;
+pub use storage::*;
+mod mesh;
+pub use mesh::*;
@@ -0,0 +1,169 @@
+use ocl::{self, builders::KernelBuilder};
+use crate::{
+    prelude::*,
+    Context,
+    bvh::{Bvh, BvhKind},
+    shape::mesh::*,
+};
+
+
+/// Maximum number of triangles in the hierarchy leaf.
+const LEAF_SIZE: usize = 4;
+/// Maximum depth of the hierarchy, the traversal stack `MESH_SHAPE_STACK_SIZE` in `mesh.h`
+/// holds one more node than the depth.
+const MAX_DEPTH: usize = 31;
+
+/// Host storage of the geometry of all meshes used in the scene.
+///
+/// The geometry is stored on the device as `MeshBuffer`
+/// which is passed to every shape hit function.
+#[derive(Clone, Debug, Default)]
+pub struct MeshStorage {
+    buffer_int: Vec<i32>,
+    buffer_float: Vec<f32>,
+}
+
+impl MeshStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the mesh geometry along with its hierarchy to the storage.
+    ///
+    /// Returns the shape that refers to this geometry or an error if the mesh has no triangles.
+    pub fn add(&mut self, data: &MeshData) -> crate::Result<Mesh> {
+        let bound = data.bound().ok_or("mesh has no triangles")?;
+        let bounds = (0..data.triangles.len())
+        .map(|i| data.triangle_bound(i))
+        .collect::<Vec<_>>();
+        let bvh = Bvh::build(&bounds, LEAF_SIZE, MAX_DEPTH);
+
+        let mesh = Mesh {
+            node_offset: self.buffer_int.len() as u32,
+            triangle_offset: (self.buffer_int.len() + 2*bvh.nodes().len()) as u32,
+            vertex_offset: self.buffer_float.len() as u32,
+            normal_offset: (self.buffer_float.len() + 3*data.vertices.len()) as u32,
+            uv_offset: (self.buffer_float.len() + 6*data.vertices.len()) as u32,
+            bound_offset: (self.buffer_float.len() + 8*data.vertices.len()) as u32,
+            bound: Some(bound),
+        };
+
+        for node in bvh.nodes() {
+            let (a, b) = match node.kind {
+                BvhKind::Leaf { first, count } => (first, count),
+                BvhKind::Inner { right } => (right, 0),
+            };
+            self.buffer_int.extend_from_slice(&[a as i32, b as i32]);
+        }
+        for &i in bvh.order() {
+            self.buffer_int.extend(data.triangles[i].iter().map(|&v| v as i32));
+        }
+        for v in data.vertices.iter().chain(data.normals.iter()) {
+            self.buffer_float.extend(v.iter().map(|&x| x as f32));
+        }
+        if data.uvs.is_empty() {
+            self.buffer_float.extend((0..2*data.vertices.len()).map(|_| 0.0));
+        } else {
+            for uv in data.uvs.iter() {
+                self.buffer_float.extend(uv.iter().map(|&x| x as f32));
+            }
+        }
+        for node in bvh.nodes() {
+            let b = &node.bound;
+            self.buffer_float.extend(b.min.iter().chain(b.max.iter()).map(|&x| x as f32));
+        }
+
+        Ok(mesh)
+    }
+
+    pub fn size_int(&self) -> usize {
+        self.buffer_int.len()
+    }
+    pub fn size_float(&self) -> usize {
+        self.buffer_float.len()
+    }
+}
+
+impl Store for MeshStorage {
+    type Data = MeshBuffer;
+
+    fn create_data(&self, context: &Context) -> crate::Result<Self::Data> {
+        MeshBuffer::new(context, &self.buffer_int, &self.buffer_float)
+    }
+
+    fn update_data(&self, context: &Context, data: &mut Self::Data) -> crate::Result<()> {
+        data.write(context, &self.buffer_int, &self.buffer_float)
+    }
+}
+
+/// Device buffers of the mesh geometry.
+pub struct MeshBuffer {
+    buffer_int: ocl::Buffer<i32>,
+    buffer_float: ocl::Buffer<f32>,
+}
+
+impl MeshBuffer {
+    fn new(context: &Context, buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        let mut buffer = Self {
+            buffer_int: Self::create(context, buffer_int.len())?,
+            buffer_float: Self::create(context, buffer_float.len())?,
+        };
+        buffer.write(context, buffer_int, buffer_float)?;
+        Ok(buffer)
+    }
+
+    fn create<T: ocl::OclPrm>(context: &Context, len: usize) -> crate::Result<ocl::Buffer<T>> {
+        Ok(ocl::Buffer::<T>::builder()
+        .queue(context.queue().clone())
+        .flags(ocl::flags::MEM_READ_ONLY)
+        .len(len.max(1))
+        .fill_val(T::default())
+        .build()?)
+    }
+
+    fn write(&mut self, context: &Context, buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<()> {
+        // Buffers are reallocated if the storage has grown.
+        if self.buffer_int.len() != buffer_int.len().max(1) {
+            self.buffer_int = Self::create(context, buffer_int.len())?;
+        }
+        if self.buffer_float.len() != buffer_float.len().max(1) {
+            self.buffer_float = Self::create(context, buffer_float.len())?;
+        }
+        if !buffer_int.is_empty() {
+            self.buffer_int.cmd()
+            .offset(0)
+            .write(buffer_int)
+            .enq()?;
+        }
+        if !buffer_float.is_empty() {
+            self.buffer_float.cmd()
+            .offset(0)
+            .write(buffer_float)
+            .enq()?;
+        }
+        Ok(())
+    }
+
+    pub fn buffer_int(&self) -> &ocl::Buffer<i32> {
+        &self.buffer_int
+    }
+    pub fn buffer_float(&self) -> &ocl::Buffer<f32> {
+        &self.buffer_float
+    }
+}
+
+impl Push for MeshBuffer {
+    fn args_count() -> usize {
+        2
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        kb
+        .arg(None::<&ocl::Buffer<i32>>) // int buffer
+        .arg(None::<&ocl::Buffer<f32>>); // float buffer
+    }
+    fn args_set(&mut self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        k.set_arg(i + 0, self.buffer_int())?;
+        k.set_arg(i + 1, self.buffer_float())?;
+        Ok(())
+    }
+}
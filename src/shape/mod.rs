@@ -5,10 +5,23 @@ pub use bound::*;
 mod target;
 pub use target::*;
 
+mod bounding_box;
+pub use bounding_box::*;
+mod bounding_sphere;
+pub use bounding_sphere::*;
+mod parallelogram;
+pub use parallelogram::*;
+
 mod mapper;
 pub use mapper::*;
+mod csg;
+pub use csg::*;
 
 mod select;
 
+pub mod primitives;
+pub mod sdf;
+pub mod mesh;
+
 #[cfg(test)]
 pub mod test;
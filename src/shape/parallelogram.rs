@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use crate::{
+    prelude::*,
+    map::Map,
+    shape::*,
+};
+
+
+/// Flat parallelogram `origin + s*u + t*v` where `s` and `t` are in `[0, 1]`.
+///
+/// It could be used both as a shape and as a target, e.g. for rectangular area lights.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Parallelogram {
+    origin: Vector3<f64>,
+    u: Vector3<f64>,
+    v: Vector3<f64>,
+}
+
+impl Parallelogram {
+    /// Creates the parallelogram, edges must be finite, non-zero and non-collinear.
+    pub fn new(origin: Vector3<f64>, u: Vector3<f64>, v: Vector3<f64>) -> crate::Result<Self> {
+        if !u.cross(&v).norm().is_normal() {
+            return Err("Parallelogram: edges are collinear".into());
+        }
+        Ok(Self { origin, u, v })
+    }
+
+    pub fn origin(&self) -> &Vector3<f64> {
+        &self.origin
+    }
+    pub fn u(&self) -> &Vector3<f64> {
+        &self.u
+    }
+    pub fn v(&self) -> &Vector3<f64> {
+        &self.v
+    }
+
+    pub fn corners(&self) -> [Vector3<f64>; 4] {
+        let o = self.origin;
+        [o, o + self.u, o + self.u + self.v, o + self.v]
+    }
+
+    /// Solid angle of the parallelogram viewed from the point.
+    pub fn solid_angle(&self, pos: Vector3<f64>) -> f64 {
+        let c = self.corners();
+        triangle_solid_angle(pos, c[0], c[1], c[2]) + triangle_solid_angle(pos, c[0], c[2], c[3])
+    }
+}
+
+/// Solid angle of the triangle viewed from the point
+/// (A. Van Oosterom, J. Strackee, "The Solid Angle of a Plane Triangle").
+fn triangle_solid_angle(pos: Vector3<f64>, a: Vector3<f64>, b: Vector3<f64>, c: Vector3<f64>) -> f64 {
+    let (a, b, c) = (a - pos, b - pos, c - pos);
+    let (la, lb, lc) = (a.norm(), b.norm(), c.norm());
+    let num = a.dot(&b.cross(&c)).abs();
+    let den = la*lb*lc + a.dot(&b)*lc + b.dot(&c)*la + c.dot(&a)*lb;
+    2.0*num.atan2(den)
+}
+
+impl Bound for Parallelogram {}
+
+impl MapBound for Parallelogram {
+    fn map_bound<M: Map>(&self, map: &M) -> Self {
+        // Maps are invertible, so the edges stay non-collinear.
+        Self {
+            origin: map.apply_abs(self.origin),
+            u: map.apply_rel(self.u),
+            v: map.apply_rel(self.v),
+        }
+    }
+}
+
+impl Instance<BoundClass> for Parallelogram {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/parallelogram.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "parallelogram".to_string()
+    }
+}
+
+impl Target for Parallelogram {}
+
+impl Instance<TargetClass> for Parallelogram {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/parallelogram.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "parallelogram".to_string()
+    }
+    fn implemented_methods() -> Vec<String> {
+        TargetClass::methods()
+    }
+}
+
+impl Shape for Parallelogram {}
+
+impl Instance<ShapeClass> for Parallelogram {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/shape/parallelogram.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "parallelogram".to_string()
+    }
+    fn implemented_methods() -> Vec<String> {
+        ShapeClass::methods()
+    }
+}
+
+/// Padding of the bounding box relative to the edge lengths.
+const BOUND_PAD: f64 = 1e-4;
+
+/// The box is padded in all directions, so the parallelogram
+/// that is parallel to some axis doesn't give the box of zero thickness.
+impl Bounded<BoundingBox> for Parallelogram {
+    fn bound(&self) -> Option<BoundingBox> {
+        let pad = Vector3::repeat(BOUND_PAD*(self.u.norm() + self.v.norm()));
+        BoundingBox::from_points(self.corners().iter().cloned())
+        .map(|b| BoundingBox::new(b.min - pad, b.max + pad))
+    }
+}
+
+impl Bounded<BoundingSphere> for Parallelogram {
+    fn bound(&self) -> Option<BoundingSphere> {
+        BoundingSphere::from_points(self.corners().iter().cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+    use nalgebra::Vector3;
+    use crate::{
+        shape::*,
+        map::*,
+    };
+
+    #[test]
+    fn solid_angle() {
+        // Each face of the cube occupies one sixth of the sphere when viewed from its center.
+        let face = Parallelogram::new(
+            Vector3::new(-1.0, -1.0, 1.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(0.0, 2.0, 0.0),
+        ).unwrap();
+        assert!((face.solid_angle(Vector3::zeros()) - 4.0*PI/6.0).abs() < 1e-9);
+        let far = face.solid_angle(Vector3::new(0.0, 0.0, 1.0 + 1e3));
+        assert!((far - 4.0/1e6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mapped() {
+        let face = Parallelogram::new(Vector3::zeros(), Vector3::x(), Vector3::y()).unwrap();
+        let mapped = face.map_bound(&Scale::uniform(2.0).unwrap().chain(Shift::new(Vector3::z())));
+        assert_eq!(*mapped.origin(), Vector3::z());
+        assert_eq!(*mapped.u(), 2.0*Vector3::x());
+        let bbox: BoundingBox = mapped.bound().unwrap();
+        assert!((bbox.max - Vector3::new(2.0, 2.0, 1.0)).norm() < 1e-2);
+    }
+
+    #[test]
+    fn degenerate() {
+        assert!(Parallelogram::new(Vector3::zeros(), Vector3::x(), 2.0*Vector3::x()).is_err());
+        assert!(Parallelogram::new(Vector3::zeros(), Vector3::zeros(), Vector3::y()).is_err());
+    }
+
+    #[test]
+    fn flat_bound() {
+        let face = Parallelogram::new(Vector3::zeros(), Vector3::x(), Vector3::y()).unwrap();
+        let bbox: BoundingBox = face.bound().unwrap();
+        assert!(bbox.min.z < 0.0 && bbox.max.z > 0.0);
+        for c in face.corners().iter() {
+            assert!(bbox.min.iter().zip(c.iter()).all(|(a, b)| a < b));
+            assert!(bbox.max.iter().zip(c.iter()).all(|(a, b)| a > b));
+        }
+    }
+}
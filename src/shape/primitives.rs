@@ -0,0 +1,237 @@
+//! Basic shapes of unit size.
+//!
+//! The shapes are placed at the origin and have the unit size,
+//! so they are designed to be transformed to the desired position via `Shape::map`.
+
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use crate::{
+    prelude::*,
+    shape::*,
+};
+
+
+macro_rules! impl_primitive {
+    ($Shape:ident, $name:expr) => {
+        impl Shape for $Shape {}
+
+        impl Instance<ShapeClass> for $Shape {
+            fn source(_: &mut HashSet<u64>) -> String {
+                format!("#include <clay_core/shape/{}.h>", $name)
+            }
+            fn inst_name() -> String {
+                format!("{}_shape", $name)
+            }
+            fn implemented_methods() -> Vec<String> {
+                ShapeClass::methods()
+            }
+        }
+    };
+}
+
+/// Sphere of unit radius centered at the origin.
+#[derive(Clone, Debug, Default, PartialEq, Pack, Unpack)]
+pub struct Sphere {}
+
+impl Sphere {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl_primitive!(Sphere, "sphere");
+
+impl Bounded<BoundingBox> for Sphere {
+    fn bound(&self) -> Option<BoundingBox> {
+        Some(BoundingBox::new(-Vector3::repeat(1.0), Vector3::repeat(1.0)))
+    }
+}
+
+impl Bounded<BoundingSphere> for Sphere {
+    fn bound(&self) -> Option<BoundingSphere> {
+        Some(BoundingSphere::new(Vector3::zeros(), 1.0))
+    }
+}
+
+/// Half-space `z <= 0` bounded by the `xy`-plane.
+///
+/// Its boundary is the infinite plane, so it could be used as a floor or a wall.
+#[derive(Clone, Debug, Default, PartialEq, Pack, Unpack)]
+pub struct HalfSpace {}
+
+impl HalfSpace {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl_primitive!(HalfSpace, "half_space");
+
+impl Bounded<BoundingBox> for HalfSpace {
+    fn bound(&self) -> Option<BoundingBox> {
+        None
+    }
+}
+
+impl Bounded<BoundingSphere> for HalfSpace {
+    fn bound(&self) -> Option<BoundingSphere> {
+        None
+    }
+}
+
+/// Cube `[-1, 1]^3` (so the length of its edge is 2).
+#[derive(Clone, Debug, Default, PartialEq, Pack, Unpack)]
+pub struct Cube {}
+
+impl Cube {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl_primitive!(Cube, "cube");
+
+impl Bounded<BoundingBox> for Cube {
+    fn bound(&self) -> Option<BoundingBox> {
+        Some(BoundingBox::new(-Vector3::repeat(1.0), Vector3::repeat(1.0)))
+    }
+}
+
+impl Bounded<BoundingSphere> for Cube {
+    fn bound(&self) -> Option<BoundingSphere> {
+        Some(BoundingSphere::new(Vector3::zeros(), 3f64.sqrt()))
+    }
+}
+
+/// Capped cylinder of unit radius around the `z` axis with `-1 <= z <= 1`.
+#[derive(Clone, Debug, Default, PartialEq, Pack, Unpack)]
+pub struct Cylinder {}
+
+impl Cylinder {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl_primitive!(Cylinder, "cylinder");
+
+impl Bounded<BoundingBox> for Cylinder {
+    fn bound(&self) -> Option<BoundingBox> {
+        Some(BoundingBox::new(-Vector3::repeat(1.0), Vector3::repeat(1.0)))
+    }
+}
+
+impl Bounded<BoundingSphere> for Cylinder {
+    fn bound(&self) -> Option<BoundingSphere> {
+        Some(BoundingSphere::new(Vector3::zeros(), 2f64.sqrt()))
+    }
+}
+
+/// Cone with the apex at `(0, 0, 1)` and the base of unit radius lying in the plane `z = -1`.
+#[derive(Clone, Debug, Default, PartialEq, Pack, Unpack)]
+pub struct Cone {}
+
+impl Cone {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl_primitive!(Cone, "cone");
+
+impl Bounded<BoundingBox> for Cone {
+    fn bound(&self) -> Option<BoundingBox> {
+        Some(BoundingBox::new(-Vector3::repeat(1.0), Vector3::repeat(1.0)))
+    }
+}
+
+impl Bounded<BoundingSphere> for Cone {
+    fn bound(&self) -> Option<BoundingSphere> {
+        // The sphere passes through the apex and the base rim.
+        Some(BoundingSphere::new(Vector3::new(0.0, 0.0, -0.25), 1.25))
+    }
+}
+
+/// Torus around the `z` axis with unit major radius and the specified minor radius.
+///
+/// The intersection is found by sphere tracing, so it's a bit slower than other primitives.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Torus {
+    pub minor_radius: f64,
+}
+
+impl Torus {
+    pub fn new(minor_radius: f64) -> Self {
+        Self { minor_radius }
+    }
+}
+
+impl_primitive!(Torus, "torus");
+
+impl Bounded<BoundingBox> for Torus {
+    fn bound(&self) -> Option<BoundingBox> {
+        let (r, h) = (1.0 + self.minor_radius, self.minor_radius);
+        Some(BoundingBox::new(-Vector3::new(r, r, h), Vector3::new(r, r, h)))
+    }
+}
+
+impl Bounded<BoundingSphere> for Torus {
+    fn bound(&self) -> Option<BoundingSphere> {
+        Some(BoundingSphere::new(Vector3::zeros(), 1.0 + self.minor_radius))
+    }
+}
+
+/// Disk of unit radius in the `xy`-plane centered at the origin.
+///
+/// The disk is flat, so it has the same enter and exit points.
+#[derive(Clone, Debug, Default, PartialEq, Pack, Unpack)]
+pub struct Disk {}
+
+impl Disk {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl_primitive!(Disk, "disk");
+
+impl Bounded<BoundingBox> for Disk {
+    fn bound(&self) -> Option<BoundingBox> {
+        Some(BoundingBox::new(Vector3::new(-1.0, -1.0, 0.0), Vector3::new(1.0, 1.0, 0.0)))
+    }
+}
+
+impl Bounded<BoundingSphere> for Disk {
+    fn bound(&self) -> Option<BoundingSphere> {
+        Some(BoundingSphere::new(Vector3::zeros(), 1.0))
+    }
+}
+
+/// Single triangle defined by its vertices.
+///
+/// Unlike other primitives it has no unit form,
+/// but still could be transformed via `Shape::map`.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Triangle {
+    pub vertices: [Vector3<f64>; 3],
+}
+
+impl Triangle {
+    pub fn new(a: Vector3<f64>, b: Vector3<f64>, c: Vector3<f64>) -> Self {
+        Self { vertices: [a, b, c] }
+    }
+}
+
+impl_primitive!(Triangle, "triangle");
+
+impl Bounded<BoundingBox> for Triangle {
+    fn bound(&self) -> Option<BoundingBox> {
+        BoundingBox::from_points(self.vertices.iter().cloned())
+    }
+}
+
+impl Bounded<BoundingSphere> for Triangle {
+    fn bound(&self) -> Option<BoundingSphere> {
+        BoundingSphere::from_points(self.vertices.iter().cloned())
+    }
+}
@@ -0,0 +1,79 @@
+/// The macro for defining a distance function in OpenCL.
+///
+/// The body has access to the point `p` and should return the signed distance to the surface.
+/// The resulting type has no parameters.
+///
+/// ```ignore
+/// sdf_custom!(Gyroid, "return fabs(dot(sin(p), cos(p.yzx)))/3.0f - 0.1f;");
+/// ```
+#[macro_export]
+macro_rules! sdf_custom {
+    ($Sdf:ident, $body:expr) => {
+        #[derive(Clone, Debug, Default)]
+        pub struct $Sdf {}
+
+        impl $Sdf {
+            pub fn new() -> Self {
+                Self {}
+            }
+        }
+
+        impl $crate::shape::sdf::Sdf for $Sdf {}
+
+        impl $crate::Instance<$crate::shape::sdf::SdfClass> for $Sdf {
+            fn source(cache: &mut std::collections::HashSet<u64>) -> String {
+                use $crate::TypeHash;
+                if !cache.insert(Self::type_hash()) {
+                    return String::new()
+                }
+                [
+                    "#include <clay_core/shape/sdf/sdf.h>".to_string(),
+                    format!("SDF_DIST_RET {}_dist(SDF_DIST_ARGS_DEF) {{", Self::inst_name()),
+                    $body.to_string(),
+                    "}".to_string(),
+                ].join("\n")
+            }
+            fn inst_name() -> String {
+                use $crate::TypeHash;
+                format!("__sdf_custom_{:x}", Self::type_hash())
+            }
+        }
+
+        impl $crate::Pack for $Sdf {
+            fn size_int() -> usize { 0 }
+            fn size_float() -> usize { 0 }
+            fn pack_to(&self, _buffer_int: &mut [i32], _buffer_float: &mut [f32]) {}
+        }
+
+        impl $crate::Unpack for $Sdf {
+            fn unpack_from(_buffer_int: &[i32], _buffer_float: &[f32]) -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod check {
+    use crate::{
+        prelude::*,
+        shape::{Shape, ShapeClass, primitives::*, sdf::*},
+    };
+
+    sdf_custom!(TestSdf, "return length(p) - 1.0f;");
+
+    #[test]
+    fn source() {
+        let shape = TestSdf::new().round(0.1).twist(1.0).smooth_union(SdfSphere::new(0.5), 0.2).shape();
+        let _ = shape.cover(crate::material::test::TestMaterial::<i32>::new());
+        type T = SdfShape<SmoothUnion<Twist<Round<TestSdf>>, SdfSphere>>;
+        let source = <T as Instance<ShapeClass>>::source(&mut std::collections::HashSet::new());
+        assert!(source.contains(&format!("{}_MAX_STEPS_DI 0", T::inst_name())));
+        assert!(source.contains(&format!("SDF_DIST_RET {}_dist", TestSdf::inst_name())));
+        // Primitives of both modules could be imported together.
+        assert_ne!(
+            <Sphere as Instance<ShapeClass>>::inst_name(),
+            <SdfSphere as Instance<SdfClass>>::inst_name(),
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Shapes defined by signed distance functions.
+//!
+//! The distance function is either written in OpenCL directly (*see `sdf_custom!`*)
+//! or composed from the built-in primitives (`SdfSphere`, `SdfCuboid`, etc.) and operators.
+//! Then it is turned into a regular shape via `Sdf::shape` and traced by sphere tracing.
+
+mod sdf;
+pub use sdf::*;
+mod shape;
+pub use shape::*;
+mod primitives;
+pub use primitives::*;
+mod ops;
+pub use ops::*;
+mod custom;
@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use crate::{
+    prelude::*,
+    shape::sdf::*,
+};
+
+
+/// Union of two distance functions with smoothly blended junction.
+#[derive(Clone, Debug, Pack, Unpack)]
+pub struct SmoothUnion<A: Sdf, B: Sdf> {
+    /// Size of the blending region, zero gives the ordinary union.
+    pub k: f64,
+    pub first: A,
+    pub second: B,
+}
+
+impl<A: Sdf, B: Sdf> SmoothUnion<A, B> {
+    pub fn new(first: A, second: B, k: f64) -> Self {
+        Self { k, first, second }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothUnion<A, B> {}
+
+impl<A: Sdf, B: Sdf> Instance<SdfClass> for SmoothUnion<A, B> {
+    fn source(cache: &mut HashSet<u64>) -> String {
+        if !cache.insert(Self::type_hash()) {
+            return String::new()
+        }
+        [
+            A::source(cache),
+            B::source(cache),
+            "#include <clay_core/shape/sdf/ops.h>".to_string(),
+            Self::layout().defines(&Self::inst_name()),
+            format!(
+                "SDF_SMOOTH_UNION_FN_DEF({}, {}, {})",
+                Self::inst_name(), A::inst_name(), B::inst_name(),
+            ),
+        ].join("\n")
+    }
+    fn inst_name() -> String {
+        format!("__sdf_smooth_union_{:x}", Self::type_hash())
+    }
+}
+
+/// Distance function with rounded edges.
+#[derive(Clone, Debug, Pack, Unpack)]
+pub struct Round<D: Sdf> {
+    pub radius: f64,
+    pub sdf: D,
+}
+
+impl<D: Sdf> Round<D> {
+    pub fn new(sdf: D, radius: f64) -> Self {
+        Self { radius, sdf }
+    }
+}
+
+impl<D: Sdf> Sdf for Round<D> {}
+
+impl<D: Sdf> Instance<SdfClass> for Round<D> {
+    fn source(cache: &mut HashSet<u64>) -> String {
+        if !cache.insert(Self::type_hash()) {
+            return String::new()
+        }
+        [
+            D::source(cache),
+            "#include <clay_core/shape/sdf/ops.h>".to_string(),
+            Self::layout().defines(&Self::inst_name()),
+            format!("SDF_ROUND_FN_DEF({}, {})", Self::inst_name(), D::inst_name()),
+        ].join("\n")
+    }
+    fn inst_name() -> String {
+        format!("__sdf_round_{:x}", Self::type_hash())
+    }
+}
+
+/// Distance function twisted around the `z` axis.
+#[derive(Clone, Debug, Pack, Unpack)]
+pub struct Twist<D: Sdf> {
+    /// Angle of rotation per unit of length along the axis.
+    pub rate: f64,
+    pub sdf: D,
+}
+
+impl<D: Sdf> Twist<D> {
+    pub fn new(sdf: D, rate: f64) -> Self {
+        Self { rate, sdf }
+    }
+}
+
+impl<D: Sdf> Sdf for Twist<D> {}
+
+impl<D: Sdf> Instance<SdfClass> for Twist<D> {
+    fn source(cache: &mut HashSet<u64>) -> String {
+        if !cache.insert(Self::type_hash()) {
+            return String::new()
+        }
+        [
+            D::source(cache),
+            "#include <clay_core/shape/sdf/ops.h>".to_string(),
+            Self::layout().defines(&Self::inst_name()),
+            format!("SDF_TWIST_FN_DEF({}, {})", Self::inst_name(), D::inst_name()),
+        ].join("\n")
+    }
+    fn inst_name() -> String {
+        format!("__sdf_twist_{:x}", Self::type_hash())
+    }
+}
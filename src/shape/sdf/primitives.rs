@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use crate::{
+    prelude::*,
+    shape::sdf::*,
+};
+
+
+macro_rules! impl_sdf {
+    ($Sdf:ident, $name:expr) => {
+        impl Sdf for $Sdf {}
+
+        impl Instance<SdfClass> for $Sdf {
+            fn source(_: &mut HashSet<u64>) -> String {
+                format!("#include <clay_core/shape/sdf/{}.h>", $name)
+            }
+            fn inst_name() -> String {
+                format!("{}_sdf", $name)
+            }
+        }
+    };
+}
+
+/// Sphere centered at the origin.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct SdfSphere {
+    pub radius: f64,
+}
+
+impl SdfSphere {
+    pub fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+}
+
+impl_sdf!(SdfSphere, "sphere");
+
+/// Rectangular box centered at the origin.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct SdfCuboid {
+    pub half_size: Vector3<f64>,
+}
+
+impl SdfCuboid {
+    pub fn new(half_size: Vector3<f64>) -> Self {
+        Self { half_size }
+    }
+}
+
+impl_sdf!(SdfCuboid, "cuboid");
+
+/// Torus around the `z` axis.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct SdfTorus {
+    pub major_radius: f64,
+    pub minor_radius: f64,
+}
+
+impl SdfTorus {
+    pub fn new(major_radius: f64, minor_radius: f64) -> Self {
+        Self { major_radius, minor_radius }
+    }
+}
+
+impl_sdf!(SdfTorus, "torus");
+
+/// Capped cylinder around the `z` axis.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct SdfCylinder {
+    pub radius: f64,
+    pub half_height: f64,
+}
+
+impl SdfCylinder {
+    pub fn new(radius: f64, half_height: f64) -> Self {
+        Self { radius, half_height }
+    }
+}
+
+impl_sdf!(SdfCylinder, "cylinder");
@@ -0,0 +1,53 @@
+use crate::{
+    prelude::*,
+    shape::sdf::*,
+};
+
+
+/// Signed distance function, i.e. the distance to the surface of a shape
+/// that is negative inside the shape.
+///
+/// The function should not overestimate the distance, otherwise the surface could be missed.
+pub trait Sdf: Pack + Instance<SdfClass> + Sized {
+    /// Makes a shape that could be rendered from the distance function.
+    fn shape(self) -> SdfShape<Self> {
+        SdfShape::new(self)
+    }
+    /// Smoothly merges two distance functions, `k` is the size of the blending region.
+    fn smooth_union<S: Sdf>(self, other: S, k: f64) -> SmoothUnion<Self, S> {
+        SmoothUnion::new(self, other, k)
+    }
+    /// Inflates the surface by the radius making the edges round.
+    fn round(self, radius: f64) -> Round<Self> {
+        Round::new(self, radius)
+    }
+    /// Twists the space around the `z` axis by the angle `rate` per unit of length.
+    ///
+    /// The resulting function overestimates the distance,
+    /// so decrease `SdfShape::step_scale` for strong twists.
+    fn twist(self, rate: f64) -> Twist<Self> {
+        Twist::new(self, rate)
+    }
+}
+
+/// Device interface for signed distance function.
+///
+/// How to implement in OpenCL:
+/// ```c
+/// #include <clay_core/shape/sdf/sdf.h>
+///
+/// SDF_DIST_RET <sdf>_dist(
+///     SDF_DIST_ARGS_DEF
+/// ) {
+///     ...
+/// }
+/// ```
+pub enum SdfClass {}
+impl Class for SdfClass {
+    fn name() -> String {
+        "sdf".to_string()
+    }
+    fn methods() -> Vec<String> {
+        vec!["dist".to_string()]
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+use crate::{
+    prelude::*,
+    shape::{*, sdf::*},
+};
+
+
+/// Shape which surface is found by sphere tracing of the distance function.
+#[derive(Clone, Debug, Pack, Unpack)]
+pub struct SdfShape<D: Sdf> {
+    /// Maximum number of tracing steps for a single ray.
+    pub max_steps: u32,
+    /// Distance to the surface that is considered as a hit.
+    pub epsilon: f64,
+    /// Distance along the ray after which tracing stops.
+    pub max_distance: f64,
+    /// Multiplier of each step, should be less than one for functions that overestimate the distance.
+    pub step_scale: f64,
+    pub sdf: D,
+}
+
+impl<D: Sdf> SdfShape<D> {
+    pub fn new(sdf: D) -> Self {
+        Self {
+            max_steps: 256,
+            epsilon: 1e-4,
+            max_distance: 1e3,
+            step_scale: 1.0,
+            sdf,
+        }
+    }
+}
+
+impl<D: Sdf> Shape for SdfShape<D> {}
+
+impl<D: Sdf> Instance<ShapeClass> for SdfShape<D> {
+    fn source(cache: &mut HashSet<u64>) -> String {
+        if !cache.insert(Self::type_hash()) {
+            return String::new()
+        }
+        [
+            D::source(cache),
+            "#include <clay_core/shape/sdf/shape.h>".to_string(),
+            Self::layout().defines(&Self::inst_name()),
+            format!("SDF_SHAPE_FN_DEF({}, {})", Self::inst_name(), D::inst_name()),
+        ].join("\n")
+    }
+    fn inst_name() -> String {
+        format!("__sdf_shape_{:x}", Self::type_hash())
+    }
+    fn implemented_methods() -> Vec<String> {
+        ShapeClass::methods()
+    }
+}
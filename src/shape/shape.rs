@@ -1,7 +1,7 @@
 use crate::{
     prelude::*,
     map::Map, 
-    shape::ShapeMapper,
+    shape::{ShapeMapper, Union, Intersection, Difference},
     material::Material, 
     object::Covered,
 };
@@ -18,6 +18,18 @@ pub trait Shape: Pack + Instance<ShapeClass> {
     fn map<M: Map>(self, map: M) -> ShapeMapper<Self, M> {
         ShapeMapper { shape: self, map }
     }
+    /// Creates a union of this shape with another one.
+    fn union<S: Shape>(self, other: S) -> Union<Self, S> {
+        Union::new(self, other)
+    }
+    /// Creates an intersection of this shape with another one.
+    fn intersect<S: Shape>(self, other: S) -> Intersection<Self, S> {
+        Intersection::new(self, other)
+    }
+    /// Cuts another shape out of this one.
+    fn subtract<S: Shape>(self, other: S) -> Difference<Self, S> {
+        Difference::new(self, other)
+    }
     /// Transforms the shape in an object by covering it with material.
     fn cover<M: Material>(self, material: M) -> Covered<Self, M> {
         Covered::new(self, material)
@@ -36,12 +48,26 @@ pub trait Shape: Pack + Instance<ShapeClass> {
 ///     ...
 /// }
 /// ```
+///
+/// `hit_ext` is the optional extension of `hit` that also gets the shared mesh storage
+/// and reports the surface at the hit point (e.g. texture coordinates).
+/// Shapes that don't implement it get the one that calls `hit`.
 pub enum ShapeClass {}
 impl Class for ShapeClass {
     fn name() -> String {
         "shape".to_string()
     }
     fn methods() -> Vec<String> {
-        vec!["hit".to_string()]
+        vec![
+            "hit".to_string(),
+            "hit_ext".to_string(),
+        ]
+    }
+    fn optional_methods() -> Vec<String> {
+        vec!["hit_ext".to_string()]
+    }
+    fn default_source(inst_name: &str, method: &str) -> String {
+        assert_eq!(method, "hit_ext");
+        format!("SHAPE_HIT_EXT_FROM_HIT_FN_DEF({})", inst_name)
     }
 }
@@ -8,6 +8,9 @@ use crate::{
 pub trait Target: Pack + Instance<TargetClass> {}
 
 /// Device interface for target.
+///
+/// `pdf` is optional, targets that implement only `sample` get the one
+/// that returns the inverse angular size given by `sample`.
 pub enum TargetClass {}
 impl Class for TargetClass {
     fn name() -> String {
@@ -16,8 +19,16 @@ impl Class for TargetClass {
     fn methods() -> Vec<String> {
         vec![
             "sample".to_string(),
+            "pdf".to_string(),
         ]
     }
+    fn optional_methods() -> Vec<String> {
+        vec!["pdf".to_string()]
+    }
+    fn default_source(inst_name: &str, method: &str) -> String {
+        assert_eq!(method, "pdf");
+        format!("TARGET_PDF_FROM_SAMPLE_FN_DEF({})", inst_name)
+    }
 }
 
 /// The shape that could be put inside the specified bound.
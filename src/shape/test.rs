@@ -4,6 +4,7 @@ use std::{
 };
 use crate::{
     pack::*,
+    unpack::*,
     class::*,
     shape::*,
 };
@@ -31,3 +32,7 @@ impl<T> Pack for TestShape<T> {
     fn size_float() -> usize { 0 }
     fn pack_to(&self, _buffer_int: &mut [i32], _buffer_float: &mut [f32]) {}
 }
+
+impl<T> Unpack for TestShape<T> {
+    fn unpack_from(_buffer_int: &[i32], _buffer_float: &[f32]) -> Self { Self::new() }
+}
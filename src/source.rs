@@ -17,3 +17,9 @@ pub fn source() -> MemHook {
     }
     hook
 }
+
+/// Content of the file in the source tree (e.g. `material/diffuse.h`).
+#[cfg(test)]
+pub(crate) fn file(name: &str) -> Option<&'static str> {
+    OCL_SRC_LIST.iter().find(|(n, _)| Path::new(n) == Path::new(name)).map(|(_, c)| *c)
+}
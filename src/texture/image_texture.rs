@@ -0,0 +1,30 @@
+use std::collections::HashSet;
+use crate::{
+    prelude::*,
+    texture::*,
+};
+
+
+/// Image located in `TextureStorage`.
+///
+/// The texture is obtained via `TextureStorage::add` or `TextureStorage::load`.
+/// Texture coordinates `[0, 1]^2` cover the whole image: `u` goes right along the rows
+/// and `v` goes down from the top row. The image is repeated outside of this range.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct ImageTexture {
+    /// Offset of the pixels in the storage buffer.
+    pub offset: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Texture for ImageTexture {}
+
+impl Instance<TextureClass> for ImageTexture {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/texture/image.h>".to_string()
+    }
+    fn inst_name() -> String {
+        "image_texture".to_string()
+    }
+}
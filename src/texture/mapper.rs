@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+use crate::{
+    prelude::*,
+    map::Map,
+    texture::*,
+};
+
+/// A new texture obtained by applying some mapping to another texture.
+///
+/// The texture is evaluated at the surface point transformed by the inverse map,
+/// so it could be moved, scaled or rotated relative to the shape.
+#[derive(Clone, Debug, Pack, Unpack)]
+pub struct TextureMapper<T: Texture, M: Map> {
+    pub texture: T,
+    pub map: M,
+}
+
+impl<T: Texture, M: Map> TextureMapper<T, M> {
+    pub fn new(texture: T, map: M) -> Self {
+        Self { texture, map }
+    }
+}
+
+impl<T: Texture, M: Map> Texture for TextureMapper<T, M> {}
+
+impl<T: Texture, M: Map> Instance<TextureClass> for TextureMapper<T, M> {
+    fn source(cache: &mut HashSet<u64>) -> String {
+        if !cache.insert(Self::type_hash()) {
+            return String::new()
+        }
+        [
+            T::source(cache),
+            M::source(cache),
+            "#include <clay_core/texture/mapper.h>".to_string(),
+            Self::layout().defines(&Self::inst_name()),
+            format!(
+                "MAP_TEXTURE_FN_DEF({}, {}, {}, {}_MAP_DI, {}_MAP_DF)",
+                Self::inst_name(),
+                T::inst_name(),
+                M::inst_name(),
+                Self::inst_name(), Self::inst_name(),
+            ),
+        ].join("\n")
+    }
+    fn inst_name() -> String {
+        format!(
+            "__texture_mapper_{:x}",
+            Self::type_hash(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use nalgebra::Vector3;
+    use crate::{
+        prelude::*,
+        map::*,
+        texture::*,
+    };
+
+    type TestMapper = TextureMapper<Checker, Scale>;
+
+    #[test]
+    fn pack() {
+        let checker = Checker::new(0.5, Vector3::zeros(), Vector3::new(1.0, 1.0, 1.0));
+        let texture = checker.map(Scale::uniform(2.0).unwrap());
+        assert!(format!("{:?}", texture.clone()).starts_with("TextureMapper"));
+        assert_eq!(TestMapper::size_float(), Checker::size_float() + Scale::size_float());
+
+        // The texture goes first followed by the map.
+        let mut buffer_float = vec![0.0; TestMapper::size_float()];
+        texture.pack_to(&mut [], &mut buffer_float);
+        let mut map_float = vec![0.0; Scale::size_float()];
+        Scale::uniform(2.0).unwrap().pack_to(&mut [], &mut map_float);
+        assert_eq!(&buffer_float[..Checker::size_float()], &[0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.5]);
+        assert_eq!(&buffer_float[Checker::size_float()..], &map_float[..]);
+    }
+
+    #[test]
+    fn source() {
+        let name = TestMapper::inst_name();
+        let mut cache = HashSet::new();
+        let source = TestMapper::source(&mut cache);
+        assert!(source.contains("#include <clay_core/texture/pattern.h>"));
+        assert!(source.contains(&format!("#define {}_MAP_DF 7", name)));
+        assert!(source.contains(&format!("MAP_TEXTURE_FN_DEF({}, checker, ", name)));
+        assert!(TestMapper::source(&mut cache).is_empty());
+    }
+}
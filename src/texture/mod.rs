@@ -0,0 +1,19 @@
+//! Textures of object surfaces.
+//!
+//! Images are uploaded to the shared `TextureStorage` (the texture atlas),
+//! and the `ImageTexture` refers to the location of the image in the storage,
+//! so the same image could be used by many materials.
+//!
+//! Procedural textures (`Checker`, `Noise`, `Marble`, etc.) need no images,
+//! they are computed on the device from the point of the surface.
+
+mod texture;
+pub use texture::*;
+mod storage;
+pub use storage::*;
+mod image_texture;
+pub use image_texture::*;
+mod procedural;
+pub use procedural::*;
+mod mapper;
+pub use mapper::*;
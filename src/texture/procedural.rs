@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+use nalgebra::Vector3;
+use crate::{
+    prelude::*,
+    texture::*,
+};
+
+
+// All procedural textures are mixtures of two colors and are evaluated at the point
+// in the space of the primitive shape that was hit, so they don't need texture coordinates
+// and stick to the shape when it is mapped. Use `Texture::map` to move them relative to the shape.
+
+macro_rules! procedural_texture {
+    ($Texture:ident, $header:expr, $name:expr) => {
+        impl Texture for $Texture {}
+
+        impl Instance<TextureClass> for $Texture {
+            fn source(_: &mut HashSet<u64>) -> String {
+                format!("#include <clay_core/texture/{}.h>", $header)
+            }
+            fn inst_name() -> String {
+                $name.to_string()
+            }
+        }
+    };
+}
+
+/// Three-dimensional checkerboard of cubic cells.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Checker {
+    pub first: Vector3<f64>,
+    pub second: Vector3<f64>,
+    /// Size of the cell.
+    pub size: f64,
+}
+
+impl Checker {
+    pub fn new(size: f64, first: Vector3<f64>, second: Vector3<f64>) -> Self {
+        Self { first, second, size }
+    }
+}
+
+procedural_texture!(Checker, "pattern", "checker");
+
+/// Parallel stripes across the x axis.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Stripes {
+    pub first: Vector3<f64>,
+    pub second: Vector3<f64>,
+    /// Distance between the starts of adjacent stripes.
+    pub period: f64,
+    /// Fraction of the period filled with the first color.
+    pub fill: f64,
+}
+
+impl Stripes {
+    pub fn new(period: f64, first: Vector3<f64>, second: Vector3<f64>) -> Self {
+        Self { first, second, period, fill: 0.5 }
+    }
+}
+
+procedural_texture!(Stripes, "pattern", "stripes");
+
+/// Linear gradient between two points, the colors are constant beyond them.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Gradient {
+    pub first: Vector3<f64>,
+    pub second: Vector3<f64>,
+    /// Point of the first color.
+    pub start: Vector3<f64>,
+    /// Point of the second color.
+    pub end: Vector3<f64>,
+}
+
+impl Gradient {
+    pub fn new(
+        start: Vector3<f64>, end: Vector3<f64>,
+        first: Vector3<f64>, second: Vector3<f64>,
+    ) -> Self {
+        Self { first, second, start, end }
+    }
+}
+
+procedural_texture!(Gradient, "pattern", "gradient");
+
+/// Perlin gradient noise, fractal (fBm) if there are several octaves.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Noise {
+    pub first: Vector3<f64>,
+    pub second: Vector3<f64>,
+    /// Size of the largest features.
+    pub size: f64,
+    /// Number of summed layers, each next one has doubled frequency and halved amplitude.
+    pub octaves: u32,
+}
+
+impl Noise {
+    /// Plain Perlin noise.
+    pub fn perlin(size: f64, first: Vector3<f64>, second: Vector3<f64>) -> Self {
+        Self::fbm(size, 1, first, second)
+    }
+    /// Fractal Brownian motion.
+    pub fn fbm(size: f64, octaves: u32, first: Vector3<f64>, second: Vector3<f64>) -> Self {
+        Self { first, second, size, octaves }
+    }
+}
+
+procedural_texture!(Noise, "noise", "noise");
+
+/// Marble-like veins across the x axis distorted by the fractal noise.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Marble {
+    pub first: Vector3<f64>,
+    pub second: Vector3<f64>,
+    /// Distance between veins.
+    pub period: f64,
+    /// Strength of the distortion in periods.
+    pub turbulence: f64,
+    pub octaves: u32,
+}
+
+impl Marble {
+    pub fn new(period: f64, first: Vector3<f64>, second: Vector3<f64>) -> Self {
+        Self { first, second, period, turbulence: 2.0, octaves: 4 }
+    }
+}
+
+procedural_texture!(Marble, "noise", "marble");
+
+/// Wood-like rings around the z axis distorted by the fractal noise.
+#[derive(Clone, Debug, PartialEq, Pack, Unpack)]
+pub struct Wood {
+    pub first: Vector3<f64>,
+    pub second: Vector3<f64>,
+    /// Distance between rings.
+    pub period: f64,
+    /// Strength of the distortion in periods.
+    pub turbulence: f64,
+    pub octaves: u32,
+}
+
+impl Wood {
+    pub fn new(period: f64, first: Vector3<f64>, second: Vector3<f64>) -> Self {
+        Self { first, second, period, turbulence: 0.5, octaves: 2 }
+    }
+}
+
+procedural_texture!(Wood, "noise", "wood");
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use nalgebra::Vector3;
+    use crate::{
+        prelude::*,
+        texture::*,
+    };
+
+    #[test]
+    fn layout() {
+        // Colors go first, so all patterns share the same mixing code on the device.
+        let (a, b) = (Vector3::zeros(), Vector3::new(1.0, 1.0, 1.0));
+        let mut buffer_float = vec![0.0; Checker::size_float()];
+        Checker::new(0.5, a, b).pack_to(&mut [], &mut buffer_float);
+        assert_eq!(buffer_float, [0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.5]);
+
+        assert_eq!(Stripes::size_float(), 8);
+        assert_eq!(Gradient::size_float(), 12);
+        assert_eq!((Noise::size_int(), Noise::size_float()), (1, 7));
+        assert_eq!((Marble::size_int(), Wood::size_float()), (1, 8));
+        assert_eq!(Noise::perlin(1.0, a, b).octaves, 1);
+    }
+
+    #[test]
+    fn source() {
+        let mut cache = HashSet::new();
+        assert_eq!(Wood::source(&mut cache), "#include <clay_core/texture/noise.h>");
+        assert_eq!(Stripes::source(&mut cache), "#include <clay_core/texture/pattern.h>");
+        assert_eq!(Marble::inst_name(), "marble");
+    }
+}
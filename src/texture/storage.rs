@@ -0,0 +1,193 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::Path,
+};
+use ocl::{self, builders::KernelBuilder};
+use image::hdr::HDRDecoder;
+use crate::{
+    prelude::*,
+    Context,
+    texture::*,
+};
+
+
+/// Reads the image file into linear RGB pixels stored row by row.
+///
+/// Radiance `.hdr` files keep their full range, other formats (PNG, JPEG, etc.)
+/// are supposed to be in sRGB color space and are converted to linear one.
+pub(crate) fn load_image(path: &Path) -> crate::Result<(usize, usize, Vec<[f32; 3]>)> {
+    let error = |e: image::ImageError| format!("{}: {}", path.display(), e);
+    let is_hdr = path.extension()
+    .map_or(false, |e| e.to_string_lossy().eq_ignore_ascii_case("hdr"));
+    if is_hdr {
+        let decoder = HDRDecoder::new(BufReader::new(File::open(path)?)).map_err(error)?;
+        let meta = decoder.metadata();
+        let pixels = decoder.read_image_hdr().map_err(error)?
+        .into_iter().map(|p| p.0).collect();
+        Ok((meta.width as usize, meta.height as usize, pixels))
+    } else {
+        let image = image::open(path).map_err(error)?.to_rgb();
+        let (width, height) = image.dimensions();
+        let pixels = image.pixels().map(|p| {
+            [srgb_to_linear(p.0[0]), srgb_to_linear(p.0[1]), srgb_to_linear(p.0[2])]
+        }).collect();
+        Ok((width as usize, height as usize, pixels))
+    }
+}
+
+/// Converts 8-bit sRGB component to the linear value in `[0, 1]`.
+pub fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32/255.0;
+    if c <= 0.04045 {
+        c/12.92
+    } else {
+        ((c + 0.055)/1.055).powf(2.4)
+    }
+}
+
+/// Host storage of all texture images used in the scene (the texture atlas).
+///
+/// Images are stored on the device as `TextureBuffer`
+/// which is passed to every material function.
+#[derive(Clone, Debug, Default)]
+pub struct TextureStorage {
+    buffer: Vec<f32>,
+}
+
+impl TextureStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the image with linear RGB pixels stored row by row.
+    ///
+    /// Returns the texture that refers to this image.
+    pub fn add(&mut self, width: usize, height: usize, pixels: &[[f32; 3]]) -> crate::Result<ImageTexture> {
+        if width == 0 || height == 0 || pixels.len() != width*height {
+            return Err(format!(
+                "texture: {} pixels doesn't match the size {}x{}",
+                pixels.len(), width, height,
+            ).into());
+        }
+        let texture = ImageTexture {
+            offset: self.buffer.len() as u32,
+            width: width as u32,
+            height: height as u32,
+        };
+        self.buffer.extend(pixels.iter().flat_map(|p| p.iter().cloned()));
+        Ok(texture)
+    }
+
+    /// Loads the image from the file and adds it to the storage.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> crate::Result<ImageTexture> {
+        let (width, height, pixels) = load_image(path.as_ref())?;
+        self.add(width, height, &pixels)
+    }
+
+    pub fn size(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+impl Store for TextureStorage {
+    type Data = TextureBuffer;
+
+    fn create_data(&self, context: &Context) -> crate::Result<Self::Data> {
+        TextureBuffer::new(context, &self.buffer)
+    }
+
+    fn update_data(&self, context: &Context, data: &mut Self::Data) -> crate::Result<()> {
+        data.write(context, &self.buffer)
+    }
+}
+
+/// Device buffer of the texture images.
+pub struct TextureBuffer {
+    buffer: ocl::Buffer<f32>,
+}
+
+impl TextureBuffer {
+    fn new(context: &Context, buffer: &[f32]) -> crate::Result<Self> {
+        let mut this = Self { buffer: Self::create(context, buffer.len())? };
+        this.write(context, buffer)?;
+        Ok(this)
+    }
+
+    fn create(context: &Context, len: usize) -> crate::Result<ocl::Buffer<f32>> {
+        Ok(ocl::Buffer::<f32>::builder()
+        .queue(context.queue().clone())
+        .flags(ocl::flags::MEM_READ_ONLY)
+        .len(len.max(1))
+        .fill_val(0.0)
+        .build()?)
+    }
+
+    fn write(&mut self, context: &Context, buffer: &[f32]) -> crate::Result<()> {
+        // Buffer is reallocated if the storage has grown.
+        if self.buffer.len() != buffer.len().max(1) {
+            self.buffer = Self::create(context, buffer.len())?;
+        }
+        if !buffer.is_empty() {
+            self.buffer.cmd()
+            .offset(0)
+            .write(buffer)
+            .enq()?;
+        }
+        Ok(())
+    }
+
+    pub fn buffer(&self) -> &ocl::Buffer<f32> {
+        &self.buffer
+    }
+}
+
+impl Push for TextureBuffer {
+    fn args_count() -> usize {
+        1
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        kb.arg(None::<&ocl::Buffer<f32>>);
+    }
+    fn args_set(&mut self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        k.set_arg(i, self.buffer())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use image::{Rgb, RgbImage};
+    use crate::{
+        prelude::*,
+        texture::*,
+    };
+
+    #[test]
+    fn storage() {
+        let mut storage = TextureStorage::new();
+        let first = storage.add(2, 1, &[[0.0; 3], [1.0; 3]]).unwrap();
+        let second = storage.add(1, 1, &[[0.5; 3]]).unwrap();
+        assert_eq!((first.offset, first.width, first.height), (0, 2, 1));
+        assert_eq!(second.offset, 6);
+        assert_eq!(storage.size(), 9);
+        assert!(storage.add(2, 2, &[[0.0; 3]; 3]).is_err());
+        assert_eq!(ImageTexture::size_int(), 3);
+    }
+
+    #[test]
+    fn load() {
+        let path = env::temp_dir().join("clay_core_texture_test.png");
+        let mut image = RgbImage::new(3, 2);
+        image.put_pixel(1, 0, Rgb([255, 128, 0]));
+        image.save(&path).unwrap();
+
+        let mut storage = TextureStorage::new();
+        let texture = storage.load(&path).unwrap();
+        assert_eq!((texture.width, texture.height), (3, 2));
+        assert_eq!(srgb_to_linear(255), 1.0);
+        assert!((srgb_to_linear(128) - 0.2158).abs() < 1e-3);
+        assert_eq!(storage.buffer[3..6], [1.0, srgb_to_linear(128), 0.0]);
+    }
+}
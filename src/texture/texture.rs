@@ -0,0 +1,39 @@
+use crate::{
+    prelude::*,
+    map::Map,
+    texture::TextureMapper,
+};
+
+
+/// Texture gives the color to each point of the surface.
+///
+/// It is used to modulate materials via `Material::texture_with`
+/// and to blend them via `Material::blend_with`.
+pub trait Texture: Pack + Instance<TextureClass> {
+    /// Creates a new texture by applying some kind of mapping to previous one.
+    fn map<M: Map>(self, map: M) -> TextureMapper<Self, M> {
+        TextureMapper::new(self, map)
+    }
+}
+
+/// Device interface for texture.
+///
+/// How to implement in OpenCL:
+/// ```c
+/// #include <clay_core/texture/texture.h>
+///
+/// TEXTURE_LOOKUP_RET <texture>_lookup(
+///     TEXTURE_LOOKUP_ARGS_DEF
+/// ) {
+///     ...
+/// }
+/// ```
+pub enum TextureClass {}
+impl Class for TextureClass {
+    fn name() -> String {
+        "texture".to_string()
+    }
+    fn methods() -> Vec<String> {
+        vec!["lookup".to_string()]
+    }
+}
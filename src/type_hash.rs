@@ -1,17 +1,46 @@
-use std::{
-    hash::{Hash, Hasher},
-    any::TypeId,
-    collections::hash_map::DefaultHasher,
-};
+use std::any::type_name;
 
 /// Trait that allows to get the hash of the Rust type.
 /// The only requirements is the type shoud have a static lifetime.
+///
+/// The hash is computed from the fully-qualified type name including generic arguments,
+/// so it doesn't depend on the compiler build and the generated device code is reproducible.
 pub trait TypeHash: 'static {
     fn type_hash() -> u64 {
-        let mut hasher = DefaultHasher::new();
-        TypeId::of::<Self>().hash(&mut hasher);
-        hasher.finish()
+        stable_hash(type_name::<Self>().as_bytes())
     }
 }
 
 impl<T: 'static> TypeHash for T {}
+
+/// 64-bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/) hash
+/// that gives the same result on every platform and every build.
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        type_hash::*,
+        shape::test::TestShape,
+        material::test::TestMaterial,
+        object::Covered,
+    };
+
+    #[test]
+    fn pinned() {
+        assert_eq!(i32::type_hash(), 0x2af2b3192b419145);
+        assert_eq!(f64::type_hash(), 0xdd744c18ff7ea1ab);
+        assert_eq!(TestShape::<i32>::type_hash(), 0xa25cf3f13c62d424);
+        assert_eq!(
+            Covered::<TestShape<f32>, TestMaterial<i32>>::type_hash(),
+            0x16d758abde3324fc,
+        );
+    }
+}
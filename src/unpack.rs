@@ -0,0 +1,332 @@
+use std::array;
+use nalgebra::{
+    Scalar, RealField,
+    Vector2, Vector3, Vector4,
+    Matrix2, Matrix3, Matrix4,
+    Point2, Point3,
+    Quaternion, UnitQuaternion,
+    Translation3, Isometry3,
+};
+use crate::pack::*;
+
+pub use clay_core_derive::Unpack;
+
+
+/// Something that could be unpacked from `i32` buffers
+pub trait UnpackInt: PackInt {
+    /// Read an object from `i32` buffer.
+    ///
+    /// Buffer *must* be of size greater or equal to object's one.
+    fn unpack_int_from(buffer: &[i32]) -> Self;
+}
+
+/// Something that could be unpacked from `f32` buffers
+pub trait UnpackFloat: PackFloat {
+    /// Read an object from `f32` buffer.
+    ///
+    /// Buffer *must* be of size greater or equal to object's one.
+    fn unpack_float_from(buffer: &[f32]) -> Self;
+}
+
+/// Something that could be unpacked from a pair of `i32` and `f32` buffers.
+///
+/// It is the reverse of `Pack` and is intended for reading device data back on the host.
+pub trait Unpack: Pack {
+    /// Read an object from int and float buffers.
+    ///
+    /// Buffers *must* be of size greater or equal to object's one.
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self;
+}
+
+pub trait UnpackerInt {
+    fn unpack<T: UnpackInt>(&mut self) -> T;
+}
+impl<'a> UnpackerInt for &'a [i32] {
+    fn unpack<T: UnpackInt>(&mut self) -> T {
+        let t = T::unpack_int_from(self);
+        *self = &self[T::size()..];
+        t
+    }
+}
+
+pub trait UnpackerFloat {
+    fn unpack<T: UnpackFloat>(&mut self) -> T;
+}
+impl<'a> UnpackerFloat for &'a [f32] {
+    fn unpack<T: UnpackFloat>(&mut self) -> T {
+        let t = T::unpack_float_from(self);
+        *self = &self[T::size()..];
+        t
+    }
+}
+
+pub struct Unpacker<'a> {
+    buffer_int: &'a [i32],
+    buffer_float: &'a [f32],
+}
+impl<'a> Unpacker<'a> {
+    pub fn new(buffer_int: &'a [i32], buffer_float: &'a [f32]) -> Self {
+        Self { buffer_int, buffer_float }
+    }
+    pub fn unpack<T: Unpack>(&mut self) -> T {
+        let t = T::unpack_from(self.buffer_int, self.buffer_float);
+        self.buffer_int = &self.buffer_int[T::size_int()..];
+        self.buffer_float = &self.buffer_float[T::size_float()..];
+        t
+    }
+    /// Unpacks an object from integer buffer only.
+    pub fn unpack_int<T: UnpackInt>(&mut self) -> T {
+        self.buffer_int.unpack()
+    }
+    /// Unpacks an object from float buffer only.
+    pub fn unpack_float<T: UnpackFloat>(&mut self) -> T {
+        self.buffer_float.unpack()
+    }
+}
+
+
+impl UnpackInt for i32 {
+    fn unpack_int_from(buffer: &[i32]) -> Self {
+        buffer[0]
+    }
+}
+impl Unpack for i32 {
+    fn unpack_from(buffer_int: &[i32], _buffer_float: &[f32]) -> Self {
+        buffer_int[0]
+    }
+}
+impl UnpackInt for u32 {
+    fn unpack_int_from(buffer: &[i32]) -> Self {
+        buffer[0] as u32
+    }
+}
+impl Unpack for u32 {
+    fn unpack_from(buffer_int: &[i32], _buffer_float: &[f32]) -> Self {
+        buffer_int[0] as u32
+    }
+}
+
+impl UnpackFloat for f32 {
+    fn unpack_float_from(buffer: &[f32]) -> Self {
+        buffer[0]
+    }
+}
+impl Unpack for f32 {
+    fn unpack_from(_buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        buffer_float[0]
+    }
+}
+impl UnpackFloat for f64 {
+    fn unpack_float_from(buffer: &[f32]) -> Self {
+        buffer[0] as f64
+    }
+}
+impl Unpack for f64 {
+    fn unpack_from(_buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        buffer_float[0] as f64
+    }
+}
+
+impl UnpackInt for bool {
+    fn unpack_int_from(buffer: &[i32]) -> Self {
+        buffer[0] != 0
+    }
+}
+impl Unpack for bool {
+    fn unpack_from(buffer_int: &[i32], _buffer_float: &[f32]) -> Self {
+        buffer_int[0] != 0
+    }
+}
+
+macro_rules! impl_matrix {
+    ($( $Matrix:ident: $n:expr ),+ $(,)?) => { $(
+        impl<T: UnpackFloat + Scalar> UnpackFloat for $Matrix<T> {
+            fn unpack_float_from(mut buffer: &[f32]) -> Self {
+                Self::from_iterator((0..$n).map(|_| buffer.unpack()))
+            }
+        }
+        impl<T: Unpack + Scalar> Unpack for $Matrix<T> {
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+                let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+                Self::from_iterator((0..$n).map(|_| unpacker.unpack()))
+            }
+        }
+    )+ };
+}
+impl_matrix!(
+    Vector2: 2, Vector3: 3, Vector4: 4,
+    Matrix2: 4, Matrix3: 9, Matrix4: 16,
+);
+
+macro_rules! impl_point {
+    ($( $Point:ident: $Vector:ident ),+ $(,)?) => { $(
+        impl<T: UnpackFloat + Scalar> UnpackFloat for $Point<T> {
+            fn unpack_float_from(buffer: &[f32]) -> Self {
+                Self::from($Vector::unpack_float_from(buffer))
+            }
+        }
+        impl<T: Unpack + Scalar> Unpack for $Point<T> {
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+                Self::from($Vector::unpack_from(buffer_int, buffer_float))
+            }
+        }
+    )+ };
+}
+impl_point!(Point2: Vector2, Point3: Vector3);
+
+impl<T: UnpackFloat + RealField> UnpackFloat for UnitQuaternion<T> {
+    fn unpack_float_from(buffer: &[f32]) -> Self {
+        Self::new_unchecked(Quaternion::from(Vector4::unpack_float_from(buffer)))
+    }
+}
+impl<T: Unpack + RealField> Unpack for UnitQuaternion<T> {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        Self::new_unchecked(Quaternion::from(Vector4::unpack_from(buffer_int, buffer_float)))
+    }
+}
+
+impl<T: UnpackFloat + RealField> UnpackFloat for Isometry3<T> {
+    fn unpack_float_from(mut buffer: &[f32]) -> Self {
+        let translation = Translation3::from(buffer.unpack::<Vector3<T>>());
+        Self::from_parts(translation, buffer.unpack())
+    }
+}
+impl<T: Unpack + RealField> Unpack for Isometry3<T> {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+        let translation = Translation3::from(unpacker.unpack::<Vector3<T>>());
+        Self::from_parts(translation, unpacker.unpack())
+    }
+}
+
+impl<T: UnpackInt, const N: usize> UnpackInt for [T; N] {
+    fn unpack_int_from(mut buffer: &[i32]) -> Self {
+        array::from_fn(|_| buffer.unpack())
+    }
+}
+impl<T: UnpackFloat, const N: usize> UnpackFloat for [T; N] {
+    fn unpack_float_from(mut buffer: &[f32]) -> Self {
+        array::from_fn(|_| buffer.unpack())
+    }
+}
+impl<T: Unpack, const N: usize> Unpack for [T; N] {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+        array::from_fn(|_| unpacker.unpack())
+    }
+}
+
+macro_rules! impl_tuple {
+    ($( ( $( $T:ident ),+ ) ),+ $(,)?) => { $(
+        impl<$( $T: UnpackInt ),+> UnpackInt for ($( $T, )+) {
+            fn unpack_int_from(mut buffer: &[i32]) -> Self {
+                ($( buffer.unpack::<$T>(), )+)
+            }
+        }
+        impl<$( $T: UnpackFloat ),+> UnpackFloat for ($( $T, )+) {
+            fn unpack_float_from(mut buffer: &[f32]) -> Self {
+                ($( buffer.unpack::<$T>(), )+)
+            }
+        }
+        impl<$( $T: Unpack ),+> Unpack for ($( $T, )+) {
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+                let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+                ($( unpacker.unpack::<$T>(), )+)
+            }
+        }
+    )+ };
+}
+impl_tuple!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+);
+
+impl<T: UnpackInt> UnpackInt for Option<T> {
+    fn unpack_int_from(mut buffer: &[i32]) -> Self {
+        if buffer.unpack::<bool>() {
+            Some(buffer.unpack())
+        } else {
+            None
+        }
+    }
+}
+impl<T: UnpackFloat> UnpackFloat for Option<T> {
+    fn unpack_float_from(mut buffer: &[f32]) -> Self {
+        if buffer.unpack::<f32>() != 0.0 {
+            Some(buffer.unpack())
+        } else {
+            None
+        }
+    }
+}
+impl<T: Unpack> Unpack for Option<T> {
+    fn unpack_from(mut buffer_int: &[i32], buffer_float: &[f32]) -> Self {
+        if buffer_int.unpack::<bool>() {
+            Some(T::unpack_from(buffer_int, buffer_float))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Vector3, Vector4, Matrix3, Isometry3};
+    use crate::{
+        pack::*,
+        unpack::*,
+        shape::{Shape, test::TestShape},
+        material::{Material, test::TestMaterial},
+    };
+
+    fn round_trip<T: Unpack>(x: &T) -> T {
+        let mut buffer_int = vec![0; T::size_int()];
+        let mut buffer_float = vec![0.0; T::size_float()];
+        x.pack_to(&mut buffer_int, &mut buffer_float);
+        T::unpack_from(&buffer_int, &buffer_float)
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(round_trip(&-1i32), -1);
+        assert_eq!(round_trip(&0xdeadbeefu32), 0xdeadbeef);
+        assert_eq!(round_trip(&0.5f32), 0.5);
+        assert_eq!(round_trip(&0.25f64), 0.25);
+    }
+
+    #[test]
+    fn nalgebra() {
+        let v = Vector3::new(1.0, 2.0, 3.0);
+        assert_eq!(round_trip(&v), v);
+        let m = Matrix3::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0);
+        assert_eq!(round_trip(&m), m);
+    }
+
+    #[test]
+    fn containers() {
+        let a = [(1i32, 2.0f32), (3, 4.0)];
+        assert_eq!(round_trip(&a), a);
+        let o = (Some(Vector4::new(1.0, 2.0, 3.0, 4.0)), None::<Vector3<f64>>, true);
+        assert_eq!(round_trip(&o), o);
+        assert_eq!(<Option<Vector3<f64>> as Pack>::size_int(), 1);
+        assert_eq!(<Option<Vector3<f64>> as Pack>::size_float(), 3);
+
+        let iso = Isometry3::new(Vector3::new(1.0, 2.0, 3.0), Vector3::new(0.0, 0.5, 0.0));
+        assert!((round_trip(&iso).to_homogeneous() - iso.to_homogeneous()).norm() < 1e-6);
+    }
+
+    #[test]
+    fn covered() {
+        let color = Vector3::new(0.5, 0.25, 0.125);
+        let obj = TestShape::<i32>::new().cover(
+            TestMaterial::<i32>::new().color_with(color),
+        );
+        assert_eq!(round_trip(&obj).material.color, color);
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+use nalgebra::{Vector3, Matrix3};
+use ocl::{self, prm, builders::KernelBuilder};
+use crate::{
+    prelude::*,
+    Context,
+    view::View,
+};
+
+
+/// Projection of the camera.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Projection {
+    /// Perspective projection with the specified vertical field of view in radians.
+    Perspective { yfov: f64 },
+    /// Orthographic projection with the specified half of the vertical size.
+    Orthographic { ymag: f64 },
+}
+
+/// Camera placed at some point with some orientation.
+///
+/// The camera looks along `-z` axis of its orientation with `y` axis pointing up.
+/// The horizontal size of the view is defined by the aspect ratio of the image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Camera {
+    pub pos: Vector3<f64>,
+    /// Rotation matrix which columns are camera axes.
+    pub ori: Matrix3<f64>,
+    pub projection: Projection,
+}
+
+impl Camera {
+    pub fn new(pos: Vector3<f64>, ori: Matrix3<f64>, projection: Projection) -> Self {
+        Self { pos, ori, projection }
+    }
+
+    fn mode_scale(&self) -> (i32, f64) {
+        match self.projection {
+            Projection::Perspective { yfov } => (0, (0.5*yfov).tan()),
+            Projection::Orthographic { ymag } => (1, ymag),
+        }
+    }
+}
+
+fn prm_vector(v: Vector3<f64>) -> prm::Float3 {
+    prm::Float3::new(v.x as f32, v.y as f32, v.z as f32)
+}
+
+impl View for Camera {
+    fn source(_: &mut HashSet<u64>) -> String {
+        "#include <clay_core/view/camera.h>".to_string()
+    }
+}
+
+impl Store for Camera {
+    type Data = CameraData;
+
+    fn create_data(&self, context: &Context) -> crate::Result<Self::Data> {
+        let mut data = CameraData {
+            pos: prm::Float3::zero(),
+            axes: [prm::Float3::zero(); 3],
+            mode: 0, scale: 0.0,
+        };
+        self.update_data(context, &mut data)?;
+        Ok(data)
+    }
+
+    fn update_data(&self, _context: &Context, data: &mut Self::Data) -> crate::Result<()> {
+        let (mode, scale) = self.mode_scale();
+        data.pos = prm_vector(self.pos);
+        for i in 0..3 {
+            data.axes[i] = prm_vector(self.ori.column(i).into_owned());
+        }
+        data.mode = mode;
+        data.scale = scale as f32;
+        Ok(())
+    }
+}
+
+/// Device data of the camera.
+pub struct CameraData {
+    pos: prm::Float3,
+    axes: [prm::Float3; 3],
+    mode: i32,
+    scale: f32,
+}
+
+impl Push for CameraData {
+    fn args_count() -> usize {
+        6
+    }
+    fn args_def(kb: &mut KernelBuilder) {
+        kb
+        .arg(prm::Float3::zero()) // position
+        .arg(prm::Float3::zero()) // x axis
+        .arg(prm::Float3::zero()) // y axis
+        .arg(prm::Float3::zero()) // z axis
+        .arg(0i32) // projection mode
+        .arg(0f32); // projection scale
+    }
+    fn args_set(&mut self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        k.set_arg(i + 0, &self.pos)?;
+        for j in 0..3 {
+            k.set_arg(i + 1 + j, &self.axes[j])?;
+        }
+        k.set_arg(i + 4, self.mode)?;
+        k.set_arg(i + 5, self.scale)?;
+        Ok(())
+    }
+}
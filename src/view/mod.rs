@@ -1,2 +1,4 @@
 mod view;
 pub use view::View;
+mod camera;
+pub use camera::*;